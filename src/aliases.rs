@@ -0,0 +1,205 @@
+//! Aliases
+//!
+//! Shorthands for the most common configuration, byte words with `u32`
+//! addresses.
+//!
+//! The traits are implemented for every type implementing the generic traits
+//! they combine, so drivers only implement the generic traits and generic
+//! code can use the shorter bounds.
+
+use crate::helpers::len_from_offset;
+use crate::storage::{Address, AddressOffset, ErasePage, MultiRead, MultiWrite, Page, StorageSize};
+use core::convert::TryFrom;
+
+/// An [`Address`] with `u32` addresses
+pub type Address32 = Address<u32>;
+
+/// An [`AddressOffset`] with `u32` addresses
+pub type AddressOffset32 = AddressOffset<u32>;
+
+/// A [`Page`] with `u32` page numbers
+pub type Page32 = Page<u32>;
+
+/// [`MultiRead`] of bytes with `u32` addresses
+pub trait ByteRead: MultiRead<u8, u32> {}
+
+impl<T: MultiRead<u8, u32> + ?Sized> ByteRead for T {}
+
+/// [`MultiWrite`] of bytes with `u32` addresses
+pub trait ByteWrite: MultiWrite<u8, u32> {}
+
+impl<T: MultiWrite<u8, u32> + ?Sized> ByteWrite for T {}
+
+/// Reading, writing, erasing and sizing a byte device with `u32` addresses,
+/// all with the same error.
+///
+/// The error is named `FlashError` so it can be used as `T::FlashError`
+/// without clashing with the `Error` of the supertraits.
+///
+/// ```
+/// use embedded_storage::aliases::{Address32, ByteFlash};
+///
+/// fn erase_and_write<T: ByteFlash>(dev: &mut T, data: &mut [u8]) -> nb::Result<(), T::FlashError> {
+///     let start = dev.try_start_address()?;
+///     dev.try_erase_address(start)?;
+///     dev.try_write_slice(start, data)
+/// }
+///
+/// # use embedded_storage::storage::{
+/// #     Address, AddressOffset, ErasePage, MultiRead, MultiWrite, Page, StorageSize,
+/// # };
+/// #
+/// # struct Flash([u8; 16]);
+/// #
+/// # impl MultiRead<u8, u32> for Flash {
+/// #     type Error = ();
+/// #     fn try_read_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), ()> {
+/// #         let start = address.0 as usize;
+/// #         buf.copy_from_slice(&self.0[start..start + buf.len()]);
+/// #         Ok(())
+/// #     }
+/// # }
+/// #
+/// # impl MultiWrite<u8, u32> for Flash {
+/// #     type Error = ();
+/// #     fn try_write_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), ()> {
+/// #         let start = address.0 as usize;
+/// #         self.0[start..start + buf.len()].copy_from_slice(buf);
+/// #         Ok(())
+/// #     }
+/// # }
+/// #
+/// # impl ErasePage<u32> for Flash {
+/// #     type Error = ();
+/// #     fn try_erase_page(&mut self, _page: Page<u32>) -> nb::Result<(), ()> {
+/// #         self.0 = [0xFF; 16];
+/// #         Ok(())
+/// #     }
+/// #     fn try_erase_address(&mut self, _address: Address<u32>) -> nb::Result<(), ()> {
+/// #         self.try_erase_page(Page(0))
+/// #     }
+/// # }
+/// #
+/// # impl StorageSize<u8, u32> for Flash {
+/// #     type Error = ();
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<u32>, ()> {
+/// #         Ok(Address(0))
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<u32>, ()> {
+/// #         Ok(AddressOffset(16))
+/// #     }
+/// #     fn try_page_size(&mut self, _address: Address<u32>) -> nb::Result<AddressOffset<u32>, ()> {
+/// #         Ok(AddressOffset(16))
+/// #     }
+/// # }
+/// #
+/// let mut flash = Flash([0; 16]);
+/// erase_and_write(&mut flash, &mut [1, 2]).unwrap();
+/// assert_eq!(flash.0[..3], [1, 2, 0xFF]);
+///
+/// let mut buf = [0u8; 2];
+/// let address: Address32 = Address(0);
+/// flash.try_read_slice(address, &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2]);
+/// ```
+pub trait ByteFlash:
+	MultiRead<u8, u32, Error = <Self as ByteFlash>::FlashError>
+	+ MultiWrite<u8, u32, Error = <Self as ByteFlash>::FlashError>
+	+ ErasePage<u32, Error = <Self as ByteFlash>::FlashError>
+	+ StorageSize<u8, u32, Error = <Self as ByteFlash>::FlashError>
+{
+	/// The error of all supertraits
+	type FlashError;
+}
+
+impl<T, E> ByteFlash for T
+where
+	T: MultiRead<u8, u32, Error = E>
+		+ MultiWrite<u8, u32, Error = E>
+		+ ErasePage<u32, Error = E>
+		+ StorageSize<u8, u32, Error = E>
+		+ ?Sized,
+{
+	type FlashError = E;
+}
+
+/// A blocking byte interface to any byte device, for application code that
+/// doesn't care about words and `nb`.
+///
+/// Every method blocks until the device completes the operation. Lengths
+/// and sizes are `usize`. Drivers implement the generic traits, which this
+/// trait is implemented for whenever the word is `u8`.
+///
+/// ```
+/// use embedded_storage::aliases::ByteStorage;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::Address;
+///
+/// fn save_name<T: ByteStorage<usize>>(dev: &mut T, name: &str) -> Result<(), T::ByteError> {
+///     let mut buf = [0u8; 16];
+///     buf[0] = name.len() as u8;
+///     buf[1..=name.len()].copy_from_slice(name.as_bytes());
+///     dev.erase(Address(0))?;
+///     dev.write_bytes(Address(0), &mut buf[..=name.len()])
+/// }
+///
+/// let mut storage = MemStorage::<64>::new(16);
+/// assert_eq!(storage.size(), Ok(64));
+/// save_name(&mut storage, "sensor-7").unwrap();
+///
+/// let mut len = [0u8; 1];
+/// storage.read_bytes(Address(0), &mut len).unwrap();
+/// let mut name = [0u8; 15];
+/// let name = &mut name[..usize::from(len[0])];
+/// storage.read_bytes(Address(1), name).unwrap();
+/// assert_eq!(name, b"sensor-7");
+/// ```
+pub trait ByteStorage<U> {
+	/// The error of the device
+	type ByteError;
+
+	/// Read `buf.len()` bytes starting at the address
+	fn read_bytes(&mut self, address: Address<U>, buf: &mut [u8]) -> Result<(), Self::ByteError>;
+
+	/// Write `buf` starting at the address
+	fn write_bytes(&mut self, address: Address<U>, buf: &mut [u8]) -> Result<(), Self::ByteError>;
+
+	/// Erase the page starting at the address.
+	///
+	/// The address must be the start of a page, as for
+	/// [`ErasePage::try_erase_address`].
+	fn erase(&mut self, address: Address<U>) -> Result<(), Self::ByteError>;
+
+	/// The size of the device in bytes, saturating at `usize::MAX`
+	fn size(&mut self) -> Result<usize, Self::ByteError>;
+}
+
+impl<T, U, E> ByteStorage<U> for T
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>
+		+ ?Sized,
+	U: Copy,
+	usize: TryFrom<U>,
+{
+	type ByteError = E;
+
+	fn read_bytes(&mut self, address: Address<U>, buf: &mut [u8]) -> Result<(), E> {
+		nb::block!(self.try_read_slice(address, buf))
+	}
+
+	fn write_bytes(&mut self, address: Address<U>, buf: &mut [u8]) -> Result<(), E> {
+		nb::block!(self.try_write_slice(address, buf))
+	}
+
+	fn erase(&mut self, address: Address<U>) -> Result<(), E> {
+		nb::block!(self.try_erase_address(address))
+	}
+
+	fn size(&mut self) -> Result<usize, E> {
+		let size = nb::block!(self.try_total_size())?;
+		Ok(len_from_offset(size).unwrap_or(usize::MAX))
+	}
+}
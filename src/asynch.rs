@@ -0,0 +1,212 @@
+//! Async
+//!
+//! `async` versions of the storage traits, enabled by the `async` feature.
+//!
+//! The methods mirror the [`blocking`](crate::blocking) traits, returning a
+//! future that resolves to a `Result` instead of an `nb::Result`. The
+//! [`NbAsync`] wrapper implements them for any `nb` based implementation.
+//!
+//! This module requires Rust 1.75 or newer, above the MSRV of the crate.
+
+#![allow(clippy::incompatible_msrv)]
+
+use crate::storage::{Address, ErasePage, MultiRead, MultiWrite, Page, SingleRead, SingleWrite};
+use core::future::{poll_fn, Future};
+use core::task::{Context, Poll};
+
+/// Async version of [`SingleRead`].
+pub trait AsyncSingleRead<Word, U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Reads the word stored at the address
+	fn read(&mut self, address: Address<U>) -> impl Future<Output = Result<Word, Self::Error>>;
+}
+
+/// Async version of [`SingleWrite`].
+pub trait AsyncSingleWrite<Word, U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Writes the word to the address
+	fn write(
+		&mut self,
+		address: Address<U>,
+		word: Word,
+	) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+/// Async version of [`MultiRead`].
+pub trait AsyncMultiRead<Word, U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Reads the words stored at the address to fill the buffer
+	fn read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+/// Async version of [`MultiWrite`].
+pub trait AsyncMultiWrite<Word, U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Writes the buffer to the address.
+	fn write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+/// Async version of [`ErasePage`].
+pub trait AsyncErasePage<U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Erase the page of memory
+	fn erase_page(&mut self, page: Page<U>) -> impl Future<Output = Result<(), Self::Error>>;
+
+	/// Erase the page of memory at the address.
+	fn erase_address(
+		&mut self,
+		address: Address<U>,
+	) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+/// Wrapper that implements the async traits for any `nb` based implementation.
+///
+/// Every poll calls the wrapped `nb` method once. On `WouldBlock` the task is
+/// woken again immediately and the future yields.
+///
+/// ```
+/// use core::future::Future;
+/// use core::pin::pin;
+/// use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+/// use embedded_storage::asynch::{AsyncMultiRead, AsyncMultiWrite, NbAsync};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::Address;
+///
+/// // A waker doing nothing, as `block_on` polls in a loop anyway
+/// fn noop_raw_waker() -> RawWaker {
+///     fn clone(_: *const ()) -> RawWaker {
+///         noop_raw_waker()
+///     }
+///     fn noop(_: *const ()) {}
+///     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+///     RawWaker::new(core::ptr::null(), &VTABLE)
+/// }
+///
+/// fn block_on<F: Future>(future: F) -> F::Output {
+///     let mut future = pin!(future);
+///     let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+///     let mut cx = Context::from_waker(&waker);
+///     loop {
+///         if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+///             return output;
+///         }
+///     }
+/// }
+///
+/// let mut storage = NbAsync(MemStorage::<16>::new(16));
+/// block_on(storage.write_slice(Address(4), &mut [1, 2, 3])).unwrap();
+///
+/// let mut buf = [0u8; 3];
+/// block_on(storage.read_slice(Address(4), &mut buf)).unwrap();
+/// assert_eq!(buf, [1, 2, 3]);
+/// ```
+pub struct NbAsync<T>(pub T);
+
+impl<T> NbAsync<T> {
+	/// Release the wrapped implementation
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+fn poll_nb<T, E>(result: nb::Result<T, E>, cx: &mut Context<'_>) -> Poll<Result<T, E>> {
+	match result {
+		Ok(value) => Poll::Ready(Ok(value)),
+		Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+		Err(nb::Error::WouldBlock) => {
+			cx.waker().wake_by_ref();
+			Poll::Pending
+		}
+	}
+}
+
+impl<T, Word, U> AsyncSingleRead<Word, U> for NbAsync<T>
+where
+	T: SingleRead<Word, U>,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	async fn read(&mut self, address: Address<U>) -> Result<Word, Self::Error> {
+		poll_fn(|cx| poll_nb(self.0.try_read(address), cx)).await
+	}
+}
+
+impl<T, Word, U> AsyncSingleWrite<Word, U> for NbAsync<T>
+where
+	T: SingleWrite<Word, U>,
+	Word: Copy,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	async fn write(&mut self, address: Address<U>, word: Word) -> Result<(), Self::Error> {
+		poll_fn(|cx| poll_nb(self.0.try_write(address, word), cx)).await
+	}
+}
+
+impl<T, Word, U> AsyncMultiRead<Word, U> for NbAsync<T>
+where
+	T: MultiRead<Word, U>,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	async fn read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> Result<(), Self::Error> {
+		poll_fn(|cx| poll_nb(self.0.try_read_slice(address, buf), cx)).await
+	}
+}
+
+impl<T, Word, U> AsyncMultiWrite<Word, U> for NbAsync<T>
+where
+	T: MultiWrite<Word, U>,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	async fn write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> Result<(), Self::Error> {
+		poll_fn(|cx| poll_nb(self.0.try_write_slice(address, buf), cx)).await
+	}
+}
+
+impl<T, U> AsyncErasePage<U> for NbAsync<T>
+where
+	T: ErasePage<U>,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	async fn erase_page(&mut self, page: Page<U>) -> Result<(), Self::Error> {
+		poll_fn(|cx| poll_nb(self.0.try_erase_page(page), cx)).await
+	}
+
+	async fn erase_address(&mut self, address: Address<U>) -> Result<(), Self::Error> {
+		poll_fn(|cx| poll_nb(self.0.try_erase_address(address), cx)).await
+	}
+}
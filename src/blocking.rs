@@ -0,0 +1,191 @@
+//! Blocking
+//!
+//! Blocking counterparts of the `nb` based storage traits.
+//!
+//! The [`Blocking`] wrapper turns any implementation of the traits in
+//! [`storage`](crate::storage) into plain blocking calls by spinning on
+//! `nb::block!`. The `Error` type of the wrapped implementation is forwarded
+//! unchanged.
+
+use crate::storage::{
+	AccessPattern, Address, ErasePage, MultiRead, MultiWrite, Page, ReadAccess, SingleRead,
+	SingleWrite,
+};
+
+/// Wrapper that implements the blocking traits for any `nb` based implementation.
+///
+/// When the wrapped implementation never returns `WouldBlock` the spin loop is
+/// never entered.
+///
+/// ```
+/// use embedded_storage::blocking::{Blocking, BlockingRead};
+/// use embedded_storage::storage::{Address, SingleRead};
+///
+/// struct Device {
+///     busy: u8,
+/// }
+///
+/// impl SingleRead<u8, u32> for Device {
+///     type Error = ();
+///
+///     fn try_read(&mut self, address: Address<u32>) -> nb::Result<u8, Self::Error> {
+///         if self.busy > 0 {
+///             self.busy -= 1;
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         Ok(address.0 as u8)
+///     }
+/// }
+///
+/// let mut device = Blocking(Device { busy: 3 });
+/// assert_eq!(device.read(Address(0x42)), Ok(0x42));
+/// ```
+pub struct Blocking<T>(pub T);
+
+impl<T> Blocking<T> {
+	/// Release the wrapped implementation
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+/// Blocking version of [`SingleRead`].
+pub trait BlockingRead<Word, U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Reads the word stored at the address
+	fn read(&mut self, address: Address<U>) -> Result<Word, Self::Error>;
+}
+
+/// Blocking version of [`SingleWrite`].
+pub trait BlockingWrite<Word, U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Writes the word to the address
+	fn write(&mut self, address: Address<U>, word: Word) -> Result<(), Self::Error>;
+}
+
+/// Blocking version of [`MultiRead`].
+pub trait BlockingMultiRead<Word, U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Reads the words stored at the address to fill the buffer
+	fn read_slice(&mut self, address: Address<U>, buf: &mut [Word]) -> Result<(), Self::Error>;
+
+	/// Reads the words stored at the address into the start of the buffer,
+	/// returning how many were read, see [`MultiRead::try_read_partial`]
+	fn read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> Result<usize, Self::Error> {
+		self.read_slice(address, buf)?;
+		Ok(buf.len())
+	}
+}
+
+/// Blocking version of [`MultiWrite`].
+pub trait BlockingMultiWrite<Word, U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Writes the buffer to the address.
+	fn write_slice(&mut self, address: Address<U>, buf: &mut [Word]) -> Result<(), Self::Error>;
+}
+
+/// Blocking version of [`ErasePage`].
+pub trait BlockingErasePage<U> {
+	/// An enumeration of Storage errors
+	type Error;
+
+	/// Erase the page of memory
+	fn erase_page(&mut self, page: Page<U>) -> Result<(), Self::Error>;
+
+	/// Erase the page of memory at the address.
+	fn erase_address(&mut self, address: Address<U>) -> Result<(), Self::Error>;
+}
+
+impl<T, Word, U> BlockingRead<Word, U> for Blocking<T>
+where
+	T: SingleRead<Word, U>,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	fn read(&mut self, address: Address<U>) -> Result<Word, Self::Error> {
+		nb::block!(self.0.try_read(address))
+	}
+}
+
+impl<T, Word, U> BlockingWrite<Word, U> for Blocking<T>
+where
+	T: SingleWrite<Word, U>,
+	Word: Copy,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	fn write(&mut self, address: Address<U>, word: Word) -> Result<(), Self::Error> {
+		nb::block!(self.0.try_write(address, word))
+	}
+}
+
+impl<T, Word, U> BlockingMultiRead<Word, U> for Blocking<T>
+where
+	T: MultiRead<Word, U>,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	fn read_slice(&mut self, address: Address<U>, buf: &mut [Word]) -> Result<(), Self::Error> {
+		nb::block!(self.0.try_read_slice(address, buf))
+	}
+
+	fn read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> Result<usize, Self::Error> {
+		nb::block!(self.0.try_read_partial(address, buf))
+	}
+}
+
+impl<T, Word, U> BlockingMultiWrite<Word, U> for Blocking<T>
+where
+	T: MultiWrite<Word, U>,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	fn write_slice(&mut self, address: Address<U>, buf: &mut [Word]) -> Result<(), Self::Error> {
+		nb::block!(self.0.try_write_slice(address, buf))
+	}
+}
+
+impl<T, U> BlockingErasePage<U> for Blocking<T>
+where
+	T: ErasePage<U>,
+	U: Copy,
+{
+	type Error = T::Error;
+
+	fn erase_page(&mut self, page: Page<U>) -> Result<(), Self::Error> {
+		nb::block!(self.0.try_erase_page(page))
+	}
+
+	fn erase_address(&mut self, address: Address<U>) -> Result<(), Self::Error> {
+		nb::block!(self.0.try_erase_address(address))
+	}
+}
+
+impl<T> ReadAccess for Blocking<T>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.0.access_pattern()
+	}
+}
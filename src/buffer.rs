@@ -0,0 +1,119 @@
+//! Buffer
+//!
+//! Owned, fixed capacity buffers for transferring words to and from a device.
+
+use crate::storage::StorageWord;
+
+/// A stack allocated buffer of up to `N` words, typically the page size of a device.
+///
+/// The buffer tracks how many of its words are valid, and
+/// [`as_slice`](PageBuffer::as_slice) and
+/// [`as_mut_slice`](PageBuffer::as_mut_slice) cover only those, so they can
+/// be passed straight to [`try_read_slice`](crate::storage::MultiRead::try_read_slice)
+/// and [`try_write_slice`](crate::storage::MultiWrite::try_write_slice).
+///
+/// The default buffer holds `N` erased words of `0xFF` bytes, ready for
+/// patching and writing back a page of NOR flash.
+///
+/// ```
+/// use embedded_storage::buffer::PageBuffer;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+///
+/// let mut storage = MemStorage::<32>::new(16);
+///
+/// let mut buf = PageBuffer::<u8, 16>::default();
+/// buf.as_mut_slice()[..3].copy_from_slice(&[1, 2, 3]);
+/// storage.try_write_slice(Address(16), buf.as_mut_slice()).unwrap();
+///
+/// let mut read = PageBuffer::<u8, 16>::new(0);
+/// read.set_len(4);
+/// storage.try_read_slice(Address(16), read.as_mut_slice()).unwrap();
+/// assert_eq!(read.as_slice(), [1, 2, 3, 0xFF]);
+/// assert_eq!(read.len(), 4);
+/// assert_eq!(PageBuffer::<u16, 2>::default().as_slice(), [0xFFFF, 0xFFFF]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageBuffer<Word, const N: usize> {
+	words: [Word; N],
+	len: usize,
+}
+
+impl<Word: Copy, const N: usize> PageBuffer<Word, N> {
+	/// A full buffer of `N` copies of `fill`
+	pub fn new(fill: Word) -> Self {
+		PageBuffer {
+			words: [fill; N],
+			len: N,
+		}
+	}
+
+	/// The number of valid words
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Whether no words are valid
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// The maximum number of words, `N`
+	pub fn capacity(&self) -> usize {
+		N
+	}
+
+	/// Change the number of valid words.
+	///
+	/// Words beyond the previous length keep their last value.
+	///
+	/// Panics if `len` is greater than `N`.
+	pub fn set_len(&mut self, len: usize) {
+		assert!(len <= N, "length exceeds the capacity of the buffer");
+		self.len = len;
+	}
+
+	/// Set every word of the buffer to `word`, without changing its length
+	pub fn fill(&mut self, word: Word) {
+		self.words = [word; N];
+	}
+
+	/// The valid words
+	pub fn as_slice(&self) -> &[Word] {
+		&self.words[..self.len]
+	}
+
+	/// The valid words, for reading into or writing from
+	pub fn as_mut_slice(&mut self) -> &mut [Word] {
+		&mut self.words[..self.len]
+	}
+}
+
+impl<Word: StorageWord, const N: usize> PageBuffer<Word, N> {
+	/// A full buffer of words made of `erase_byte`
+	pub fn erased(erase_byte: u8) -> Self {
+		let mut bytes = Word::Bytes::default();
+		for byte in bytes.as_mut() {
+			*byte = erase_byte;
+		}
+		PageBuffer::new(Word::from_le_bytes(bytes))
+	}
+}
+
+impl<Word: StorageWord, const N: usize> Default for PageBuffer<Word, N> {
+	fn default() -> Self {
+		PageBuffer::erased(0xFF)
+	}
+}
+
+impl<Word: Copy, const N: usize> AsRef<[Word]> for PageBuffer<Word, N> {
+	fn as_ref(&self) -> &[Word] {
+		self.as_slice()
+	}
+}
+
+impl<Word: Copy, const N: usize> AsMut<[Word]> for PageBuffer<Word, N> {
+	fn as_mut(&mut self) -> &mut [Word] {
+		self.as_mut_slice()
+	}
+}
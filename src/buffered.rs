@@ -0,0 +1,163 @@
+//! Buffered
+//!
+//! Serve many small sequential reads from a prefetched chunk in RAM.
+
+use crate::helpers::{len_from_offset, offset_from_len, Error};
+use crate::storage::{Address, AddressOffset, MultiRead, StorageSize};
+use core::convert::TryFrom;
+use core::ops::{Add, Sub};
+
+/// Sequential reader prefetching `buf.len()` words at a time.
+///
+/// Every `read` is served from `buf`, refilling it from the current position
+/// when it runs out. Reads at least as long as `buf` bypass it. The prefetch
+/// never reads beyond the end of the device, and reading past the end
+/// returns [`Error::OutOfBounds`]. The operations block on the device, and
+/// never return `WouldBlock`.
+///
+/// ```
+/// use embedded_storage::buffered::BufReader;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, MultiRead, MultiWrite, StorageSize};
+///
+/// // Counts the reads of a MemStorage
+/// struct Device {
+///     mem: MemStorage<64>,
+///     reads: usize,
+/// }
+///
+/// impl MultiRead<u8, usize> for Device {
+///     type Error = embedded_storage::mem::Error;
+///
+///     fn try_read_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         self.reads += 1;
+///         self.mem.try_read_slice(address, buf)
+///     }
+/// }
+/// # impl StorageSize<u8, usize> for Device {
+/// #     type Error = embedded_storage::mem::Error;
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<usize>, Self::Error> {
+/// #         self.mem.try_start_address()
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.mem.try_total_size()
+/// #     }
+/// #     fn try_page_size(&mut self, address: Address<usize>) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.mem.try_page_size(address)
+/// #     }
+/// # }
+///
+/// let mut mem = MemStorage::<64>::new(64);
+/// let mut data = [0u8; 64];
+/// for (i, byte) in data.iter_mut().enumerate() {
+///     *byte = i as u8;
+/// }
+/// mem.try_write_slice(Address(0), &mut data).unwrap();
+///
+/// let mut buf = [0u8; 16];
+/// let mut reader = BufReader::new(Device { mem, reads: 0 }, &mut buf).unwrap();
+/// let mut word = [0u8; 2];
+/// for i in 0..32 {
+///     reader.read(&mut word).unwrap();
+///     assert_eq!(word, [2 * i as u8, 2 * i as u8 + 1]);
+/// }
+/// assert_eq!(reader.get_ref().reads, 4);
+///
+/// reader.seek(Address(62));
+/// reader.read(&mut word).unwrap();
+/// assert_eq!(word, [62, 63]);
+/// assert!(reader.read(&mut word).is_err());
+/// ```
+pub struct BufReader<'a, T, Word, U> {
+	dev: T,
+	buf: &'a mut [Word],
+	position: Address<U>,
+	end: Address<U>,
+	pos: usize,
+	filled: usize,
+}
+
+impl<'a, T, Word, U, E> BufReader<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>,
+	Word: Copy,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Wrap `dev`, reading from its start address and prefetching into `buf`
+	pub fn new(mut dev: T, buf: &'a mut [Word]) -> Result<Self, E>
+	where
+		T: StorageSize<Word, U, Error = E>,
+	{
+		let start = nb::block!(dev.try_start_address())?;
+		let size = nb::block!(dev.try_total_size())?;
+		Ok(BufReader {
+			dev,
+			buf,
+			position: start,
+			end: &start + &size,
+			pos: 0,
+			filled: 0,
+		})
+	}
+
+	/// The address of the next word returned by `read`
+	pub fn position(&self) -> Address<U> {
+		self.position
+	}
+
+	/// Move to `position`, discarding the prefetched words
+	pub fn seek(&mut self, position: Address<U>) {
+		self.position = position;
+		self.pos = 0;
+		self.filled = 0;
+	}
+
+	/// A reference to the device
+	pub fn get_ref(&self) -> &T {
+		&self.dev
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+
+	/// Read words from the current position to fill `out`
+	pub fn read(&mut self, out: &mut [Word]) -> Result<(), Error<E>> {
+		let left = if self.position <= self.end {
+			len_from_offset(AddressOffset(self.end.0 - self.position.0)).unwrap_or(usize::MAX)
+		} else {
+			0
+		};
+		if out.len() > left {
+			return Err(Error::OutOfBounds);
+		}
+		let mut done = 0;
+		while done < out.len() {
+			if self.pos == self.filled {
+				let rest = &mut out[done..];
+				if rest.len() >= self.buf.len() {
+					nb::block!(self.dev.try_read_slice(self.position, rest))?;
+					self.advance(rest.len())?;
+					return Ok(());
+				}
+				let len = self.buf.len().min(left - done);
+				nb::block!(self.dev.try_read_slice(self.position, &mut self.buf[..len]))?;
+				self.pos = 0;
+				self.filled = len;
+			}
+			let len = (self.filled - self.pos).min(out.len() - done);
+			out[done..done + len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+			self.pos += len;
+			done += len;
+			self.advance(len)?;
+		}
+		Ok(())
+	}
+
+	fn advance(&mut self, len: usize) -> Result<(), Error<E>> {
+		self.position += offset_from_len(len).ok_or(Error::Overflow)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,408 @@
+//! Cache
+//!
+//! Buffer writes to a page in RAM, erasing and programming it once.
+
+use crate::helpers::{len_from_offset, offset_from_len, Error};
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, Flush, MultiRead, MultiWrite, ReadAccess,
+	StorageSize,
+};
+use core::convert::TryFrom;
+use core::ops::{Add, Rem, Sub};
+
+/// Write back cache holding one page of a device.
+///
+/// Writes are applied to a copy of their page in `scratch`, which must be at
+/// least as long as the largest page. The cached page is written back, by
+/// erasing it and programming the whole page, when a write touches another
+/// page or [`flush`](CachedPage::flush) is called. Writes in the cache replace
+/// the previous contents, as in RAM, rather than clearing bits.
+///
+/// Reads see the pending writes. Reads of other pages go straight to the
+/// device without changing the cached page. The operations block on the
+/// device, and never return `WouldBlock`.
+///
+/// Pages are located relative to the start address, assuming all pages up to
+/// an address are the size of the page containing it.
+///
+/// ```
+/// use embedded_storage::cache::CachedPage;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{
+///     Address, AddressOffset, DurableWrites, ErasePage, Flush, MultiRead, MultiWrite, Page, StorageSize,
+/// };
+///
+/// // Counts the erases of a MemStorage
+/// struct Device {
+///     mem: MemStorage<32>,
+///     erases: usize,
+/// }
+///
+/// impl ErasePage<usize> for Device {
+///     type Error = embedded_storage::mem::Error;
+///
+///     fn try_erase_page(&mut self, page: Page<usize>) -> nb::Result<(), Self::Error> {
+///         self.erases += 1;
+///         self.mem.try_erase_page(page)
+///     }
+///
+///     fn try_erase_address(&mut self, address: Address<usize>) -> nb::Result<(), Self::Error> {
+///         self.erases += 1;
+///         self.mem.try_erase_address(address)
+///     }
+/// }
+/// # impl MultiRead<u8, usize> for Device {
+/// #     type Error = embedded_storage::mem::Error;
+/// #     fn try_read_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+/// #         self.mem.try_read_slice(address, buf)
+/// #     }
+/// # }
+/// # impl MultiWrite<u8, usize> for Device {
+/// #     type Error = embedded_storage::mem::Error;
+/// #     fn try_write_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+/// #         self.mem.try_write_slice(address, buf)
+/// #     }
+/// # }
+/// # impl DurableWrites for Device {
+/// #     type Error = embedded_storage::mem::Error;
+/// # }
+/// # impl StorageSize<u8, usize> for Device {
+/// #     type Error = embedded_storage::mem::Error;
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<usize>, Self::Error> {
+/// #         self.mem.try_start_address()
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.mem.try_total_size()
+/// #     }
+/// #     fn try_page_size(&mut self, address: Address<usize>) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.mem.try_page_size(address)
+/// #     }
+/// # }
+///
+/// let device = Device { mem: MemStorage::new(16), erases: 0 };
+/// let mut scratch = [0u8; 16];
+/// let mut cache = CachedPage::new(device, &mut scratch).unwrap();
+///
+/// cache.try_write_slice(Address(17), &mut [1]).unwrap();
+/// cache.try_write_slice(Address(18), &mut [2, 3]).unwrap();
+/// let mut buf = [0u8; 4];
+/// cache.try_read_slice(Address(16), &mut buf).unwrap();
+/// assert_eq!(buf, [0xFF, 1, 2, 3]);
+///
+/// // Writes the page back and flushes the device
+/// cache.try_flush().unwrap();
+/// let device = cache.into_inner();
+/// assert_eq!(device.erases, 1);
+/// assert_eq!(device.mem.as_slice()[16..20], [0xFF, 1, 2, 3]);
+/// ```
+///
+/// If loading the next page fails, no page is cached until a later write
+/// loads one:
+///
+/// ```
+/// use embedded_storage::cache::CachedPage;
+/// use embedded_storage::mem::{Error, MemStorage};
+/// use embedded_storage::storage::{
+///     Address, AddressOffset, ErasePage, MultiRead, MultiWrite, Page, StorageSize,
+/// };
+///
+/// // Fails reading the second page once, after transferring part of it
+/// struct Device {
+///     mem: MemStorage<32>,
+///     fail: bool,
+/// }
+///
+/// impl MultiRead<u8, usize> for Device {
+///     type Error = Error;
+///
+///     fn try_read_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         if address.0 == 16 && self.fail {
+///             self.fail = false;
+///             buf[..4].fill(0);
+///             return Err(nb::Error::Other(Error::OutOfBounds));
+///         }
+///         self.mem.try_read_slice(address, buf)
+///     }
+/// }
+/// # impl MultiWrite<u8, usize> for Device {
+/// #     type Error = Error;
+/// #     fn try_write_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+/// #         self.mem.try_write_slice(address, buf)
+/// #     }
+/// # }
+/// # impl ErasePage<usize> for Device {
+/// #     type Error = Error;
+/// #     fn try_erase_page(&mut self, page: Page<usize>) -> nb::Result<(), Self::Error> {
+/// #         self.mem.try_erase_page(page)
+/// #     }
+/// #     fn try_erase_address(&mut self, address: Address<usize>) -> nb::Result<(), Self::Error> {
+/// #         self.mem.try_erase_address(address)
+/// #     }
+/// # }
+/// # impl StorageSize<u8, usize> for Device {
+/// #     type Error = Error;
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<usize>, Self::Error> {
+/// #         self.mem.try_start_address()
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.mem.try_total_size()
+/// #     }
+/// #     fn try_page_size(&mut self, address: Address<usize>) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.mem.try_page_size(address)
+/// #     }
+/// # }
+///
+/// let mut scratch = [0u8; 16];
+/// let device = Device { mem: MemStorage::new(16), fail: true };
+/// let mut cache = CachedPage::new(device, &mut scratch).unwrap();
+/// cache.try_write_slice(Address(0), &mut [1, 2, 3, 4]).unwrap();
+/// assert!(cache.try_write_slice(Address(16), &mut [5]).is_err());
+///
+/// // The first page was written back, and isn't mixed with the failed read
+/// let mut buf = [0u8; 4];
+/// cache.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2, 3, 4]);
+/// cache.try_write_slice(Address(4), &mut [6]).unwrap();
+/// cache.flush().unwrap();
+/// assert_eq!(cache.into_inner().mem.as_slice()[..6], [1, 2, 3, 4, 6, 0xFF]);
+/// ```
+///
+/// A device returning `WouldBlock` is waited on, while the device itself
+/// needs [`test_util`](crate::test_util) or `nb::block!`:
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use embedded_storage::cache::CachedPage;
+/// use embedded_storage::mem::MemStorageBuilder;
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+/// use embedded_storage::test_util::{block_read, block_write};
+///
+/// let mut storage = MemStorageBuilder::<32>::new().page_size(16).would_block_cycles(2).build();
+/// block_write(&mut storage, Address(0), &mut [1, 2]).unwrap();
+///
+/// let mut scratch = [0u8; 16];
+/// let mut cache = CachedPage::new(storage, &mut scratch).unwrap();
+/// cache.try_write_slice(Address(2), &mut [3]).unwrap();
+/// let mut buf = [0u8; 3];
+/// cache.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2, 3]);
+/// cache.flush().unwrap();
+///
+/// let mut storage = cache.into_inner();
+/// block_read(&mut storage, Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2, 3]);
+/// # }
+/// ```
+pub struct CachedPage<'a, T, Word, U> {
+	dev: T,
+	scratch: &'a mut [Word],
+	start: Address<U>,
+	page: Option<(Address<U>, usize)>,
+	dirty: bool,
+}
+
+impl<'a, T, Word, U, E> CachedPage<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>,
+	Word: Copy,
+	U: Add<U, Output = U>
+		+ Sub<U, Output = U>
+		+ Rem<U, Output = U>
+		+ PartialEq
+		+ From<u8>
+		+ TryFrom<usize>
+		+ Copy,
+	usize: TryFrom<U>,
+{
+	/// Wrap `dev`, caching pages in `scratch`
+	pub fn new(mut dev: T, scratch: &'a mut [Word]) -> Result<Self, E> {
+		let start = nb::block!(dev.try_start_address())?;
+		Ok(CachedPage {
+			dev,
+			scratch,
+			start,
+			page: None,
+			dirty: false,
+		})
+	}
+
+	/// Write the cached page back to the device, if it was modified
+	///
+	/// Unlike [`Flush::try_flush`], this doesn't flush the device itself.
+	pub fn flush(&mut self) -> Result<(), Error<E>> {
+		if let (true, Some((address, len))) = (self.dirty, self.page) {
+			nb::block!(self.dev.try_erase_address(address))?;
+			nb::block!(self.dev.try_write_slice(address, &mut self.scratch[..len]))?;
+			self.dirty = false;
+		}
+		Ok(())
+	}
+
+	/// Release the device, without writing back the cached page
+	///
+	/// Call [`flush`](CachedPage::flush) first to keep the pending writes.
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+
+	/// The start address and length of the page containing `address`, and the offset of `address` in it
+	fn locate(&mut self, address: Address<U>) -> Result<(Address<U>, usize, usize), Error<E>> {
+		let page_size = nb::block!(self.dev.try_page_size(address))?;
+		if page_size.0 == U::from(0) {
+			// A zero sized page would never advance
+			return Err(Error::OutOfBounds);
+		}
+		let offset = (address.0 - self.start.0) % page_size.0;
+		let page = Address(address.0 - offset);
+		let offset = len_from_offset(AddressOffset(offset)).ok_or(Error::Overflow)?;
+		let len = len_from_offset(page_size).ok_or(Error::Overflow)?;
+		Ok((page, offset, len))
+	}
+
+	fn is_cached(&self, page: Address<U>) -> bool {
+		self.page.map_or(false, |(address, _)| address == page)
+	}
+}
+
+impl<'a, T, Word, U, E> MultiRead<Word, U> for CachedPage<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>,
+	Word: Copy,
+	U: Add<U, Output = U>
+		+ Sub<U, Output = U>
+		+ Rem<U, Output = U>
+		+ PartialEq
+		+ From<u8>
+		+ TryFrom<usize>
+		+ Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<E>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let mut address = address;
+		let mut buf = buf;
+		while !buf.is_empty() {
+			let (page, offset, len) = self.locate(address)?;
+			let n = len.saturating_sub(offset).min(buf.len());
+			let (part, rest) = buf.split_at_mut(n);
+			if self.is_cached(page) {
+				part.copy_from_slice(&self.scratch[offset..offset + n]);
+			} else {
+				nb::block!(self.dev.try_read_slice(address, part)).map_err(Error::Device)?;
+			}
+			address += offset_from_len(n).ok_or(Error::Overflow)?;
+			buf = rest;
+		}
+		Ok(())
+	}
+}
+
+impl<'a, T, Word, U, E> MultiWrite<Word, U> for CachedPage<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>,
+	Word: Copy,
+	U: Add<U, Output = U>
+		+ Sub<U, Output = U>
+		+ Rem<U, Output = U>
+		+ PartialEq
+		+ From<u8>
+		+ TryFrom<usize>
+		+ Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<E>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let mut address = address;
+		let mut buf = &buf[..];
+		while !buf.is_empty() {
+			let (page, offset, len) = self.locate(address)?;
+			if !self.is_cached(page) {
+				self.flush()?;
+				// Until the new page is fully read, `scratch` matches no page
+				self.page = None;
+				let scratch = self.scratch.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+				nb::block!(self.dev.try_read_slice(page, scratch)).map_err(Error::Device)?;
+				self.page = Some((page, len));
+			}
+			let n = len.saturating_sub(offset).min(buf.len());
+			self.scratch[offset..offset + n].copy_from_slice(&buf[..n]);
+			self.dirty = true;
+			address += offset_from_len(n).ok_or(Error::Overflow)?;
+			buf = &buf[n..];
+		}
+		Ok(())
+	}
+}
+
+impl<'a, T, Word, U, E> Flush for CachedPage<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>
+		+ Flush<Error = E>,
+	Word: Copy,
+	U: Add<U, Output = U>
+		+ Sub<U, Output = U>
+		+ Rem<U, Output = U>
+		+ PartialEq
+		+ From<u8>
+		+ TryFrom<usize>
+		+ Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<E>;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.flush()?;
+		self.dev.try_flush().map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<'a, T, Word, U> StorageSize<Word, U> for CachedPage<'a, T, Word, U>
+where
+	T: StorageSize<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		self.dev.try_start_address()
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.dev.try_total_size()
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.dev.try_page_size(address)
+	}
+}
+
+impl<'a, T, Word, U> ReadAccess for CachedPage<'a, T, Word, U>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.dev.access_pattern()
+	}
+}
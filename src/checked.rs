@@ -0,0 +1,496 @@
+//! Checked
+//!
+//! Wrappers validating accesses before they reach the device.
+
+use crate::helpers::PageIter;
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, ErrorKind, Flush, MultiRead, MultiWrite,
+	Page, ReadAccess, Semantics, SingleRead, SingleWrite, StorageError, StorageSize,
+	WriteSemantics,
+};
+use core::convert::TryFrom;
+use core::ops::{Add, Rem, Sub};
+
+/// Errors returned by [`CheckedWrite`] and [`Partition`](crate::partition::Partition)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E, U> {
+	/// An error reported by the device
+	Device(E),
+	/// The address or length of the write isn't a multiple of the write size
+	Misaligned {
+		/// The address of the rejected write
+		address: Address<U>,
+	},
+	/// The access is not within the bounds of the device
+	OutOfBounds {
+		/// The address of the rejected access
+		address: Address<U>,
+	},
+	/// The page is not within the bounds of the device
+	PageOutOfBounds {
+		/// The rejected page
+		page: Page<U>,
+	},
+}
+
+impl<E: StorageError, U: core::fmt::Debug> StorageError for Error<E, U> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::Misaligned { .. } => ErrorKind::NotAligned,
+			Error::OutOfBounds { .. } | Error::PageOutOfBounds { .. } => ErrorKind::OutOfBounds,
+		}
+	}
+}
+
+/// Errors returned by [`Bounded`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsError<E, U> {
+	/// An error reported by the device
+	Device(E),
+	/// The access is not within the bounds of the device
+	OutOfBounds {
+		/// The address of the rejected access
+		address: Address<U>,
+	},
+	/// The page is not within the bounds of the device
+	PageOutOfBounds {
+		/// The rejected page
+		page: Page<U>,
+	},
+}
+
+impl<E: StorageError, U: core::fmt::Debug> StorageError for BoundsError<E, U> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			BoundsError::Device(e) => e.kind(),
+			BoundsError::OutOfBounds { .. } | BoundsError::PageOutOfBounds { .. } => {
+				ErrorKind::OutOfBounds
+			}
+		}
+	}
+}
+
+/// Rejects writes that aren't aligned to the write size of the device.
+///
+/// Both the start address and the length of every `try_write_slice` must be a
+/// multiple of the write size, otherwise [`Error::Misaligned`] is returned and
+/// the device is left untouched. All other traits are forwarded unchanged.
+///
+/// ```
+/// use embedded_storage::checked::{CheckedWrite, Error};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+///
+/// let mut storage = CheckedWrite::new(MemStorage::<16>::new(16), 4);
+///
+/// assert_eq!(
+///     storage.try_write_slice(Address(2), &mut [0; 4]),
+///     Err(nb::Error::Other(Error::Misaligned { address: Address(2) }))
+/// );
+/// assert_eq!(
+///     storage.try_write_slice(Address(4), &mut [0; 2]),
+///     Err(nb::Error::Other(Error::Misaligned { address: Address(4) }))
+/// );
+///
+/// storage.try_write_slice(Address(4), &mut [1, 2, 3, 4]).unwrap();
+/// let mut buf = [0u8; 4];
+/// storage.try_read_slice(Address(4), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2, 3, 4]);
+/// ```
+pub struct CheckedWrite<T, U> {
+	inner: T,
+	write_size: usize,
+	align: U,
+}
+
+impl<T, U> CheckedWrite<T, U>
+where
+	U: TryFrom<usize>,
+{
+	/// Wrap `inner`, requiring writes to be aligned to `write_size` words.
+	///
+	/// Panics if `write_size` is zero or doesn't fit in `U`.
+	pub fn new(inner: T, write_size: usize) -> Self {
+		assert!(write_size > 0);
+		let align = match U::try_from(write_size) {
+			Ok(align) => align,
+			Err(_) => panic!("write size must fit the address type"),
+		};
+		CheckedWrite {
+			inner,
+			write_size,
+			align,
+		}
+	}
+}
+
+impl<T, U> CheckedWrite<T, U> {
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.inner
+	}
+}
+
+impl<T, Word, U> MultiWrite<Word, U> for CheckedWrite<T, U>
+where
+	T: MultiWrite<Word, U>,
+	U: Rem<U, Output = U> + PartialEq + From<u8> + Copy,
+{
+	type Error = Error<T::Error, U>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		if address.0 % self.align != U::from(0) || buf.len() % self.write_size != 0 {
+			return Err(nb::Error::Other(Error::Misaligned { address }));
+		}
+		self.inner
+			.try_write_slice(address, buf)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, Word, U> SingleRead<Word, U> for CheckedWrite<T, U>
+where
+	T: SingleRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		self.inner.try_read(address)
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for CheckedWrite<T, U>
+where
+	T: MultiRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.inner.try_read_slice(address, buf)
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		self.inner.try_read_partial(address, buf)
+	}
+}
+
+impl<T, U> ErasePage<U> for CheckedWrite<T, U>
+where
+	T: ErasePage<U>,
+{
+	type Error = T::Error;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		self.inner.try_erase_page(page)
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		self.inner.try_erase_address(address)
+	}
+}
+
+impl<T, Word, U> StorageSize<Word, U> for CheckedWrite<T, U>
+where
+	T: StorageSize<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		self.inner.try_start_address()
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner.try_total_size()
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner.try_page_size(address)
+	}
+}
+
+impl<T, U> Flush for CheckedWrite<T, U>
+where
+	T: Flush,
+{
+	type Error = T::Error;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.inner.try_flush()
+	}
+}
+
+impl<T, U> WriteSemantics for CheckedWrite<T, U>
+where
+	T: WriteSemantics,
+{
+	fn write_semantics(&self) -> Semantics {
+		self.inner.write_semantics()
+	}
+}
+
+impl<T, U> ReadAccess for CheckedWrite<T, U>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.inner.access_pattern()
+	}
+}
+
+/// Rejects accesses outside of the address range of the device.
+///
+/// The start address, total size and number of pages are queried once, when
+/// the wrapper is created. Every read, write and erase by address is checked
+/// against them and rejected with [`BoundsError::OutOfBounds`] without
+/// accessing the device, and erasing a page past the last one with
+/// [`BoundsError::PageOutOfBounds`].
+///
+/// ```
+/// use embedded_storage::checked::{Bounded, BoundsError};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, ErasePage, MultiRead, MultiWrite, Page};
+///
+/// let mut storage = Bounded::new(MemStorage::<16>::new(16)).unwrap();
+///
+/// let mut buf = [0u8; 4];
+/// storage.try_read_slice(Address(12), &mut buf).unwrap();
+///
+/// // Straddles the end of the device
+/// assert_eq!(
+///     storage.try_write_slice(Address(14), &mut buf),
+///     Err(nb::Error::Other(BoundsError::OutOfBounds { address: Address(14) }))
+/// );
+/// assert_eq!(
+///     storage.try_read_slice(Address(16), &mut []),
+///     Ok(())
+/// );
+///
+/// storage.try_erase_page(Page(0)).unwrap();
+/// assert_eq!(
+///     storage.try_erase_page(Page(1)),
+///     Err(nb::Error::Other(BoundsError::PageOutOfBounds { page: Page(1) }))
+/// );
+/// ```
+pub struct Bounded<T, U> {
+	inner: T,
+	start: Address<U>,
+	end: Address<U>,
+	pages: U,
+}
+
+impl<T, U> Bounded<T, U>
+where
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	/// Wrap `inner`, querying its start address, total size and pages.
+	pub fn new<Word>(mut inner: T) -> Result<Self, T::Error>
+	where
+		T: StorageSize<Word, U>,
+	{
+		let start = nb::block!(inner.try_start_address())?;
+		let size = nb::block!(inner.try_total_size())?;
+		let mut pages = U::from(0);
+		let mut iter = PageIter::new(&mut inner)?;
+		for _ in &mut iter {
+			pages = pages + U::from(1);
+		}
+		if let Some(e) = iter.into_error() {
+			return Err(e);
+		}
+		Ok(Bounded {
+			inner,
+			start,
+			end: &start + &size,
+			pages,
+		})
+	}
+
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.inner
+	}
+
+	/// Check that `len` words from `address` are within the device
+	fn check<E>(&self, address: Address<U>, len: usize) -> Result<(), BoundsError<E, U>> {
+		let fits = match U::try_from(len) {
+			Ok(len) => {
+				address >= self.start && address <= self.end && len <= self.end.0 - address.0
+			}
+			Err(_) => false,
+		};
+		if fits {
+			Ok(())
+		} else {
+			Err(BoundsError::OutOfBounds { address })
+		}
+	}
+}
+
+impl<T, Word, U> SingleRead<Word, U> for Bounded<T, U>
+where
+	T: SingleRead<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = BoundsError<T::Error, U>;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		self.check(address, 1)?;
+		self.inner
+			.try_read(address)
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+}
+
+impl<T, Word, U> SingleWrite<Word, U> for Bounded<T, U>
+where
+	T: SingleWrite<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = BoundsError<T::Error, U>;
+
+	fn try_write(&mut self, address: Address<U>, word: Word) -> nb::Result<(), Self::Error> {
+		self.check(address, 1)?;
+		self.inner
+			.try_write(address, word)
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for Bounded<T, U>
+where
+	T: MultiRead<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = BoundsError<T::Error, U>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.check(address, buf.len())?;
+		self.inner
+			.try_read_slice(address, buf)
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		self.check(address, buf.len())?;
+		self.inner
+			.try_read_partial(address, buf)
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+}
+
+impl<T, Word, U> MultiWrite<Word, U> for Bounded<T, U>
+where
+	T: MultiWrite<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = BoundsError<T::Error, U>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.check(address, buf.len())?;
+		self.inner
+			.try_write_slice(address, buf)
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+}
+
+impl<T, U> ErasePage<U> for Bounded<T, U>
+where
+	T: ErasePage<U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = BoundsError<T::Error, U>;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		if page.0 >= self.pages {
+			return Err(nb::Error::Other(BoundsError::PageOutOfBounds { page }));
+		}
+		self.inner
+			.try_erase_page(page)
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		self.check(address, 1)?;
+		self.inner
+			.try_erase_address(address)
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+}
+
+impl<T, Word, U> StorageSize<Word, U> for Bounded<T, U>
+where
+	T: StorageSize<Word, U>,
+	U: Sub<U, Output = U> + Copy,
+{
+	type Error = BoundsError<T::Error, U>;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		Ok(self.start)
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		Ok(AddressOffset(self.end.0 - self.start.0))
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner
+			.try_page_size(address)
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+}
+
+impl<T, U> Flush for Bounded<T, U>
+where
+	T: Flush,
+{
+	type Error = BoundsError<T::Error, U>;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.inner
+			.try_flush()
+			.map_err(|e| e.map(BoundsError::Device))
+	}
+}
+
+impl<T, U> WriteSemantics for Bounded<T, U>
+where
+	T: WriteSemantics,
+{
+	fn write_semantics(&self) -> Semantics {
+		self.inner.write_semantics()
+	}
+}
+
+impl<T, U> ReadAccess for Bounded<T, U>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.inner.access_pattern()
+	}
+}
@@ -0,0 +1,217 @@
+//! Commit
+//!
+//! Power loss safe updates of a record, alternating between two slots.
+//!
+//! Each slot is a page holding a 12 byte header followed by the data:
+//!
+//! | Offset | Size | Content                                               |
+//! |--------|------|-------------------------------------------------------|
+//! | 0      | 4    | Sequence number, little endian                        |
+//! | 4      | 4    | Data length in bytes, little endian                   |
+//! | 8      | 4    | CRC-32 (IEEE) of the sequence, length and data, little endian |
+//! | 12     | len  | Data                                                  |
+//!
+//! [`write_ab`] erases the slot not holding the latest record and writes the
+//! data, length and checksum, then the sequence number last. Until the
+//! sequence number is written the checksum doesn't match, so a write
+//! interrupted at any point leaves the previous record as the latest valid one.
+
+use crate::crc::{Crc, Crc32Ieee};
+use crate::helpers::{len_from_offset, offset_from_len, Error};
+use crate::storage::{Address, ErasePage, MultiRead, MultiWrite, StorageSize};
+use core::convert::TryFrom;
+use core::ops::Add;
+
+/// Size of the header of a slot in bytes
+pub const HEADER_SIZE: usize = 12;
+
+/// Number of bytes read per transaction while checking a slot
+const CHUNK_SIZE: usize = 32;
+
+/// Write `data` as a new record, to the slot not holding the latest valid record.
+///
+/// Returns the sequence number of the new record. Returns
+/// [`Error::CrossesPage`] if the header and data don't fit in the page of the
+/// slot.
+///
+/// ```
+/// use embedded_storage::commit::{read_latest, write_ab};
+/// use embedded_storage::helpers::Error;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::Address;
+///
+/// let mut storage = MemStorage::<64>::new(32);
+/// let (a, b) = (Address(0), Address(32));
+/// let mut buf = [0u8; 20];
+/// assert_eq!(read_latest(&mut storage, a, b, &mut buf), Ok(None));
+///
+/// assert_eq!(write_ab(&mut storage, a, b, &mut [1, 2, 3]), Ok(0));
+/// assert_eq!(write_ab(&mut storage, a, b, &mut [4, 5]), Ok(1));
+/// assert_eq!(read_latest(&mut storage, a, b, &mut buf), Ok(Some(2)));
+/// assert_eq!(buf[..2], [4, 5]);
+///
+/// // Power is lost after writing the third record to slot A, before its sequence number
+/// write_ab(&mut storage, a, b, &mut [6, 7, 8, 9]).unwrap();
+/// storage.as_mut_slice()[..4].fill(0xFF);
+/// assert_eq!(read_latest(&mut storage, a, b, &mut buf), Ok(Some(2)));
+/// assert_eq!(buf[..2], [4, 5]);
+///
+/// assert_eq!(write_ab(&mut storage, a, b, &mut [0; 21]), Err(Error::CrossesPage));
+/// ```
+pub fn write_ab<T, U, E>(
+	dev: &mut T,
+	slot_a: Address<U>,
+	slot_b: Address<U>,
+	data: &mut [u8],
+) -> Result<u32, Error<E>>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>
+		+ ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let a = read_slot(dev, slot_a)?;
+	let b = read_slot(dev, slot_b)?;
+	let (slot, seq) = match (a, b) {
+		(Some((a, _)), Some((b, _))) if b > a => (slot_a, b.wrapping_add(1)),
+		(Some((a, _)), _) => (slot_b, a.wrapping_add(1)),
+		(None, Some((b, _))) => (slot_a, b.wrapping_add(1)),
+		(None, None) => (slot_a, 0),
+	};
+	write_slot(dev, slot, seq, data)?;
+	Ok(seq)
+}
+
+/// Erase `slot` and write `data` to it as the record with sequence number `seq`
+pub(crate) fn write_slot<T, U, E>(
+	dev: &mut T,
+	slot: Address<U>,
+	seq: u32,
+	data: &mut [u8],
+) -> Result<(), Error<E>>
+where
+	T: MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>
+		+ ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	if data.len() > capacity(dev, slot)? {
+		return Err(Error::CrossesPage);
+	}
+
+	let seq = seq.to_le_bytes();
+	let len = u32::try_from(data.len())
+		.map_err(|_| Error::Overflow)?
+		.to_le_bytes();
+	let mut crc = Crc32Ieee::new();
+	crc.update(&seq);
+	crc.update(&len);
+	crc.update(data);
+	let mut header = [0; HEADER_SIZE - 4];
+	header[..4].copy_from_slice(&len);
+	header[4..].copy_from_slice(&crc.finish().to_le_bytes());
+
+	nb::block!(dev.try_erase_address(slot))?;
+	nb::block!(dev.try_write_slice(at(slot, HEADER_SIZE)?, data))?;
+	nb::block!(dev.try_write_slice(at(slot, 4)?, &mut header))?;
+	let mut commit = seq;
+	nb::block!(dev.try_write_slice(slot, &mut commit))?;
+	Ok(())
+}
+
+/// Read the record with the highest sequence number and a valid checksum into `buf`.
+///
+/// Returns the length of the record, or `None` if neither slot holds a valid
+/// record. Returns [`Error::BufferTooSmall`] if the record doesn't fit in `buf`.
+pub fn read_latest<T, U, E>(
+	dev: &mut T,
+	slot_a: Address<U>,
+	slot_b: Address<U>,
+	buf: &mut [u8],
+) -> Result<Option<usize>, Error<E>>
+where
+	T: MultiRead<u8, U, Error = E> + StorageSize<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let latest = match (read_slot(dev, slot_a)?, read_slot(dev, slot_b)?) {
+		(Some((a, len)), Some((b, _))) if a >= b => Some((slot_a, len)),
+		(_, Some((_, len))) => Some((slot_b, len)),
+		(Some((_, len)), None) => Some((slot_a, len)),
+		(None, None) => None,
+	};
+	match latest {
+		Some((slot, len)) => {
+			let data = buf.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+			nb::block!(dev.try_read_slice(at(slot, HEADER_SIZE)?, data))?;
+			Ok(Some(len))
+		}
+		None => Ok(None),
+	}
+}
+
+/// The sequence number and length of the record in `slot`, if it is valid
+pub(crate) fn read_slot<T, U, E>(
+	dev: &mut T,
+	slot: Address<U>,
+) -> Result<Option<(u32, usize)>, Error<E>>
+where
+	T: MultiRead<u8, U, Error = E> + StorageSize<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let mut header = [0; HEADER_SIZE];
+	nb::block!(dev.try_read_slice(slot, &mut header))?;
+	let mut word = [0; 4];
+	word.copy_from_slice(&header[..4]);
+	let seq = u32::from_le_bytes(word);
+	word.copy_from_slice(&header[4..8]);
+	let len = u32::from_le_bytes(word) as usize;
+	word.copy_from_slice(&header[8..]);
+	let stored = u32::from_le_bytes(word);
+	if len > capacity(dev, slot)? {
+		return Ok(None);
+	}
+
+	let mut crc = Crc32Ieee::new();
+	crc.update(&header[..8]);
+	let mut address = at(slot, HEADER_SIZE)?;
+	let mut left = len;
+	let mut chunk = [0; CHUNK_SIZE];
+	while left > 0 {
+		let part = &mut chunk[..left.min(CHUNK_SIZE)];
+		nb::block!(dev.try_read_slice(address, part))?;
+		crc.update(part);
+		address += offset_from_len(part.len()).ok_or(Error::Overflow)?;
+		left -= part.len();
+	}
+	if crc.finish() == stored {
+		Ok(Some((seq, len)))
+	} else {
+		Ok(None)
+	}
+}
+
+/// The number of data bytes that fit in the page of `slot`
+fn capacity<T, U, E>(dev: &mut T, slot: Address<U>) -> Result<usize, Error<E>>
+where
+	T: StorageSize<u8, U, Error = E> + ?Sized,
+	U: Copy,
+	usize: TryFrom<U>,
+{
+	let page_size = nb::block!(dev.try_page_size(slot))?;
+	let page_size = len_from_offset(page_size).unwrap_or(usize::MAX);
+	Ok(page_size.saturating_sub(HEADER_SIZE))
+}
+
+pub(crate) fn at<U, E>(slot: Address<U>, offset: usize) -> Result<Address<U>, Error<E>>
+where
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	Ok(&slot + &offset_from_len(offset).ok_or(Error::Overflow)?)
+}
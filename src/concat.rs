@@ -0,0 +1,345 @@
+//! Concat
+//!
+//! Present two devices as one contiguous address space.
+
+use crate::helpers::PageIter;
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, ErrorKind, Flush, MultiRead, MultiWrite,
+	Page, ReadAccess, SingleRead, SingleWrite, StorageError, StorageSize,
+};
+use core::convert::TryFrom;
+use core::ops::{Add, Sub};
+
+/// Errors returned by [`Concat`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<A, B> {
+	/// An error reported by the first device
+	A(A),
+	/// An error reported by the second device
+	B(B),
+	/// The access is not within the combined address space
+	OutOfBounds,
+}
+
+impl<A: StorageError, B: StorageError> StorageError for Error<A, B> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::A(e) => e.kind(),
+			Error::B(e) => e.kind(),
+			Error::OutOfBounds => ErrorKind::OutOfBounds,
+		}
+	}
+}
+
+/// Two devices presented as one, with the second device following directly after the first.
+///
+/// The combined address space starts at the start address of `A`. Addresses
+/// beyond the end of `A` are routed to `B`. Pages are numbered the same way,
+/// the first page of `B` following the last page of `A`.
+///
+/// Multi word accesses crossing from `A` into `B` are split across both
+/// devices. If `B` returns `WouldBlock` for the second part, retrying repeats
+/// the access to `A` as well.
+///
+/// ```
+/// use embedded_storage::concat::Concat;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{
+///     Address, AddressOffset, ErasePage, MultiRead, MultiWrite, Page, StorageSize,
+/// };
+///
+/// let mut storage = Concat::new(MemStorage::<8>::new(4), MemStorage::<8>::new(4)).unwrap();
+/// assert_eq!(storage.try_total_size(), Ok(AddressOffset(16)));
+///
+/// storage.try_write_slice(Address(6), &mut [1, 2, 3, 4]).unwrap();
+/// let mut buf = [0u8; 4];
+/// storage.try_read_slice(Address(6), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2, 3, 4]);
+///
+/// // Page 1 is the last page of the first device
+/// storage.try_erase_page(Page(1)).unwrap();
+///
+/// let (a, b) = storage.into_inner();
+/// assert_eq!(a.as_slice()[6..], [0xFF, 0xFF]);
+/// assert_eq!(b.as_slice()[..2], [3, 4]);
+/// ```
+pub struct Concat<A, B, U> {
+	a: A,
+	b: B,
+	a_start: Address<U>,
+	a_end: Address<U>,
+	a_pages: U,
+	b_start: Address<U>,
+	end: Address<U>,
+}
+
+impl<A, B, U> Concat<A, B, U>
+where
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Combine `a` and `b`, querying the address range and pages of both.
+	pub fn new<Word>(mut a: A, mut b: B) -> Result<Self, Error<A::Error, B::Error>>
+	where
+		A: StorageSize<Word, U>,
+		B: StorageSize<Word, U>,
+	{
+		let mut a_pages = U::from(0);
+		{
+			let mut pages = PageIter::new(&mut a).map_err(Error::A)?;
+			for _ in &mut pages {
+				a_pages = a_pages + U::from(1);
+			}
+			if let Some(e) = pages.into_error() {
+				return Err(Error::A(e));
+			}
+		}
+		let a_start = nb::block!(a.try_start_address()).map_err(Error::A)?;
+		let a_size = nb::block!(a.try_total_size()).map_err(Error::A)?;
+		let b_start = nb::block!(b.try_start_address()).map_err(Error::B)?;
+		let b_size = nb::block!(b.try_total_size()).map_err(Error::B)?;
+		let a_end = &a_start + &a_size;
+		Ok(Concat {
+			a,
+			b,
+			a_start,
+			a_end,
+			a_pages,
+			b_start,
+			end: &a_end + &b_size,
+		})
+	}
+
+	/// Release the two devices
+	pub fn into_inner(self) -> (A, B) {
+		(self.a, self.b)
+	}
+
+	/// Check that `len` words from `address` are within the combined space,
+	/// returning how many of them belong to `A`
+	fn split<EA, EB>(&self, address: Address<U>, len: usize) -> Result<usize, Error<EA, EB>> {
+		let len_u = U::try_from(len).map_err(|_| Error::OutOfBounds)?;
+		if address < self.a_start || address > self.end || len_u > self.end.0 - address.0 {
+			return Err(Error::OutOfBounds);
+		}
+		if address >= self.a_end {
+			return Ok(0);
+		}
+		Ok(match usize::try_from(self.a_end.0 - address.0) {
+			Ok(in_a) => in_a.min(len),
+			Err(_) => len,
+		})
+	}
+
+	/// The address in `B` of an address beyond the end of `A`
+	fn b_address(&self, address: Address<U>) -> Address<U> {
+		Address(self.b_start.0 + (address.0 - self.a_end.0))
+	}
+}
+
+impl<A, B, Word, U> SingleRead<Word, U> for Concat<A, B, U>
+where
+	A: SingleRead<Word, U>,
+	B: SingleRead<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<A::Error, B::Error>;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		if self.split(address, 1)? > 0 {
+			self.a.try_read(address).map_err(|e| e.map(Error::A))
+		} else {
+			let address = self.b_address(address);
+			self.b.try_read(address).map_err(|e| e.map(Error::B))
+		}
+	}
+}
+
+impl<A, B, Word, U> SingleWrite<Word, U> for Concat<A, B, U>
+where
+	A: SingleWrite<Word, U>,
+	B: SingleWrite<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<A::Error, B::Error>;
+
+	fn try_write(&mut self, address: Address<U>, word: Word) -> nb::Result<(), Self::Error> {
+		if self.split(address, 1)? > 0 {
+			self.a.try_write(address, word).map_err(|e| e.map(Error::A))
+		} else {
+			let address = self.b_address(address);
+			self.b.try_write(address, word).map_err(|e| e.map(Error::B))
+		}
+	}
+}
+
+impl<A, B, Word, U> MultiRead<Word, U> for Concat<A, B, U>
+where
+	A: MultiRead<Word, U>,
+	B: MultiRead<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<A::Error, B::Error>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let in_a = self.split(address, buf.len())?;
+		let (a, b) = buf.split_at_mut(in_a);
+		if !a.is_empty() {
+			self.a
+				.try_read_slice(address, a)
+				.map_err(|e| e.map(Error::A))?;
+		}
+		if !b.is_empty() {
+			let address = self.b_address(if in_a > 0 { self.a_end } else { address });
+			self.b
+				.try_read_slice(address, b)
+				.map_err(|e| e.map(Error::B))?;
+		}
+		Ok(())
+	}
+}
+
+impl<A, B, Word, U> MultiWrite<Word, U> for Concat<A, B, U>
+where
+	A: MultiWrite<Word, U>,
+	B: MultiWrite<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<A::Error, B::Error>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let in_a = self.split(address, buf.len())?;
+		let (a, b) = buf.split_at_mut(in_a);
+		if !a.is_empty() {
+			self.a
+				.try_write_slice(address, a)
+				.map_err(|e| e.map(Error::A))?;
+		}
+		if !b.is_empty() {
+			let address = self.b_address(if in_a > 0 { self.a_end } else { address });
+			self.b
+				.try_write_slice(address, b)
+				.map_err(|e| e.map(Error::B))?;
+		}
+		Ok(())
+	}
+}
+
+impl<A, B, U> ErasePage<U> for Concat<A, B, U>
+where
+	A: ErasePage<U>,
+	B: ErasePage<U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<A::Error, B::Error>;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		if page.0 < self.a_pages {
+			self.a.try_erase_page(page).map_err(|e| e.map(Error::A))
+		} else {
+			let page = Page(page.0 - self.a_pages);
+			self.b.try_erase_page(page).map_err(|e| e.map(Error::B))
+		}
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		if self.split(address, 1)? > 0 {
+			self.a
+				.try_erase_address(address)
+				.map_err(|e| e.map(Error::A))
+		} else {
+			let address = self.b_address(address);
+			self.b
+				.try_erase_address(address)
+				.map_err(|e| e.map(Error::B))
+		}
+	}
+}
+
+impl<A, B, Word, U> StorageSize<Word, U> for Concat<A, B, U>
+where
+	A: StorageSize<Word, U>,
+	B: StorageSize<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<A::Error, B::Error>;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		Ok(self.a_start)
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		Ok(AddressOffset(self.end.0 - self.a_start.0))
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		if self.split(address, 1)? > 0 {
+			self.a.try_page_size(address).map_err(|e| e.map(Error::A))
+		} else {
+			let address = self.b_address(address);
+			self.b.try_page_size(address).map_err(|e| e.map(Error::B))
+		}
+	}
+}
+
+/// Flushes `A`, then `B`. A `WouldBlock` from `B` flushes `A` again on the
+/// next call, which completes at once as nothing is left to write.
+///
+/// ```
+/// use embedded_storage::cache::CachedPage;
+/// use embedded_storage::concat::Concat;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, Flush, MultiWrite};
+///
+/// let (mut a, mut b) = ([0u8; 8], [0u8; 8]);
+/// let low = CachedPage::new(MemStorage::<8>::new(8), &mut a).unwrap();
+/// let high = CachedPage::new(MemStorage::<8>::new(8), &mut b).unwrap();
+/// let mut storage = Concat::new(low, high).unwrap();
+///
+/// storage.try_write_slice(Address(6), &mut [1, 2, 3, 4]).unwrap();
+/// storage.try_flush().unwrap();
+///
+/// let (low, high) = storage.into_inner();
+/// assert_eq!(low.into_inner().as_slice()[6..], [1, 2]);
+/// assert_eq!(high.into_inner().as_slice()[..2], [3, 4]);
+/// ```
+impl<A, B, U> Flush for Concat<A, B, U>
+where
+	A: Flush,
+	B: Flush,
+{
+	type Error = Error<A::Error, B::Error>;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.a.try_flush().map_err(|e| e.map(Error::A))?;
+		self.b.try_flush().map_err(|e| e.map(Error::B))
+	}
+}
+
+/// Reports [`AccessPattern::SequentialOptimized`] if either half does.
+impl<A, B, U> ReadAccess for Concat<A, B, U>
+where
+	A: ReadAccess,
+	B: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		match (self.a.access_pattern(), self.b.access_pattern()) {
+			(AccessPattern::Random, AccessPattern::Random) => AccessPattern::Random,
+			_ => AccessPattern::SequentialOptimized,
+		}
+	}
+}
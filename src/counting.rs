@@ -0,0 +1,222 @@
+//! Counting
+//!
+//! Count the accesses made to a device, to profile access patterns and
+//! estimate wear in tests.
+
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, Flush, MultiRead, MultiWrite, Page,
+	ReadAccess, Semantics, SingleRead, SingleWrite, StorageSize, WriteSemantics,
+};
+use core::mem::{size_of, size_of_val};
+
+/// Counts the completed reads, writes and erases of the wrapped device.
+///
+/// Only operations that complete are counted, so a `WouldBlock` or an error
+/// from the device leaves the counters unchanged. The byte counts are the
+/// number of words accessed times the size of a word.
+///
+/// ```
+/// use embedded_storage::counting::Counting;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, ErasePage, MultiRead, MultiWrite, Page};
+///
+/// let mut storage = Counting::new(MemStorage::<32>::new(16));
+/// storage.try_erase_page(Page(1)).unwrap();
+/// storage.try_write_slice(Address(16), &mut [1, 2, 3]).unwrap();
+/// storage.try_write_slice(Address(19), &mut [4]).unwrap();
+/// let mut buf = [0u8; 8];
+/// storage.try_read_slice(Address(16), &mut buf).unwrap();
+/// assert!(storage.try_read_slice(Address(30), &mut buf).is_err());
+///
+/// assert_eq!(storage.read_ops, 1);
+/// assert_eq!(storage.bytes_read, 8);
+/// assert_eq!(storage.write_ops, 2);
+/// assert_eq!(storage.bytes_written, 4);
+/// assert_eq!(storage.erase_ops, 1);
+///
+/// storage.reset();
+/// assert_eq!(storage.write_ops, 0);
+/// ```
+pub struct Counting<T> {
+	inner: T,
+	/// The number of completed reads
+	pub read_ops: usize,
+	/// The number of completed writes
+	pub write_ops: usize,
+	/// The number of completed erases
+	pub erase_ops: usize,
+	/// The number of bytes read
+	pub bytes_read: usize,
+	/// The number of bytes written
+	pub bytes_written: usize,
+}
+
+impl<T> Counting<T> {
+	/// Wrap `inner`, with all counters at zero
+	pub fn new(inner: T) -> Self {
+		Counting {
+			inner,
+			read_ops: 0,
+			write_ops: 0,
+			erase_ops: 0,
+			bytes_read: 0,
+			bytes_written: 0,
+		}
+	}
+
+	/// Set all counters back to zero
+	pub fn reset(&mut self) {
+		self.read_ops = 0;
+		self.write_ops = 0;
+		self.erase_ops = 0;
+		self.bytes_read = 0;
+		self.bytes_written = 0;
+	}
+
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.inner
+	}
+}
+
+impl<T, Word, U> SingleRead<Word, U> for Counting<T>
+where
+	T: SingleRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		let word = self.inner.try_read(address)?;
+		self.read_ops += 1;
+		self.bytes_read += size_of::<Word>();
+		Ok(word)
+	}
+}
+
+impl<T, Word, U> SingleWrite<Word, U> for Counting<T>
+where
+	T: SingleWrite<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_write(&mut self, address: Address<U>, word: Word) -> nb::Result<(), Self::Error> {
+		self.inner.try_write(address, word)?;
+		self.write_ops += 1;
+		self.bytes_written += size_of::<Word>();
+		Ok(())
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for Counting<T>
+where
+	T: MultiRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.inner.try_read_slice(address, buf)?;
+		self.read_ops += 1;
+		self.bytes_read += size_of_val(buf);
+		Ok(())
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		let len = self.inner.try_read_partial(address, buf)?;
+		self.read_ops += 1;
+		self.bytes_read += len * size_of::<Word>();
+		Ok(len)
+	}
+}
+
+impl<T, Word, U> MultiWrite<Word, U> for Counting<T>
+where
+	T: MultiWrite<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.inner.try_write_slice(address, buf)?;
+		self.write_ops += 1;
+		self.bytes_written += size_of_val(buf);
+		Ok(())
+	}
+}
+
+impl<T, U> ErasePage<U> for Counting<T>
+where
+	T: ErasePage<U>,
+{
+	type Error = T::Error;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		self.inner.try_erase_page(page)?;
+		self.erase_ops += 1;
+		Ok(())
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		self.inner.try_erase_address(address)?;
+		self.erase_ops += 1;
+		Ok(())
+	}
+}
+
+impl<T, Word, U> StorageSize<Word, U> for Counting<T>
+where
+	T: StorageSize<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		self.inner.try_start_address()
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner.try_total_size()
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner.try_page_size(address)
+	}
+}
+
+impl<T> Flush for Counting<T>
+where
+	T: Flush,
+{
+	type Error = T::Error;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.inner.try_flush()
+	}
+}
+
+impl<T> WriteSemantics for Counting<T>
+where
+	T: WriteSemantics,
+{
+	fn write_semantics(&self) -> Semantics {
+		self.inner.write_semantics()
+	}
+}
+
+impl<T> ReadAccess for Counting<T>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.inner.access_pattern()
+	}
+}
@@ -0,0 +1,230 @@
+//! CRC
+//!
+//! CRC32 checksums and a checked block format for detecting corrupted data.
+//!
+//! A checked block starts with an 8 byte header followed by the data:
+//!
+//! | Offset | Size | Content                                      |
+//! |--------|------|----------------------------------------------|
+//! | 0      | 4    | Data length in bytes, little endian          |
+//! | 4      | 4    | CRC of the length and the data, little endian |
+//! | 8      | len  | Data                                         |
+
+use crate::helpers::{len_from_offset, offset_from_len, Error};
+use crate::storage::{Address, AddressOffset, AddressRange, MultiRead, MultiWrite, StorageWord};
+use core::convert::TryFrom;
+use core::ops::Add;
+
+/// Reflected polynomial of the standard CRC-32 (IEEE 802.3)
+pub const IEEE: u32 = 0xEDB8_8320;
+
+/// The standard CRC-32, as used by zlib and Ethernet
+pub type Crc32Ieee = Crc32<IEEE>;
+
+/// Size of the header of a checked block in bytes
+pub const HEADER_SIZE: usize = 8;
+
+/// A 32 bit checksum accumulator.
+///
+/// Implement this for a hardware CRC unit to use it instead of [`Crc32`].
+pub trait Crc {
+	/// Start a new checksum
+	fn reset(&mut self);
+
+	/// Add `bytes` to the checksum
+	fn update(&mut self, bytes: &[u8]);
+
+	/// The checksum of all bytes since the last reset
+	fn finish(&self) -> u32;
+}
+
+/// Software CRC-32 for the reflected polynomial `POLY`.
+///
+/// The register is initialized to `0xFFFFFFFF` and inverted at the end.
+///
+/// ```
+/// use embedded_storage::crc::{Crc, Crc32Ieee};
+///
+/// let mut crc = Crc32Ieee::new();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.finish(), 0xCBF4_3926);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32<const POLY: u32> {
+	value: u32,
+}
+
+impl<const POLY: u32> Crc32<POLY> {
+	/// Start a new checksum
+	pub fn new() -> Self {
+		Crc32 { value: !0 }
+	}
+}
+
+impl<const POLY: u32> Default for Crc32<POLY> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<const POLY: u32> Crc for Crc32<POLY> {
+	fn reset(&mut self) {
+		self.value = !0;
+	}
+
+	fn update(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.value ^= u32::from(*byte);
+			for _ in 0..8 {
+				let mask = (self.value & 1).wrapping_neg();
+				self.value = (self.value >> 1) ^ (POLY & mask);
+			}
+		}
+	}
+
+	fn finish(&self) -> u32 {
+		!self.value
+	}
+}
+
+/// Write `data` as a checked block at `address`.
+///
+/// The block takes [`HEADER_SIZE`] bytes more than `data`.
+///
+/// ```
+/// use embedded_storage::crc::{read_checked, write_checked, Crc32Ieee};
+/// use embedded_storage::helpers::Error;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::Address;
+///
+/// let mut storage = MemStorage::<64>::new(64);
+/// let mut crc = Crc32Ieee::new();
+///
+/// write_checked(&mut storage, Address(0), &mut crc, &mut [1, 2, 3, 4, 5]).unwrap();
+///
+/// let mut buf = [0u8; 16];
+/// assert_eq!(read_checked(&mut storage, Address(0), &mut crc, &mut buf), Ok(5));
+/// assert_eq!(buf[..5], [1, 2, 3, 4, 5]);
+///
+/// // Flip a single bit of the data
+/// storage.as_mut_slice()[10] ^= 0x01;
+/// assert_eq!(read_checked(&mut storage, Address(0), &mut crc, &mut buf), Err(Error::Crc));
+/// ```
+pub fn write_checked<T, U, C, E>(
+	dev: &mut T,
+	address: Address<U>,
+	crc: &mut C,
+	data: &mut [u8],
+) -> Result<(), Error<E>>
+where
+	T: MultiWrite<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + From<u8> + Copy,
+	C: Crc + ?Sized,
+{
+	let len = u32::try_from(data.len())
+		.map_err(|_| Error::Overflow)?
+		.to_le_bytes();
+	crc.reset();
+	crc.update(&len);
+	crc.update(data);
+
+	let mut header = [0u8; HEADER_SIZE];
+	header[..4].copy_from_slice(&len);
+	header[4..].copy_from_slice(&crc.finish().to_le_bytes());
+	nb::block!(dev.try_write_slice(address, &mut header))?;
+	nb::block!(dev.try_write_slice(data_address(address), data))?;
+	Ok(())
+}
+
+/// Read a checked block at `address` into `buf`, returning the length of the stored data.
+///
+/// Returns [`Error::Crc`] if the data doesn't match the stored checksum and
+/// [`Error::BufferTooSmall`] if the stored data doesn't fit in `buf`. Only the
+/// stored length is read from the device.
+pub fn read_checked<T, U, C, E>(
+	dev: &mut T,
+	address: Address<U>,
+	crc: &mut C,
+	buf: &mut [u8],
+) -> Result<usize, Error<E>>
+where
+	T: MultiRead<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + From<u8> + Copy,
+	C: Crc + ?Sized,
+{
+	let mut header = [0u8; HEADER_SIZE];
+	nb::block!(dev.try_read_slice(address, &mut header))?;
+	let mut len = [0u8; 4];
+	len.copy_from_slice(&header[..4]);
+	let mut stored = [0u8; 4];
+	stored.copy_from_slice(&header[4..]);
+
+	let data = buf
+		.get_mut(..u32::from_le_bytes(len) as usize)
+		.ok_or(Error::BufferTooSmall)?;
+	nb::block!(dev.try_read_slice(data_address(address), data))?;
+
+	crc.reset();
+	crc.update(&len);
+	crc.update(data);
+	if crc.finish() != u32::from_le_bytes(stored) {
+		return Err(Error::Crc);
+	}
+	Ok(data.len())
+}
+
+/// The CRC-32 (IEEE) of the words in `range`, read in chunks of `chunk.len()` words.
+///
+/// Words are added to the checksum as little endian bytes. The last chunk is
+/// shorter if the range isn't a multiple of the chunk size. Returns
+/// [`Error::BufferTooSmall`] if `chunk` is empty and the range isn't.
+///
+/// ```
+/// use embedded_storage::crc::region_crc;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MultiWrite};
+///
+/// let mut storage = MemStorage::<32>::new(16);
+/// storage.try_write_slice(Address(7), &mut b"123456789".clone()).unwrap();
+///
+/// let mut chunk = [0u8; 4];
+/// let range = AddressRange::new(Address(7), AddressOffset(9));
+/// assert_eq!(region_crc(&mut storage, range, &mut chunk), Ok(0xCBF4_3926));
+/// ```
+pub fn region_crc<T, Word, U>(
+	dev: &mut T,
+	range: AddressRange<U>,
+	chunk: &mut [Word],
+) -> Result<u32, Error<T::Error>>
+where
+	T: MultiRead<Word, U> + ?Sized,
+	Word: StorageWord,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let mut remaining = len_from_offset(range.len).ok_or(Error::Overflow)?;
+	if remaining > 0 && chunk.is_empty() {
+		return Err(Error::BufferTooSmall);
+	}
+	let mut crc = Crc32Ieee::new();
+	let mut address = range.start;
+	while remaining > 0 {
+		let len = remaining.min(chunk.len());
+		let words = &mut chunk[..len];
+		nb::block!(dev.try_read_slice(address, words))?;
+		for word in words.iter() {
+			crc.update(word.to_le_bytes().as_ref());
+		}
+		address += offset_from_len(len).ok_or(Error::Overflow)?;
+		remaining -= len;
+	}
+	Ok(crc.finish())
+}
+
+fn data_address<U>(address: Address<U>) -> Address<U>
+where
+	U: Add<U, Output = U> + From<u8> + Copy,
+{
+	&address + &AddressOffset(U::from(HEADER_SIZE as u8))
+}
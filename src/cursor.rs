@@ -0,0 +1,184 @@
+//! Cursor
+//!
+//! Sequential access to a device, treating it as a stream of words.
+
+use crate::helpers::{offset_from_len, Error};
+use crate::storage::{Address, AddressOffset, MultiRead, MultiWrite, SingleRead, StorageSize};
+use core::convert::TryFrom;
+use core::ops::{Add, Sub};
+
+/// A device together with a current position.
+///
+/// Every `read` and `write` starts at the current position and advances it by
+/// the number of words transferred. If a transfer returns an error, including
+/// `WouldBlock`, the position is left unchanged so the transfer can be retried.
+///
+/// [`peek`](Cursor::peek) reads the word at the current position without
+/// advancing. The word is kept until the next `read`, which uses it instead
+/// of reading it from the device again. Writing, seeking or borrowing the
+/// device mutably discards it.
+///
+/// ```
+/// use embedded_storage::cursor::Cursor;
+/// use embedded_storage::helpers::Error;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiWrite};
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(0), &mut [0, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+///
+/// let mut cursor = Cursor::bounded(storage).unwrap();
+/// let mut buf = [0u8; 3];
+/// cursor.read(&mut buf).unwrap();
+/// assert_eq!(buf, [0, 1, 2]);
+/// cursor.read(&mut buf).unwrap();
+/// assert_eq!(buf, [3, 4, 5]);
+/// assert_eq!(cursor.position(), Address(6));
+///
+/// assert_eq!(cursor.peek(), Ok(6));
+/// assert_eq!(cursor.position(), Address(6));
+/// cursor.read(&mut buf[..2]).unwrap();
+/// assert_eq!(buf[..2], [6, 7]);
+/// assert_eq!(cursor.position(), Address(8));
+///
+/// cursor.seek(Address(14));
+/// cursor.write(&mut [0xAA, 0xBB]).unwrap();
+/// assert_eq!(cursor.position(), Address(16));
+/// assert_eq!(cursor.read(&mut buf), Err(nb::Error::Other(Error::OutOfBounds)));
+/// assert_eq!(cursor.into_inner().as_slice()[14..], [0xAA, 0xBB]);
+/// ```
+pub struct Cursor<T, Word, U> {
+	dev: T,
+	position: Address<U>,
+	end: Option<Address<U>>,
+	peeked: Option<Word>,
+}
+
+impl<T, Word, U> Cursor<T, Word, U>
+where
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + Copy,
+{
+	/// Create a cursor at `position`, without bounds checking.
+	pub fn new(dev: T, position: Address<U>) -> Self {
+		Cursor {
+			dev,
+			position,
+			end: None,
+			peeked: None,
+		}
+	}
+
+	/// Create a cursor at the start address of the device.
+	///
+	/// Transfers that would go beyond the end of the device return
+	/// [`Error::OutOfBounds`] without accessing the device.
+	pub fn bounded<E>(mut dev: T) -> Result<Self, E>
+	where
+		T: StorageSize<Word, U, Error = E>,
+	{
+		let start = nb::block!(dev.try_start_address())?;
+		let size = nb::block!(dev.try_total_size())?;
+		Ok(Cursor {
+			dev,
+			position: start,
+			end: Some(&start + &size),
+			peeked: None,
+		})
+	}
+
+	/// Move the cursor to `position`
+	pub fn seek(&mut self, position: Address<U>) {
+		self.peeked = None;
+		self.position = position;
+	}
+
+	/// The current position of the cursor
+	pub fn position(&self) -> Address<U> {
+		self.position
+	}
+
+	/// A reference to the device
+	pub fn get_ref(&self) -> &T {
+		&self.dev
+	}
+
+	/// A mutable reference to the device. Accesses through it don't move the cursor.
+	pub fn get_mut(&mut self) -> &mut T {
+		self.peeked = None;
+		&mut self.dev
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+
+	/// Read the word at the current position, without advancing
+	pub fn peek<E>(&mut self) -> nb::Result<Word, Error<E>>
+	where
+		T: SingleRead<Word, U, Error = E>,
+		Word: Copy,
+	{
+		if let Some(word) = self.peeked {
+			return Ok(word);
+		}
+		self.check(1)?;
+		let word = self
+			.dev
+			.try_read(self.position)
+			.map_err(|e| e.map(Error::Device))?;
+		self.peeked = Some(word);
+		Ok(word)
+	}
+
+	/// Read words from the current position to fill `buf`
+	pub fn read(&mut self, buf: &mut [Word]) -> nb::Result<(), Error<T::Error>>
+	where
+		T: MultiRead<Word, U>,
+	{
+		let len = self.check(buf.len())?;
+		match buf.split_first_mut() {
+			Some((first, rest)) if self.peeked.is_some() => {
+				if !rest.is_empty() {
+					let one = offset_from_len(1).ok_or(Error::Overflow)?;
+					let next = &self.position + &one;
+					self.dev
+						.try_read_slice(next, rest)
+						.map_err(|e| e.map(Error::Device))?;
+				}
+				*first = self.peeked.take().unwrap();
+			}
+			_ => self
+				.dev
+				.try_read_slice(self.position, buf)
+				.map_err(|e| e.map(Error::Device))?,
+		}
+		self.position += len;
+		Ok(())
+	}
+
+	/// Write `buf` at the current position
+	pub fn write(&mut self, buf: &mut [Word]) -> nb::Result<(), Error<T::Error>>
+	where
+		T: MultiWrite<Word, U>,
+	{
+		let len = self.check(buf.len())?;
+		self.peeked = None;
+		self.dev
+			.try_write_slice(self.position, buf)
+			.map_err(|e| e.map(Error::Device))?;
+		self.position += len;
+		Ok(())
+	}
+
+	/// Check that `len` words fit from the current position
+	fn check<E>(&self, len: usize) -> Result<AddressOffset<U>, Error<E>> {
+		let len = offset_from_len(len).ok_or(Error::Overflow)?;
+		if let Some(end) = self.end {
+			if self.position > end || len.0 > end.0 - self.position.0 {
+				return Err(Error::OutOfBounds);
+			}
+		}
+		Ok(len)
+	}
+}
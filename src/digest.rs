@@ -0,0 +1,67 @@
+//! Digest
+//!
+//! Hash a region of a device with any [`Digest`], such as SHA-256 for
+//! verifying a signed firmware image, without loading it into RAM.
+
+use crate::helpers::{len_from_offset, offset_from_len, Error};
+use crate::storage::{AddressRange, MultiRead, StorageWord};
+use ::digest::{Digest, Output};
+use core::convert::TryFrom;
+use core::ops::Add;
+
+/// The digest of the words in `range`, read in chunks of `chunk.len()` words.
+///
+/// Words are hashed as little endian bytes. The last chunk is shorter if the
+/// range isn't a multiple of the chunk size. Returns
+/// [`Error::BufferTooSmall`] if `chunk` is empty and the range isn't.
+///
+/// ```
+/// use embedded_storage::digest::region_digest;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MultiWrite};
+/// use sha2::Sha256;
+///
+/// let mut storage = MemStorage::<32>::new(16);
+/// storage.try_write_slice(Address(5), &mut b"abc".clone()).unwrap();
+///
+/// let range = AddressRange::new(Address(5), AddressOffset(3));
+/// let hash = region_digest::<Sha256, _, _, _>(&mut storage, range, &mut [0u8; 2]).unwrap();
+/// assert_eq!(
+///     hash[..],
+///     [
+///         0xBA, 0x78, 0x16, 0xBF, 0x8F, 0x01, 0xCF, 0xEA, 0x41, 0x41, 0x40, 0xDE, 0x5D, 0xAE, 0x22,
+///         0x23, 0xB0, 0x03, 0x61, 0xA3, 0x96, 0x17, 0x7A, 0x9C, 0xB4, 0x10, 0xFF, 0x61, 0xF2, 0x00,
+///         0x15, 0xAD,
+///     ]
+/// );
+/// ```
+pub fn region_digest<D, T, Word, U>(
+	dev: &mut T,
+	range: AddressRange<U>,
+	chunk: &mut [Word],
+) -> Result<Output<D>, Error<T::Error>>
+where
+	D: Digest,
+	T: MultiRead<Word, U> + ?Sized,
+	Word: StorageWord,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let mut remaining = len_from_offset(range.len).ok_or(Error::Overflow)?;
+	if remaining > 0 && chunk.is_empty() {
+		return Err(Error::BufferTooSmall);
+	}
+	let mut digest = D::new();
+	let mut address = range.start;
+	while remaining > 0 {
+		let len = remaining.min(chunk.len());
+		let words = &mut chunk[..len];
+		nb::block!(dev.try_read_slice(address, words))?;
+		for word in words.iter() {
+			digest.update(word.to_le_bytes());
+		}
+		address += offset_from_len(len).ok_or(Error::Overflow)?;
+		remaining -= len;
+	}
+	Ok(digest.finalize())
+}
@@ -0,0 +1,117 @@
+//! Endian
+//!
+//! Read and write multi byte integers on byte addressed devices.
+//!
+//! Each function blocks until the device completes the access, reading or
+//! writing exactly the size of the integer starting at `address`. Errors from
+//! the device, including out of bounds accesses, are returned unchanged.
+//!
+//! ```
+//! use embedded_storage::endian::{read_u32_be, read_u32_le, write_u32_le};
+//! use embedded_storage::mem::MemStorage;
+//! use embedded_storage::storage::Address;
+//!
+//! let mut storage = MemStorage::<16>::new(16);
+//! write_u32_le(&mut storage, Address(4), 0xDEADBEEF).unwrap();
+//! assert_eq!(storage.as_slice()[4..8], [0xEF, 0xBE, 0xAD, 0xDE]);
+//!
+//! assert_eq!(read_u32_le(&mut storage, Address(4)), Ok(0xDEADBEEF));
+//! assert_eq!(read_u32_be(&mut storage, Address(4)), Ok(0xEFBEADDE));
+//! assert!(read_u32_le(&mut storage, Address(14)).is_err());
+//! ```
+
+use crate::storage::{Address, MultiRead, MultiWrite};
+
+fn read_array<T, U, const N: usize>(dev: &mut T, address: Address<U>) -> Result<[u8; N], T::Error>
+where
+	T: MultiRead<u8, U> + ?Sized,
+	U: Copy,
+{
+	let mut buf = [0; N];
+	nb::block!(dev.try_read_slice(address, &mut buf))?;
+	Ok(buf)
+}
+
+fn write_array<T, U, const N: usize>(
+	dev: &mut T,
+	address: Address<U>,
+	mut buf: [u8; N],
+) -> Result<(), T::Error>
+where
+	T: MultiWrite<u8, U> + ?Sized,
+	U: Copy,
+{
+	nb::block!(dev.try_write_slice(address, &mut buf))
+}
+
+/// Read a little endian `u16` at `address`
+pub fn read_u16_le<T, U>(dev: &mut T, address: Address<U>) -> Result<u16, T::Error>
+where
+	T: MultiRead<u8, U> + ?Sized,
+	U: Copy,
+{
+	read_array(dev, address).map(u16::from_le_bytes)
+}
+
+/// Read a big endian `u16` at `address`
+pub fn read_u16_be<T, U>(dev: &mut T, address: Address<U>) -> Result<u16, T::Error>
+where
+	T: MultiRead<u8, U> + ?Sized,
+	U: Copy,
+{
+	read_array(dev, address).map(u16::from_be_bytes)
+}
+
+/// Read a little endian `u32` at `address`
+pub fn read_u32_le<T, U>(dev: &mut T, address: Address<U>) -> Result<u32, T::Error>
+where
+	T: MultiRead<u8, U> + ?Sized,
+	U: Copy,
+{
+	read_array(dev, address).map(u32::from_le_bytes)
+}
+
+/// Read a big endian `u32` at `address`
+pub fn read_u32_be<T, U>(dev: &mut T, address: Address<U>) -> Result<u32, T::Error>
+where
+	T: MultiRead<u8, U> + ?Sized,
+	U: Copy,
+{
+	read_array(dev, address).map(u32::from_be_bytes)
+}
+
+/// Write `value` as a little endian `u16` at `address`
+pub fn write_u16_le<T, U>(dev: &mut T, address: Address<U>, value: u16) -> Result<(), T::Error>
+where
+	T: MultiWrite<u8, U> + ?Sized,
+	U: Copy,
+{
+	write_array(dev, address, value.to_le_bytes())
+}
+
+/// Write `value` as a big endian `u16` at `address`
+pub fn write_u16_be<T, U>(dev: &mut T, address: Address<U>, value: u16) -> Result<(), T::Error>
+where
+	T: MultiWrite<u8, U> + ?Sized,
+	U: Copy,
+{
+	write_array(dev, address, value.to_be_bytes())
+}
+
+/// Write `value` as a little endian `u32` at `address`
+pub fn write_u32_le<T, U>(dev: &mut T, address: Address<U>, value: u32) -> Result<(), T::Error>
+where
+	T: MultiWrite<u8, U> + ?Sized,
+	U: Copy,
+{
+	write_array(dev, address, value.to_le_bytes())
+}
+
+/// Write `value` as a big endian `u32` at `address`
+pub fn write_u32_be<T, U>(dev: &mut T, address: Address<U>, value: u32) -> Result<(), T::Error>
+where
+	T: MultiWrite<u8, U> + ?Sized,
+	U: Copy,
+{
+	write_array(dev, address, value.to_be_bytes())
+}
@@ -0,0 +1,222 @@
+//! Fault
+//!
+//! Inject device errors at chosen operations, to exercise error handling
+//! deterministically in tests.
+
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, Flush, MultiRead, MultiWrite, Page,
+	ReadAccess, SingleRead, SingleWrite, StorageSize,
+};
+
+/// The kinds of operation a fault can be scheduled for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+	/// Reads through [`SingleRead`] or [`MultiRead`]
+	Read,
+	/// Writes through [`SingleWrite`] or [`MultiWrite`]
+	Write,
+	/// Erases through [`ErasePage`]
+	Erase,
+}
+
+/// Returns a scheduled error instead of calling the wrapped device.
+///
+/// [`fail`](FaultInjector::fail) schedules `error` for the `nth` operation
+/// of a kind, counting from one and from the time it's scheduled. That
+/// operation returns the error without reaching the device, and the fault is
+/// then cleared so later operations succeed again. Every call counts as an
+/// operation, including calls returning `WouldBlock`. Scheduling a fault
+/// replaces the previous one, if it hasn't been injected yet.
+///
+/// ```
+/// use embedded_storage::fault::{FaultInjector, Operation};
+/// use embedded_storage::mem::{Error, MemStorage};
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+///
+/// let mut storage = FaultInjector::new(MemStorage::<16>::new(16));
+/// storage.fail(Operation::Write, 3, Error::OutOfBounds);
+///
+/// storage.try_write_slice(Address(0), &mut [1]).unwrap();
+/// storage.try_write_slice(Address(1), &mut [2]).unwrap();
+/// // Reads don't count towards a write fault
+/// let mut buf = [0u8; 2];
+/// storage.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(
+///     storage.try_write_slice(Address(2), &mut [3]),
+///     Err(nb::Error::Other(Error::OutOfBounds))
+/// );
+/// assert!(!storage.is_pending());
+///
+/// storage.try_write_slice(Address(3), &mut [4]).unwrap();
+/// assert_eq!(storage.into_inner().as_slice()[..4], [1, 2, 0xFF, 4]);
+/// ```
+pub struct FaultInjector<T, E> {
+	inner: T,
+	fault: Option<(Operation, usize, E)>,
+}
+
+impl<T, E> FaultInjector<T, E> {
+	/// Wrap `inner`, with no fault scheduled
+	pub fn new(inner: T) -> Self {
+		FaultInjector { inner, fault: None }
+	}
+
+	/// Fail the `nth` operation of kind `op` from now with `error`
+	///
+	/// An `nth` of zero is treated as one, failing the next operation.
+	pub fn fail(&mut self, op: Operation, nth: usize, error: E) {
+		self.fault = Some((op, nth.max(1), error));
+	}
+
+	/// Whether a scheduled fault is still to be injected
+	pub fn is_pending(&self) -> bool {
+		self.fault.is_some()
+	}
+
+	/// Remove the scheduled fault, returning whether one was pending
+	pub fn clear(&mut self) -> bool {
+		self.fault.take().is_some()
+	}
+
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.inner
+	}
+
+	/// Count an operation of kind `op`, returning the error if it's scheduled for it
+	fn check(&mut self, op: Operation) -> nb::Result<(), E> {
+		match &mut self.fault {
+			Some((kind, nth, _)) if *kind == op => {
+				*nth -= 1;
+				if *nth == 0 {
+					if let Some((_, _, error)) = self.fault.take() {
+						return Err(nb::Error::Other(error));
+					}
+				}
+				Ok(())
+			}
+			_ => Ok(()),
+		}
+	}
+}
+
+impl<T, Word, U, E> SingleRead<Word, U> for FaultInjector<T, E>
+where
+	T: SingleRead<Word, U, Error = E>,
+{
+	type Error = E;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		self.check(Operation::Read)?;
+		self.inner.try_read(address)
+	}
+}
+
+impl<T, Word, U, E> SingleWrite<Word, U> for FaultInjector<T, E>
+where
+	T: SingleWrite<Word, U, Error = E>,
+{
+	type Error = E;
+
+	fn try_write(&mut self, address: Address<U>, word: Word) -> nb::Result<(), Self::Error> {
+		self.check(Operation::Write)?;
+		self.inner.try_write(address, word)
+	}
+}
+
+impl<T, Word, U, E> MultiRead<Word, U> for FaultInjector<T, E>
+where
+	T: MultiRead<Word, U, Error = E>,
+{
+	type Error = E;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.check(Operation::Read)?;
+		self.inner.try_read_slice(address, buf)
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		self.check(Operation::Read)?;
+		self.inner.try_read_partial(address, buf)
+	}
+}
+
+impl<T, Word, U, E> MultiWrite<Word, U> for FaultInjector<T, E>
+where
+	T: MultiWrite<Word, U, Error = E>,
+{
+	type Error = E;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.check(Operation::Write)?;
+		self.inner.try_write_slice(address, buf)
+	}
+}
+
+impl<T, U, E> ErasePage<U> for FaultInjector<T, E>
+where
+	T: ErasePage<U, Error = E>,
+{
+	type Error = E;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		self.check(Operation::Erase)?;
+		self.inner.try_erase_page(page)
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		self.check(Operation::Erase)?;
+		self.inner.try_erase_address(address)
+	}
+}
+
+impl<T, Word, U, E> StorageSize<Word, U> for FaultInjector<T, E>
+where
+	T: StorageSize<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		self.inner.try_start_address()
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner.try_total_size()
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner.try_page_size(address)
+	}
+}
+
+impl<T, E> Flush for FaultInjector<T, E>
+where
+	T: Flush,
+{
+	type Error = T::Error;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.inner.try_flush()
+	}
+}
+
+impl<T, E> ReadAccess for FaultInjector<T, E>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.inner.access_pattern()
+	}
+}
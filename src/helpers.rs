@@ -0,0 +1,1913 @@
+//! Helpers
+//!
+//! Generic algorithms built on top of the storage traits.
+
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, AddressRange, Banked, EccResult, EccStatus, ErasePage,
+	ErrorKind, MaxTransfer, MultiRead, MultiWrite, Page, ReadAccess, Semantics, SingleRead,
+	StorageError, StorageSize, StorageWord, Timing, WriteSemantics,
+};
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::ops::{Add, Div, Rem, Sub};
+
+/// Errors returned by the helpers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// The data read back differs from the data written, starting at `mismatch_index`
+	Mismatch {
+		/// Index of the first word that differs
+		mismatch_index: usize,
+	},
+	/// A caller provided buffer is too small for the operation
+	BufferTooSmall,
+	/// The data doesn't fit in the remaining space of the page
+	CrossesPage,
+	/// A length doesn't fit in the address type, or an offset doesn't fit in a `usize`
+	Overflow,
+	/// The stored checksum doesn't match the data
+	Crc,
+	/// The access is not within the bounds of the device
+	OutOfBounds,
+	/// An address isn't on a page boundary
+	NotAligned,
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::OutOfBounds => ErrorKind::OutOfBounds,
+			Error::NotAligned => ErrorKind::NotAligned,
+			_ => ErrorKind::Other,
+		}
+	}
+}
+
+impl<E> From<E> for Error<E> {
+	fn from(e: E) -> Self {
+		Error::Device(e)
+	}
+}
+
+/// The offset spanning `len` words, or `None` if `len` doesn't fit in `U`
+pub(crate) fn offset_from_len<U: TryFrom<usize>>(len: usize) -> Option<AddressOffset<U>> {
+	U::try_from(len).ok().map(AddressOffset)
+}
+
+/// The number of words spanned by `offset`, or `None` if it doesn't fit in a `usize`
+pub(crate) fn len_from_offset<U>(offset: AddressOffset<U>) -> Option<usize>
+where
+	usize: TryFrom<U>,
+{
+	usize::try_from(offset.0).ok()
+}
+
+/// Fill `buf` by reading one word at a time, starting at `address`.
+///
+/// The address is advanced by one word after each read. A `WouldBlock` from
+/// the device retries the current word, so this never returns `WouldBlock`
+/// itself.
+///
+/// This allows a [`MultiRead`](crate::storage::MultiRead) implementation to be
+/// expressed in terms of [`SingleRead`]. It issues one transaction per word,
+/// so devices with an optimized method of reading multiple words should
+/// implement `try_read_slice` directly instead.
+///
+/// ```
+/// use embedded_storage::helpers::read_slice_via_single;
+/// use embedded_storage::storage::{Address, MultiRead, SingleRead};
+///
+/// // Blocks on every other read
+/// struct Device {
+///     busy: bool,
+/// }
+///
+/// impl SingleRead<u8, u32> for Device {
+///     type Error = ();
+///
+///     fn try_read(&mut self, address: Address<u32>) -> nb::Result<u8, Self::Error> {
+///         self.busy = !self.busy;
+///         if self.busy {
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         Ok(address.0 as u8)
+///     }
+/// }
+///
+/// impl MultiRead<u8, u32> for Device {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         read_slice_via_single(self, address, buf)
+///     }
+/// }
+///
+/// let mut buf = [0u8; 4];
+/// Device { busy: false }.try_read_slice(Address(0x10), &mut buf).unwrap();
+/// assert_eq!(buf, [0x10, 0x11, 0x12, 0x13]);
+/// ```
+pub fn read_slice_via_single<T, Word, U>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [Word],
+) -> nb::Result<(), T::Error>
+where
+	T: SingleRead<Word, U> + ?Sized,
+	U: Add<U, Output = U> + From<u8> + Copy,
+{
+	let mut address = address;
+	for word in buf.iter_mut() {
+		*word = nb::block!(dev.try_read(address))?;
+		address += AddressOffset(U::from(1));
+	}
+	Ok(())
+}
+
+/// Implements [`MultiRead`] for a [`SingleRead`] device, reading one word at a time.
+///
+/// The newtype avoids overlapping with the implementations of devices that
+/// provide `MultiRead` themselves. Reads go through
+/// [`read_slice_via_single`], so addresses advance by one word per read.
+/// `SingleRead` is forwarded unchanged.
+///
+/// ```
+/// use embedded_storage::helpers::DeriveMulti;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(4), &mut [1, 2, 3, 4]).unwrap();
+///
+/// let mut storage = DeriveMulti(storage);
+/// let mut buf = [0u8; 6];
+/// storage.try_read_slice(Address(3), &mut buf).unwrap();
+/// assert_eq!(buf, [0xFF, 1, 2, 3, 4, 0xFF]);
+/// ```
+pub struct DeriveMulti<T>(pub T);
+
+impl<T> DeriveMulti<T> {
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T, Word, U> SingleRead<Word, U> for DeriveMulti<T>
+where
+	T: SingleRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		self.0.try_read(address)
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for DeriveMulti<T>
+where
+	T: SingleRead<Word, U>,
+	U: Add<U, Output = U> + From<u8> + Copy,
+{
+	type Error = T::Error;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		read_slice_via_single(&mut self.0, address, buf)
+	}
+}
+
+impl<T> ReadAccess for DeriveMulti<T>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.0.access_pattern()
+	}
+}
+
+/// Implements [`SingleRead`] for a [`MultiRead`] device, reading a one word slice.
+///
+/// `MultiRead` is forwarded unchanged.
+///
+/// ```
+/// use embedded_storage::helpers::DeriveSingle;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiWrite, SingleRead};
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(4), &mut [1, 2]).unwrap();
+///
+/// let mut storage = DeriveSingle(storage);
+/// assert_eq!(storage.try_read(Address(4)), Ok(1));
+/// assert_eq!(storage.try_read(Address(5)), Ok(2));
+/// assert_eq!(storage.try_read(Address(6)), Ok(0xFF));
+/// ```
+pub struct DeriveSingle<T>(pub T);
+
+impl<T> DeriveSingle<T> {
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T, Word, U> SingleRead<Word, U> for DeriveSingle<T>
+where
+	T: MultiRead<Word, U>,
+	Word: Default,
+{
+	type Error = T::Error;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		let mut buf = [Word::default()];
+		self.0.try_read_slice(address, &mut buf)?;
+		let [word] = buf;
+		Ok(word)
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for DeriveSingle<T>
+where
+	T: MultiRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.0.try_read_slice(address, buf)
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		self.0.try_read_partial(address, buf)
+	}
+}
+
+impl<T> ReadAccess for DeriveSingle<T>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.0.access_pattern()
+	}
+}
+
+/// Fill `buf` from `address` with reads of at most `chunk_len` words each.
+///
+/// This suits bus controllers with a maximum transfer size. The last read is
+/// shorter if `buf.len()` isn't a multiple of `chunk_len`. A `WouldBlock` from
+/// the device retries the current chunk.
+///
+/// Devices declaring their limit through [`MaxTransfer`] can be read with
+/// [`read_chunked_limited`] instead. This function can't pick the limit up
+/// itself, as choosing a different implementation for the devices that
+/// implement `MaxTransfer` would need specialization, which stable Rust
+/// doesn't have.
+///
+/// Panics if `chunk_len` is zero.
+///
+/// ```
+/// use embedded_storage::helpers::read_chunked;
+/// use embedded_storage::storage::{Address, MultiRead};
+///
+/// // Transfers at most 4 words, and blocks on every other transfer
+/// struct Device {
+///     busy: bool,
+///     transfers: usize,
+/// }
+///
+/// impl MultiRead<u8, u32> for Device {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         assert!(buf.len() <= 4);
+///         self.busy = !self.busy;
+///         if self.busy {
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         for (i, word) in buf.iter_mut().enumerate() {
+///             *word = address.0 as u8 + i as u8;
+///         }
+///         self.transfers += 1;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut device = Device { busy: false, transfers: 0 };
+/// let mut buf = [0u8; 10];
+/// read_chunked(&mut device, Address(0x10), &mut buf, 4).unwrap();
+/// assert_eq!(buf, [0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19]);
+/// assert_eq!(device.transfers, 3);
+/// ```
+///
+/// Chunks that don't fit in the address type return [`Error::Overflow`]
+/// without reading.
+///
+/// ```
+/// use embedded_storage::helpers::{read_chunked, Error};
+/// use embedded_storage::storage::{Address, MultiRead};
+///
+/// struct Device;
+///
+/// impl MultiRead<u8, u16> for Device {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, _address: Address<u16>, _buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         unreachable!()
+///     }
+/// }
+///
+/// let mut buf = vec![0u8; 0x1_0000];
+/// assert_eq!(read_chunked(&mut Device, Address(0), &mut buf, 0x1_0000), Err(Error::Overflow));
+/// ```
+pub fn read_chunked<T, Word, U>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [Word],
+	chunk_len: usize,
+) -> Result<(), Error<T::Error>>
+where
+	T: MultiRead<Word, U> + ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	let mut address = address;
+	for chunk in buf.chunks_mut(chunk_len) {
+		let len = offset_from_len(chunk.len()).ok_or(Error::Overflow)?;
+		nb::block!(dev.try_read_slice(address, chunk))?;
+		address += len;
+	}
+	Ok(())
+}
+
+/// [`read_chunked`], with chunks of at most [`MaxTransfer::MAX_TRANSFER_WORDS`] words.
+///
+/// ```
+/// use embedded_storage::helpers::read_chunked_limited;
+/// use embedded_storage::storage::{Address, MaxTransfer, MultiRead};
+///
+/// // A bus moving at most 4 bytes at a time
+/// struct Device {
+///     transfers: usize,
+/// }
+///
+/// impl MaxTransfer for Device {
+///     const MAX_TRANSFER_WORDS: usize = 4;
+/// }
+///
+/// impl MultiRead<u8, u32> for Device {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         if buf.len() > Self::MAX_TRANSFER_WORDS {
+///             return Err(nb::Error::Other(()));
+///         }
+///         for (i, byte) in buf.iter_mut().enumerate() {
+///             *byte = address.0 as u8 + i as u8;
+///         }
+///         self.transfers += 1;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut device = Device { transfers: 0 };
+/// let mut buf = [0u8; 10];
+/// read_chunked_limited(&mut device, Address(0), &mut buf, 16).unwrap();
+/// assert_eq!(buf, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+/// assert_eq!(device.transfers, 3);
+/// ```
+pub fn read_chunked_limited<T, Word, U>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [Word],
+	chunk_len: usize,
+) -> Result<(), Error<T::Error>>
+where
+	T: MaxTransfer + MultiRead<Word, U> + ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	let chunk_len = chunk_len.min(T::MAX_TRANSFER_WORDS).max(1);
+	read_chunked(dev, address, buf, chunk_len)
+}
+
+/// Read words starting at `address` into `buf` until `sentinel`, returning the number of words before it.
+///
+/// Words are read one at a time, so nothing after the sentinel is read, and
+/// a sentinel just before the end of the device is found. Nothing beyond
+/// `buf.len()` words is read: if the sentinel isn't found, `buf` is filled
+/// and its length is returned.
+///
+/// ```
+/// use embedded_storage::helpers::read_until;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiWrite};
+///
+/// let mut storage = MemStorage::<64>::new(16);
+/// storage.try_write_slice(Address(4), &mut b"hello, flash".clone()).unwrap();
+///
+/// // Terminated by the erased byte after the string
+/// let mut buf = [0u8; 32];
+/// assert_eq!(read_until(&mut storage, Address(4), 0xFF, &mut buf), Ok(12));
+/// assert_eq!(&buf[..12], b"hello, flash");
+///
+/// // The buffer is filled before finding the sentinel
+/// let mut buf = [0u8; 5];
+/// assert_eq!(read_until(&mut storage, Address(4), 0xFF, &mut buf), Ok(5));
+/// assert_eq!(&buf, b"hello");
+///
+/// // Terminated by the last byte of the device
+/// storage.try_write_slice(Address(60), &mut b"abc".clone()).unwrap();
+/// let mut buf = [0u8; 32];
+/// assert_eq!(read_until(&mut storage, Address(60), 0xFF, &mut buf), Ok(3));
+/// assert_eq!(&buf[..3], b"abc");
+/// ```
+pub fn read_until<T, Word, U>(
+	dev: &mut T,
+	address: Address<U>,
+	sentinel: Word,
+	buf: &mut [Word],
+) -> Result<usize, Error<T::Error>>
+where
+	T: MultiRead<Word, U> + ?Sized,
+	Word: PartialEq,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	let one = offset_from_len(1).ok_or(Error::Overflow)?;
+	let mut address = address;
+	for count in 0..buf.len() {
+		if count > 0 {
+			address += one;
+		}
+		nb::block!(dev.try_read_slice(address, &mut buf[count..=count]))?;
+		if buf[count] == sentinel {
+			return Ok(count);
+		}
+	}
+	Ok(buf.len())
+}
+
+/// Fill a byte buffer from a device with wider words, starting at the word at `address`.
+///
+/// Words are read whole and unpacked in little endian order, see
+/// [`ByteView`](crate::view::ByteView) for other orders. When the length of
+/// `buf` isn't a multiple of the word size, only the requested bytes of the
+/// last word are copied, and no word past it is read.
+///
+/// ```
+/// use embedded_storage::helpers::read_bytes;
+/// use embedded_storage::storage::{Address, MultiRead};
+///
+/// // A device storing one 32 bit word
+/// struct Device(u32);
+///
+/// impl MultiRead<u32, u32> for Device {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, address: Address<u32>, buf: &mut [u32]) -> nb::Result<(), Self::Error> {
+///         if address.0 as usize + buf.len() > 1 {
+///             return Err(nb::Error::Other(()));
+///         }
+///         buf.fill(self.0);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut buf = [0u8; 3];
+/// read_bytes(&mut Device(0x44332211), Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [0x11, 0x22, 0x33]);
+/// assert!(read_bytes(&mut Device(0x44332211), Address(0), &mut [0u8; 5]).is_err());
+/// ```
+pub fn read_bytes<T, Word, U>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [u8],
+) -> Result<(), Error<T::Error>>
+where
+	T: MultiRead<Word, U> + ?Sized,
+	Word: StorageWord,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	const CHUNK: usize = 16;
+	let mut address = address;
+	let mut words = [Word::from_le_bytes(Default::default()); CHUNK];
+	for chunk in buf.chunks_mut(CHUNK * Word::BYTES) {
+		let len = (chunk.len() + Word::BYTES - 1) / Word::BYTES;
+		nb::block!(dev.try_read_slice(address, &mut words[..len]))?;
+		for (bytes, word) in chunk.chunks_mut(Word::BYTES).zip(words.iter()) {
+			bytes.copy_from_slice(&word.to_le_bytes().as_ref()[..bytes.len()]);
+		}
+		address += offset_from_len(len).ok_or(Error::Overflow)?;
+	}
+	Ok(())
+}
+
+/// Write `buf` at `address`, then read it back into `scratch` and compare.
+///
+/// Flash can only clear bits, so a worn cell or a write over data that wasn't
+/// erased shows up as a difference when reading back. `scratch` must be at
+/// least as long as `buf`.
+///
+/// ```
+/// use embedded_storage::helpers::{write_verify, Error};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::Address;
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// let mut scratch = [0u8; 4];
+///
+/// write_verify(&mut storage, Address(0), &mut [1, 2, 3, 4], &mut scratch).unwrap();
+///
+/// // A stuck cell at address 5 fails to program
+/// storage.as_mut_slice()[5] = 0x00;
+/// assert_eq!(
+///     write_verify(&mut storage, Address(4), &mut [1, 2, 3, 4], &mut scratch),
+///     Err(Error::Mismatch { mismatch_index: 1 })
+/// );
+/// ```
+pub fn write_verify<T, Word, U, E>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [Word],
+	scratch: &mut [Word],
+) -> Result<(), Error<E>>
+where
+	T: MultiRead<Word, U, Error = E> + MultiWrite<Word, U, Error = E> + ?Sized,
+	Word: PartialEq,
+	U: Copy,
+{
+	let scratch = scratch.get_mut(..buf.len()).ok_or(Error::BufferTooSmall)?;
+	nb::block!(dev.try_write_slice(address, buf))?;
+	nb::block!(dev.try_read_slice(address, scratch))?;
+	match buf.iter().zip(scratch.iter()).position(|(a, b)| a != b) {
+		Some(mismatch_index) => Err(Error::Mismatch { mismatch_index }),
+		None => Ok(()),
+	}
+}
+
+/// Iterator over the pages of a device, yielding each page with its start address.
+///
+/// Pages are numbered from zero at the start address of the device. The page
+/// size is queried for every page, so devices with pages of different sizes are
+/// iterated correctly. If querying the page size fails, iteration stops and the
+/// error is available from [`PageIter::error`].
+///
+/// ```
+/// use embedded_storage::helpers::PageIter;
+/// use embedded_storage::storage::{Address, AddressOffset, Page, StorageSize};
+///
+/// // Four pages of 4 words followed by two pages of 8 words
+/// struct Device;
+///
+/// impl StorageSize<u8, u32> for Device {
+///     type Error = ();
+///
+///     fn try_start_address(&mut self) -> nb::Result<Address<u32>, Self::Error> {
+///         Ok(Address(0x100))
+///     }
+///
+///     fn try_total_size(&mut self) -> nb::Result<AddressOffset<u32>, Self::Error> {
+///         Ok(AddressOffset(32))
+///     }
+///
+///     fn try_page_size(&mut self, address: Address<u32>) -> nb::Result<AddressOffset<u32>, Self::Error> {
+///         Ok(AddressOffset(if address.0 < 0x110 { 4 } else { 8 }))
+///     }
+/// }
+///
+/// let mut device = Device;
+/// let mut pages = PageIter::new(&mut device).unwrap();
+/// assert_eq!(pages.next(), Some((Page(0), Address(0x100))));
+/// assert_eq!(pages.next(), Some((Page(1), Address(0x104))));
+/// assert_eq!(pages.next(), Some((Page(2), Address(0x108))));
+/// assert_eq!(pages.next(), Some((Page(3), Address(0x10C))));
+/// assert_eq!(pages.next(), Some((Page(4), Address(0x110))));
+/// assert_eq!(pages.next(), Some((Page(5), Address(0x118))));
+/// assert_eq!(pages.next(), None);
+/// assert!(pages.error().is_none());
+/// ```
+pub struct PageIter<'a, T, Word, U>
+where
+	T: StorageSize<Word, U> + ?Sized,
+{
+	dev: &'a mut T,
+	address: Address<U>,
+	end: Address<U>,
+	page: U,
+	error: Option<T::Error>,
+	_word: PhantomData<Word>,
+}
+
+impl<'a, T, Word, U> PageIter<'a, T, Word, U>
+where
+	T: StorageSize<Word, U> + ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+{
+	/// Create an iterator over all pages of `dev`
+	pub fn new(dev: &'a mut T) -> Result<Self, T::Error> {
+		let start = nb::block!(dev.try_start_address())?;
+		let size = nb::block!(dev.try_total_size())?;
+		Ok(PageIter {
+			dev,
+			address: start,
+			end: &start + &size,
+			page: U::from(0),
+			error: None,
+			_word: PhantomData,
+		})
+	}
+
+	/// The error that stopped the iteration, if any
+	pub fn error(&self) -> Option<&T::Error> {
+		self.error.as_ref()
+	}
+
+	/// Consume the iterator, returning the error that stopped the iteration, if any
+	pub fn into_error(self) -> Option<T::Error> {
+		self.error
+	}
+}
+
+impl<'a, T, Word, U> Iterator for PageIter<'a, T, Word, U>
+where
+	T: StorageSize<Word, U> + ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+{
+	type Item = (Page<U>, Address<U>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.error.is_some() || self.address >= self.end {
+			return None;
+		}
+		let size = match nb::block!(self.dev.try_page_size(self.address)) {
+			Ok(size) => size,
+			Err(e) => {
+				self.error = Some(e);
+				return None;
+			}
+		};
+		if size.0 == U::from(0) {
+			// A zero sized page would never advance
+			self.address = self.end;
+			return None;
+		}
+		let item = (Page(self.page), self.address);
+		self.address += size;
+		self.page = self.page + U::from(1);
+		Some(item)
+	}
+}
+
+/// Iterate over the runs of non erased bytes in `range`, stopping at the first erased page.
+///
+/// See [`NonErasedIter`].
+pub fn non_erased_iter<'a, T, U, E>(
+	dev: &'a mut T,
+	range: AddressRange<U>,
+	erase_byte: u8,
+	scratch: &'a mut [u8],
+) -> NonErasedIter<'a, T, U, E>
+where
+	T: MultiRead<u8, U, Error = E> + StorageSize<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Ord + Copy,
+	usize: TryFrom<U>,
+{
+	NonErasedIter {
+		dev,
+		start: range.start,
+		len: len_from_offset(range.len),
+		erase_byte,
+		scratch,
+		offset: 0,
+		page_end: 0,
+		page_written: true,
+		filled: 0,
+		index: 0,
+		error: None,
+	}
+}
+
+/// Iterator over the runs of non erased bytes in a range, created by [`non_erased_iter`].
+///
+/// Each item is a range of consecutive bytes that differ from the erase
+/// byte. The bytes are read in chunks of up to `scratch.len()` bytes. As in
+/// an append only log, data is assumed to end at the first page that is
+/// entirely erased, so iteration stops there without reading further.
+/// Pages are walked from the start of the range, which should be the start
+/// of a page.
+///
+/// Runs only tell apart erased and written bytes, so they suit finding the
+/// written parts of a region, for example to back it up or check it is
+/// blank. Record formats whose data can hold the erase byte, such as those
+/// of [`log`](crate::log) and [`kv`](crate::kv), need their headers to find
+/// the end of a record, and walk them instead.
+///
+/// If reading fails, iteration stops and the error is available from
+/// [`NonErasedIter::error`]. An empty `scratch` stops with
+/// [`Error::BufferTooSmall`].
+///
+/// ```
+/// use embedded_storage::helpers::non_erased_iter;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MultiWrite};
+///
+/// let mut storage = MemStorage::<64>::new(16);
+/// storage.try_write_slice(Address(2), &mut [1, 2, 3]).unwrap();
+/// storage.try_write_slice(Address(14), &mut [4, 5, 6, 7]).unwrap();
+/// // Beyond the erased third page, so never reached
+/// storage.try_write_slice(Address(60), &mut [8]).unwrap();
+///
+/// let range = AddressRange::new(Address(0), AddressOffset(64));
+/// let mut scratch = [0u8; 5];
+/// let mut runs = non_erased_iter(&mut storage, range, 0xFF, &mut scratch);
+/// assert_eq!(runs.next(), Some(AddressRange::new(Address(2), AddressOffset(3))));
+/// assert_eq!(runs.next(), Some(AddressRange::new(Address(14), AddressOffset(4))));
+/// assert_eq!(runs.next(), None);
+/// assert!(runs.error().is_none());
+/// ```
+pub struct NonErasedIter<'a, T: ?Sized, U, E> {
+	dev: &'a mut T,
+	start: Address<U>,
+	len: Option<usize>,
+	erase_byte: u8,
+	scratch: &'a mut [u8],
+	/// The offset of the next byte to examine
+	offset: usize,
+	page_end: usize,
+	page_written: bool,
+	/// The number of bytes read into `scratch`, and the index of the next one to examine
+	filled: usize,
+	index: usize,
+	error: Option<Error<E>>,
+}
+
+impl<'a, T, U, E> NonErasedIter<'a, T, U, E>
+where
+	T: MultiRead<u8, U, Error = E> + StorageSize<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Ord + Copy,
+	usize: TryFrom<U>,
+{
+	/// The error that stopped the iteration, if any
+	pub fn error(&self) -> Option<&Error<E>> {
+		self.error.as_ref()
+	}
+
+	fn address(&self, offset: usize) -> Result<Address<U>, Error<E>> {
+		Ok(&self.start + &offset_from_len(offset).ok_or(Error::Overflow)?)
+	}
+
+	/// The range of the next run, or `None` at the end of the data
+	fn scan(&mut self) -> Result<Option<AddressRange<U>>, Error<E>> {
+		let len = self.len.ok_or(Error::Overflow)?;
+		let mut run: Option<usize> = None;
+		loop {
+			if self.offset == self.page_end {
+				if !self.page_written {
+					// The rest of the range is assumed to be erased
+					self.len = Some(self.offset);
+					return Ok(None);
+				}
+				if self.offset == len {
+					break;
+				}
+				let size = nb::block!(self.dev.try_page_size(self.address(self.offset)?))?;
+				let size = len_from_offset(size).ok_or(Error::Overflow)?;
+				if size == 0 {
+					// A zero sized page would never advance
+					return Err(Error::OutOfBounds);
+				}
+				self.page_end = (self.offset + size).min(len);
+				self.page_written = false;
+			}
+			if self.index == self.filled {
+				if self.scratch.is_empty() {
+					return Err(Error::BufferTooSmall);
+				}
+				let n = (self.page_end - self.offset).min(self.scratch.len());
+				let address = self.address(self.offset)?;
+				nb::block!(self.dev.try_read_slice(address, &mut self.scratch[..n]))?;
+				self.filled = n;
+				self.index = 0;
+			}
+			if self.scratch[self.index] != self.erase_byte {
+				self.page_written = true;
+				run.get_or_insert(self.offset);
+			} else if run.is_some() {
+				break;
+			}
+			self.index += 1;
+			self.offset += 1;
+		}
+		match run {
+			Some(from) => Ok(Some(AddressRange::new(
+				self.address(from)?,
+				offset_from_len(self.offset - from).ok_or(Error::Overflow)?,
+			))),
+			None => Ok(None),
+		}
+	}
+}
+
+impl<'a, T, U, E> Iterator for NonErasedIter<'a, T, U, E>
+where
+	T: MultiRead<u8, U, Error = E> + StorageSize<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Ord + Copy,
+	usize: TryFrom<U>,
+{
+	type Item = AddressRange<U>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.error.is_some() {
+			return None;
+		}
+		match self.scan() {
+			Ok(run) => run,
+			Err(e) => {
+				self.error = Some(e);
+				None
+			}
+		}
+	}
+}
+
+/// Update part of a page by reading it into `scratch`, modifying it, erasing the page and writing it back.
+///
+/// `address` must be the start of a page. The page is read into the start of
+/// `scratch`, which must be at least as long as the page reported by
+/// `try_page_size`, and `f` is called on exactly one page worth of words.
+///
+/// ```
+/// use embedded_storage::helpers::{modify_page, Error};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiWrite};
+///
+/// let mut storage = MemStorage::<32>::new(16);
+/// storage.try_write_slice(Address(16), &mut [1, 2, 3, 4]).unwrap();
+///
+/// let mut scratch = [0u8; 16];
+/// modify_page(&mut storage, Address(16), &mut scratch, |page| page[1] = 0xAA).unwrap();
+/// assert_eq!(storage.as_slice()[16..20], [1, 0xAA, 3, 4]);
+///
+/// let mut small = [0u8; 8];
+/// assert_eq!(
+///     modify_page(&mut storage, Address(16), &mut small, |_| ()),
+///     Err(Error::BufferTooSmall)
+/// );
+/// ```
+pub fn modify_page<T, Word, U, E, F>(
+	dev: &mut T,
+	address: Address<U>,
+	scratch: &mut [Word],
+	mut f: F,
+) -> Result<(), Error<E>>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>
+		+ ?Sized,
+	U: Copy,
+	usize: TryFrom<U>,
+	F: FnMut(&mut [Word]),
+{
+	let page_size = nb::block!(dev.try_page_size(address))?;
+	let len = len_from_offset(page_size).ok_or(Error::Overflow)?;
+	let page = scratch.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+	nb::block!(dev.try_read_slice(address, page))?;
+	f(page);
+	nb::block!(dev.try_erase_address(address))?;
+	nb::block!(dev.try_write_slice(address, page))?;
+	Ok(())
+}
+
+/// Write `buf` at `address`, erasing only the pages where the target bytes aren't erased.
+///
+/// Devices reporting [`Semantics::Arbitrary`] are written directly, one page
+/// at a time, without erasing. Otherwise, for every page the write touches,
+/// the target bytes are read into `scratch`. If they all are `erase_byte` the
+/// data is written directly, if not the page is updated with
+/// [`modify_page`], so `scratch` must be at least as long as the largest page
+/// written to. Returns [`Error::OutOfBounds`] without accessing the device if
+/// the write isn't within it.
+///
+/// ```
+/// use embedded_storage::helpers::write_smart;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, Page, Wear};
+/// use embedded_storage::wear::WearTracking;
+///
+/// let mut storage = WearTracking::<_, _, 4>::new(MemStorage::<64>::new(16)).unwrap();
+/// let mut scratch = [0u8; 16];
+///
+/// // Page 1 is erased, so it is written directly
+/// write_smart(&mut storage, Address(20), &mut [1, 2, 3], 0xFF, &mut scratch).unwrap();
+/// assert_eq!(storage.try_erase_count(Page(1)), Ok(0));
+///
+/// // Overwriting the data erases the page, keeping the rest of it
+/// write_smart(&mut storage, Address(21), &mut [4], 0xFF, &mut scratch).unwrap();
+/// assert_eq!(storage.try_erase_count(Page(1)), Ok(1));
+///
+/// // Only the dirty page of a write spanning two pages is erased
+/// write_smart(&mut storage, Address(22), &mut [5; 12], 0xFF, &mut scratch).unwrap();
+/// assert_eq!(storage.try_erase_count(Page(1)), Ok(2));
+/// assert_eq!(storage.try_erase_count(Page(2)), Ok(0));
+///
+/// let storage = storage.into_inner();
+/// assert_eq!(storage.as_slice()[19..36], [0xFF, 1, 4, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 5, 0xFF, 0xFF]);
+/// ```
+///
+/// On a device erasing to `0x00`, bytes programmed to `0xFF` aren't mistaken
+/// for erased ones:
+///
+/// ```
+/// use embedded_storage::helpers::write_smart;
+/// use embedded_storage::counting::Counting;
+/// use embedded_storage::mem::MemStorageBuilder;
+/// use embedded_storage::storage::{Address, MultiWrite};
+///
+/// let mut storage = MemStorageBuilder::<32>::new().page_size(16).erase_byte(0x00).build();
+/// storage.try_write_slice(Address(0), &mut [0xFF, 0xFF]).unwrap();
+/// let mut storage = Counting::new(storage);
+/// let mut scratch = [0u8; 16];
+///
+/// write_smart(&mut storage, Address(0), &mut [1, 2], 0x00, &mut scratch).unwrap();
+/// assert_eq!(storage.erase_ops, 1);
+///
+/// // The second page is erased, so it is written directly
+/// write_smart(&mut storage, Address(16), &mut [3], 0x00, &mut scratch).unwrap();
+/// assert_eq!(storage.erase_ops, 1);
+///
+/// let storage = storage.into_inner();
+/// assert_eq!(storage.as_slice()[..3], [1, 2, 0x00]);
+/// assert_eq!(storage.as_slice()[16..18], [3, 0x00]);
+/// ```
+///
+/// A RAM backed device needs no erase at all:
+///
+/// ```
+/// use embedded_storage::helpers::write_smart;
+/// use embedded_storage::storage::{
+///     Address, AddressOffset, ErasePage, MultiRead, MultiWrite, Page, Semantics, StorageSize,
+///     Wear, WriteSemantics,
+/// };
+/// use embedded_storage::wear::WearTracking;
+///
+/// struct Ram([u8; 32]);
+///
+/// impl WriteSemantics for Ram {
+///     fn write_semantics(&self) -> Semantics {
+///         Semantics::Arbitrary
+///     }
+/// }
+/// # impl MultiRead<u8, usize> for Ram {
+/// #     type Error = ();
+/// #     fn try_read_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), ()> {
+/// #         buf.copy_from_slice(&self.0[address.0..address.0 + buf.len()]);
+/// #         Ok(())
+/// #     }
+/// # }
+/// # impl MultiWrite<u8, usize> for Ram {
+/// #     type Error = ();
+/// #     fn try_write_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), ()> {
+/// #         self.0[address.0..address.0 + buf.len()].copy_from_slice(buf);
+/// #         Ok(())
+/// #     }
+/// # }
+/// # impl ErasePage<usize> for Ram {
+/// #     type Error = ();
+/// #     fn try_erase_page(&mut self, page: Page<usize>) -> nb::Result<(), ()> {
+/// #         self.0[page.0 * 16..page.0 * 16 + 16].fill(0);
+/// #         Ok(())
+/// #     }
+/// #     fn try_erase_address(&mut self, address: Address<usize>) -> nb::Result<(), ()> {
+/// #         self.try_erase_page(Page(address.0 / 16))
+/// #     }
+/// # }
+/// # impl StorageSize<u8, usize> for Ram {
+/// #     type Error = ();
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<usize>, ()> {
+/// #         Ok(Address(0))
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<usize>, ()> {
+/// #         Ok(AddressOffset(32))
+/// #     }
+/// #     fn try_page_size(&mut self, _address: Address<usize>) -> nb::Result<AddressOffset<usize>, ()> {
+/// #         Ok(AddressOffset(16))
+/// #     }
+/// # }
+///
+/// let mut storage = WearTracking::<_, _, 2>::new(Ram([0x55; 32])).unwrap();
+/// write_smart(&mut storage, Address(14), &mut [1, 2, 3, 4], 0xFF, &mut []).unwrap();
+/// assert_eq!(storage.try_erase_count(Page(0)), Ok(0));
+/// assert_eq!(storage.try_erase_count(Page(1)), Ok(0));
+/// assert_eq!(storage.into_inner().0[13..19], [0x55, 1, 2, 3, 4, 0x55]);
+/// ```
+pub fn write_smart<T, U, E>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [u8],
+	erase_byte: u8,
+	scratch: &mut [u8],
+) -> Result<(), Error<E>>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>
+		+ WriteSemantics
+		+ ?Sized,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + From<u8> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let arbitrary = dev.write_semantics() == Semantics::Arbitrary;
+	for_each_page_part(dev, address, buf, |dev, at, part| {
+		if arbitrary {
+			nb::block!(dev.try_write_slice(at.address, part))?;
+			return Ok(());
+		}
+		let target = scratch.get_mut(..part.len()).ok_or(Error::BufferTooSmall)?;
+		nb::block!(dev.try_read_slice(at.address, target))?;
+		if target.iter().all(|byte| *byte == erase_byte) {
+			nb::block!(dev.try_write_slice(at.address, part))?;
+		} else {
+			modify_page(dev, at.page, scratch, |words| {
+				words[at.offset..at.offset + part.len()].copy_from_slice(part)
+			})?;
+		}
+		Ok(())
+	})
+}
+
+/// Write `buf` at any `address`, erasing and reprogramming the pages it touches.
+///
+/// Only the pages overlapping the write are erased. Pages it covers
+/// entirely are erased and written directly, while the first and last pages,
+/// when the write only covers part of them, are updated with
+/// [`modify_page`], so `scratch` must hold one of them. See [`write_smart`]
+/// to also skip erasing pages whose target bytes are already erased. Returns
+/// [`Error::OutOfBounds`] without accessing the device if the write isn't
+/// within it.
+///
+/// ```
+/// use embedded_storage::counting::Counting;
+/// use embedded_storage::helpers::write_unaligned;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::Address;
+///
+/// let mut storage = MemStorage::<64>::new(16);
+/// storage.as_mut_slice().fill(0x55);
+/// let mut storage = Counting::new(storage);
+/// let mut scratch = [0u8; 16];
+///
+/// // Within the second page
+/// write_unaligned(&mut storage, Address(20), &mut [1, 2, 3], &mut scratch).unwrap();
+/// assert_eq!(storage.erase_ops, 1);
+///
+/// // Across the boundary between the third and fourth pages
+/// storage.reset();
+/// write_unaligned(&mut storage, Address(42), &mut [9; 10], &mut scratch).unwrap();
+/// assert_eq!(storage.erase_ops, 2);
+///
+/// let storage = storage.into_inner();
+/// let data = storage.as_slice();
+/// assert_eq!(data[..20], [0x55; 20]);
+/// assert_eq!(data[20..24], [1, 2, 3, 0x55]);
+/// assert_eq!(data[24..42], [0x55; 18]);
+/// assert_eq!(data[42..52], [9; 10]);
+/// assert_eq!(data[52..], [0x55; 12]);
+/// ```
+pub fn write_unaligned<T, Word, U, E>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [Word],
+	scratch: &mut [Word],
+) -> Result<(), Error<E>>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>
+		+ ?Sized,
+	Word: Copy,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + From<u8> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	for_each_page_part(dev, address, buf, |dev, at, part| {
+		if at.offset == 0 && part.len() == at.room {
+			nb::block!(dev.try_erase_address(at.page))?;
+			nb::block!(dev.try_write_slice(at.page, part))?;
+		} else {
+			modify_page(dev, at.page, scratch, |words| {
+				words[at.offset..at.offset + part.len()].copy_from_slice(part)
+			})?;
+		}
+		Ok(())
+	})
+}
+
+/// Write `value` to every word of `range`, using `scratch` as the write buffer.
+///
+/// The range is written in chunks of `scratch.len()` words, the last chunk
+/// being shorter if the range isn't a multiple of it. `scratch` is filled with
+/// `value` before every write, so its initial contents don't matter. Returns
+/// [`Error::BufferTooSmall`] if `scratch` is empty and the range isn't.
+///
+/// For devices implementing [`MaxTransfer`], [`fill_limited`] also keeps the
+/// writes within the device limit. As with [`read_chunked`], the limit isn't
+/// applied here automatically because stable Rust has no specialization.
+///
+/// ```
+/// use embedded_storage::helpers::fill;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MultiRead};
+///
+/// let mut storage = MemStorage::<32>::new(16);
+/// let mut scratch = [0u8; 4];
+/// fill(&mut storage, AddressRange::new(Address(3), AddressOffset(10)), 0x00, &mut scratch).unwrap();
+///
+/// let mut buf = [0u8; 12];
+/// storage.try_read_slice(Address(2), &mut buf).unwrap();
+/// assert_eq!(buf, [0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF]);
+/// ```
+pub fn fill<T, Word, U>(
+	dev: &mut T,
+	range: AddressRange<U>,
+	value: Word,
+	scratch: &mut [Word],
+) -> Result<(), Error<T::Error>>
+where
+	T: MultiWrite<Word, U> + ?Sized,
+	Word: Copy,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let mut remaining = len_from_offset(range.len).ok_or(Error::Overflow)?;
+	if remaining > 0 && scratch.is_empty() {
+		return Err(Error::BufferTooSmall);
+	}
+	let mut address = range.start;
+	while remaining > 0 {
+		let len = remaining.min(scratch.len());
+		let chunk = &mut scratch[..len];
+		for word in chunk.iter_mut() {
+			*word = value;
+		}
+		nb::block!(dev.try_write_slice(address, chunk))?;
+		address += offset_from_len(len).ok_or(Error::Overflow)?;
+		remaining -= len;
+	}
+	Ok(())
+}
+
+/// [`fill`], writing at most [`MaxTransfer::MAX_TRANSFER_WORDS`] words at a time.
+///
+/// ```
+/// use embedded_storage::helpers::fill_limited;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MaxTransfer, MultiWrite};
+///
+/// struct Device {
+///     data: [u8; 16],
+///     transfers: usize,
+/// }
+///
+/// impl MaxTransfer for Device {
+///     const MAX_TRANSFER_WORDS: usize = 4;
+/// }
+///
+/// impl MultiWrite<u8, usize> for Device {
+///     type Error = ();
+///
+///     fn try_write_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         if buf.len() > Self::MAX_TRANSFER_WORDS {
+///             return Err(nb::Error::Other(()));
+///         }
+///         self.data[address.0..address.0 + buf.len()].copy_from_slice(buf);
+///         self.transfers += 1;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut device = Device { data: [0xFF; 16], transfers: 0 };
+/// let range = AddressRange::new(Address(2), AddressOffset(10));
+/// fill_limited(&mut device, range, 0x00, &mut [0u8; 8]).unwrap();
+/// assert_eq!(device.data[1..13], [0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF]);
+/// assert_eq!(device.transfers, 3);
+/// ```
+pub fn fill_limited<T, Word, U>(
+	dev: &mut T,
+	range: AddressRange<U>,
+	value: Word,
+	scratch: &mut [Word],
+) -> Result<(), Error<T::Error>>
+where
+	T: MaxTransfer + MultiWrite<Word, U> + ?Sized,
+	Word: Copy,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let len = scratch.len().min(T::MAX_TRANSFER_WORDS.max(1));
+	fill(dev, range, value, &mut scratch[..len])
+}
+
+/// Copy the whole contents of a device into `buf`, returning the number of words copied.
+///
+/// The device is read from its start address for its total size. Returns
+/// [`Error::BufferTooSmall`] without reading if `buf` is shorter than that.
+///
+/// ```
+/// use embedded_storage::helpers::{dump, restore, Error};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiWrite};
+///
+/// let mut golden = MemStorage::<32>::new(8);
+/// golden.try_write_slice(Address(6), &mut [1, 2, 3, 4]).unwrap();
+///
+/// let mut image = [0u8; 32];
+/// assert_eq!(dump(&mut golden, &mut image), Ok(32));
+/// assert_eq!(dump(&mut golden, &mut [0u8; 16]), Err(Error::BufferTooSmall));
+///
+/// let mut copy = MemStorage::<32>::new(8);
+/// copy.as_mut_slice().fill(0x55);
+/// restore(&mut copy, &mut image).unwrap();
+/// assert_eq!(copy.as_slice(), golden.as_slice());
+/// ```
+///
+/// A size that doesn't fit in a `usize`, such as a `u64` size above
+/// `u32::MAX` on a 32 bit target, returns [`Error::Overflow`].
+///
+/// ```
+/// use embedded_storage::helpers::{dump, Error};
+/// use embedded_storage::storage::{Address, AddressOffset, MultiRead, StorageSize};
+///
+/// // 8GB of storage
+/// struct Device;
+///
+/// impl MultiRead<u8, u64> for Device {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, _address: Address<u64>, _buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         unreachable!()
+///     }
+/// }
+///
+/// impl StorageSize<u8, u64> for Device {
+///     type Error = ();
+///
+///     fn try_start_address(&mut self) -> nb::Result<Address<u64>, Self::Error> {
+///         Ok(Address(0))
+///     }
+///
+///     fn try_total_size(&mut self) -> nb::Result<AddressOffset<u64>, Self::Error> {
+///         Ok(AddressOffset(1 << 33))
+///     }
+///
+///     fn try_page_size(&mut self, _address: Address<u64>) -> nb::Result<AddressOffset<u64>, Self::Error> {
+///         Ok(AddressOffset(4096))
+///     }
+/// }
+///
+/// let expected = if cfg!(target_pointer_width = "64") {
+///     Error::BufferTooSmall
+/// } else {
+///     Error::Overflow
+/// };
+/// assert_eq!(dump(&mut Device, &mut [0u8; 16]), Err(expected));
+/// ```
+pub fn dump<T, Word, U, E>(dev: &mut T, buf: &mut [Word]) -> Result<usize, Error<E>>
+where
+	T: MultiRead<Word, U, Error = E> + StorageSize<Word, U, Error = E> + ?Sized,
+	U: Copy,
+	usize: TryFrom<U>,
+{
+	let start = nb::block!(dev.try_start_address())?;
+	let size = nb::block!(dev.try_total_size())?;
+	let len = len_from_offset(size).ok_or(Error::Overflow)?;
+	let buf = buf.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+	nb::block!(dev.try_read_slice(start, buf))?;
+	Ok(len)
+}
+
+/// Write an image taken with [`dump`] back to a device, erasing every page before writing it.
+///
+/// The image is written from the start address of the device, page by page.
+/// Pages beyond the end of the image are left untouched. Returns
+/// [`Error::OutOfBounds`] if the image is larger than the device.
+pub fn restore<T, Word, U, E>(dev: &mut T, image: &mut [Word]) -> Result<(), Error<E>>
+where
+	T: MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>
+		+ ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let mut address = nb::block!(dev.try_start_address())?;
+	let size = nb::block!(dev.try_total_size())?;
+	if len_from_offset(size).map_or(false, |size| image.len() > size) {
+		return Err(Error::OutOfBounds);
+	}
+	let mut image = image;
+	while !image.is_empty() {
+		let page_size = nb::block!(dev.try_page_size(address))?;
+		let len = len_from_offset(page_size).map_or(image.len(), |len| len.min(image.len()));
+		if len == 0 {
+			// A zero sized page would never advance
+			return Err(Error::OutOfBounds);
+		}
+		let (page, rest) = image.split_at_mut(len);
+		nb::block!(dev.try_erase_address(address))?;
+		nb::block!(dev.try_write_slice(address, page))?;
+		address += offset_from_len(len).ok_or(Error::Overflow)?;
+		image = rest;
+	}
+	Ok(())
+}
+
+/// Erase every page in `range`, which must start and end on page boundaries.
+///
+/// Page boundaries are found by walking the pages from the start of the
+/// device, so devices with pages of different sizes are handled. The whole
+/// range is checked before anything is erased, returning
+/// [`Error::NotAligned`] if either end is within a page and
+/// [`Error::OutOfBounds`] if the range is not within the device.
+///
+/// ```
+/// use embedded_storage::helpers::{erase_range, Error};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange};
+///
+/// let mut storage = MemStorage::<64>::new(8);
+/// storage.as_mut_slice().fill(0);
+///
+/// erase_range(&mut storage, AddressRange::new(Address(16), AddressOffset(24))).unwrap();
+/// assert_eq!(storage.as_slice()[..16], [0; 16]);
+/// assert_eq!(storage.as_slice()[16..40], [0xFF; 24]);
+/// assert_eq!(storage.as_slice()[40..], [0; 24]);
+///
+/// assert_eq!(
+///     erase_range(&mut storage, AddressRange::new(Address(4), AddressOffset(8))),
+///     Err(Error::NotAligned)
+/// );
+/// assert_eq!(
+///     erase_range(&mut storage, AddressRange::new(Address(56), AddressOffset(16))),
+///     Err(Error::OutOfBounds)
+/// );
+/// ```
+pub fn erase_range<T, Word, U, E>(dev: &mut T, range: AddressRange<U>) -> Result<(), Error<E>>
+where
+	T: ErasePage<U, Error = E> + StorageSize<Word, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+{
+	count_pages(dev, range)?;
+	let end = range.end();
+	let mut address = range.start;
+	while address < end {
+		nb::block!(dev.try_erase_address(address))?;
+		address = next_page(dev, address)?;
+	}
+	Ok(())
+}
+
+/// Erase every page of the device, for devices without [`MassErase`](crate::storage::MassErase).
+///
+/// This is [`erase_range`] over the whole device.
+///
+/// ```
+/// use embedded_storage::helpers::erase_all;
+/// use embedded_storage::mem::MemStorage;
+///
+/// let mut storage = MemStorage::<32>::new(8);
+/// storage.as_mut_slice().fill(0);
+/// erase_all(&mut storage).unwrap();
+/// assert_eq!(storage.as_slice(), [0xFF; 32]);
+/// ```
+pub fn erase_all<T, Word, U, E>(dev: &mut T) -> Result<(), Error<E>>
+where
+	T: ErasePage<U, Error = E> + StorageSize<Word, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+{
+	let start = nb::block!(dev.try_start_address())?;
+	let size = nb::block!(dev.try_total_size())?;
+	erase_range(dev, AddressRange::new(start, size))
+}
+
+/// Find the bank of a [`Banked`] device holding `address`, and the offset of the address in it.
+///
+/// The first bank starts at the start address of the device. Returns
+/// [`Error::OutOfBounds`] for addresses before it or beyond the last bank.
+/// See [`Banked`] for an example.
+pub fn locate_bank<T, Word, U, E>(
+	dev: &mut T,
+	address: Address<U>,
+) -> Result<(usize, AddressOffset<U>), Error<E>>
+where
+	T: Banked<U> + StorageSize<Word, U, Error = E> + ?Sized,
+	U: Sub<U, Output = U> + Div<U, Output = U> + Rem<U, Output = U> + Ord + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	let start = nb::block!(dev.try_start_address())?;
+	let size = dev.bank_size();
+	if address < start || size.0 == U::from(0) {
+		return Err(Error::OutOfBounds);
+	}
+	let offset = address.0 - start.0;
+	let bank = usize::try_from(offset / size.0).map_err(|_| Error::OutOfBounds)?;
+	if bank >= dev.bank_count() {
+		return Err(Error::OutOfBounds);
+	}
+	Ok((bank, AddressOffset(offset % size.0)))
+}
+
+/// Pages visited by [`sync_image`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncStats {
+	/// Pages that already held the desired contents
+	pub unchanged: usize,
+	/// Pages that were erased and programmed
+	pub rewritten: usize,
+}
+
+/// Make the pages starting at `address` hold `desired`, leaving pages that already match alone.
+///
+/// Each page is read into `scratch`, which must hold a whole page, and
+/// compared with the corresponding part of `desired`. Only the pages that
+/// differ are erased and programmed, which saves erase cycles when the same
+/// image is provisioned again. The range covered by `desired` is checked as
+/// [`erase_range`] does.
+///
+/// ```
+/// use embedded_storage::counting::Counting;
+/// use embedded_storage::helpers::{sync_image, SyncStats};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiWrite};
+///
+/// let mut desired = [0u8; 48];
+/// desired[..16].fill(1);
+/// desired[16..32].fill(2);
+/// desired[32..].fill(3);
+///
+/// let mut storage = Counting::new(MemStorage::<48>::new(16));
+/// storage.try_write_slice(Address(16), &mut [2; 16]).unwrap();
+/// storage.reset();
+///
+/// let stats = sync_image(&mut storage, Address(0), &mut desired, &mut [0; 16]).unwrap();
+/// assert_eq!(stats, SyncStats { unchanged: 1, rewritten: 2 });
+/// assert_eq!(storage.erase_ops, 2);
+/// assert_eq!(storage.into_inner().as_slice(), &desired[..]);
+/// ```
+pub fn sync_image<T, Word, U, E>(
+	dev: &mut T,
+	address: Address<U>,
+	desired: &mut [Word],
+	scratch: &mut [Word],
+) -> Result<SyncStats, Error<E>>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>
+		+ ?Sized,
+	Word: PartialEq,
+	U: Add<U, Output = U> + Ord + From<u8> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let range = AddressRange::new(
+		address,
+		offset_from_len(desired.len()).ok_or(Error::Overflow)?,
+	);
+	count_pages(dev, range)?;
+	let mut stats = SyncStats::default();
+	let mut address = address;
+	let mut rest = desired;
+	while !rest.is_empty() {
+		let size = nb::block!(dev.try_page_size(address))?;
+		let len = len_from_offset(size).ok_or(Error::Overflow)?;
+		let (part, tail) = rest.split_at_mut(len);
+		let current = scratch.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+		nb::block!(dev.try_read_slice(address, current))?;
+		if current == part {
+			stats.unchanged += 1;
+		} else {
+			nb::block!(dev.try_erase_address(address))?;
+			nb::block!(dev.try_write_slice(address, part))?;
+			stats.rewritten += 1;
+		}
+		address = &address + &size;
+		rest = tail;
+	}
+	Ok(stats)
+}
+
+/// Estimate the worst case time to erase `range` with [`erase_range`], in microseconds.
+///
+/// The range is checked as [`erase_range`] does, and [`Error::Overflow`] is
+/// returned if the estimate doesn't fit in a `u32`.
+///
+/// ```
+/// use embedded_storage::helpers::estimate_erase_us;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, Timing};
+///
+/// struct Flash(MemStorage<64>);
+///
+/// impl Timing for Flash {
+///     const MAX_READ_US: u32 = 10;
+///     const MAX_WRITE_US: u32 = 400;
+///     const MAX_ERASE_US: u32 = 45_000;
+/// }
+/// # use embedded_storage::storage::StorageSize;
+/// # impl StorageSize<u8, usize> for Flash {
+/// #     type Error = embedded_storage::mem::Error;
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<usize>, Self::Error> {
+/// #         self.0.try_start_address()
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.0.try_total_size()
+/// #     }
+/// #     fn try_page_size(&mut self, address: Address<usize>) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.0.try_page_size(address)
+/// #     }
+/// # }
+///
+/// let mut flash = Flash(MemStorage::new(16));
+/// // Three pages
+/// let range = AddressRange::new(Address(16), AddressOffset(48));
+/// assert_eq!(estimate_erase_us::<_, u8, _, _>(&mut flash, range), Ok(135_000));
+/// ```
+pub fn estimate_erase_us<T, Word, U, E>(
+	dev: &mut T,
+	range: AddressRange<U>,
+) -> Result<u32, Error<E>>
+where
+	T: Timing + StorageSize<Word, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+{
+	let pages = count_pages(dev, range)?;
+	let pages = u32::try_from(pages).map_err(|_| Error::Overflow)?;
+	pages.checked_mul(T::MAX_ERASE_US).ok_or(Error::Overflow)
+}
+
+/// Rewrite the pages in `range` that needed error correction when read.
+///
+/// Each page is read into `scratch`, which must be at least as long as the
+/// largest page. If the device reports [`EccResult::Corrected`] errors, the
+/// page is erased and programmed again with the corrected data, refreshing
+/// its cells before the errors become uncorrectable. Pages reporting
+/// [`EccResult::Uncorrectable`] are left untouched, as rewriting them would
+/// make the errors permanent. The range is checked as [`erase_range`] does.
+///
+/// Returns the number of pages rewritten.
+///
+/// ```
+/// use embedded_storage::helpers::scrub;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{
+///     Address, AddressOffset, AddressRange, EccResult, EccStatus, ErasePage, MultiRead, MultiWrite,
+///     Page, StorageSize,
+/// };
+///
+/// // Reports a corrected error on every read of the second page
+/// struct Nand {
+///     mem: MemStorage<48>,
+///     last: EccResult,
+///     erases: usize,
+/// }
+///
+/// impl MultiRead<u8, usize> for Nand {
+///     type Error = embedded_storage::mem::Error;
+///
+///     fn try_read_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         self.last = if address.0 / 16 == 1 { EccResult::Corrected(1) } else { EccResult::Clean };
+///         self.mem.try_read_slice(address, buf)
+///     }
+/// }
+///
+/// impl EccStatus for Nand {
+///     type Error = embedded_storage::mem::Error;
+///
+///     fn try_last_ecc(&mut self) -> nb::Result<EccResult, Self::Error> {
+///         Ok(self.last)
+///     }
+/// }
+///
+/// impl ErasePage<usize> for Nand {
+///     type Error = embedded_storage::mem::Error;
+///
+///     fn try_erase_page(&mut self, page: Page<usize>) -> nb::Result<(), Self::Error> {
+///         self.erases += 1;
+///         self.mem.try_erase_page(page)
+///     }
+///
+///     fn try_erase_address(&mut self, address: Address<usize>) -> nb::Result<(), Self::Error> {
+///         self.erases += 1;
+///         self.mem.try_erase_address(address)
+///     }
+/// }
+/// # impl MultiWrite<u8, usize> for Nand {
+/// #     type Error = embedded_storage::mem::Error;
+/// #     fn try_write_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+/// #         self.mem.try_write_slice(address, buf)
+/// #     }
+/// # }
+/// # impl StorageSize<u8, usize> for Nand {
+/// #     type Error = embedded_storage::mem::Error;
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<usize>, Self::Error> {
+/// #         self.mem.try_start_address()
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.mem.try_total_size()
+/// #     }
+/// #     fn try_page_size(&mut self, address: Address<usize>) -> nb::Result<AddressOffset<usize>, Self::Error> {
+/// #         self.mem.try_page_size(address)
+/// #     }
+/// # }
+///
+/// let mut nand = Nand { mem: MemStorage::new(16), last: EccResult::Clean, erases: 0 };
+/// nand.mem.try_write_slice(Address(16), &mut [1, 2, 3]).unwrap();
+///
+/// let range = AddressRange::new(Address(0), AddressOffset(48));
+/// assert_eq!(scrub(&mut nand, range, &mut [0; 16]), Ok(1));
+/// assert_eq!(nand.erases, 1);
+/// assert_eq!(nand.mem.as_slice()[16..19], [1, 2, 3]);
+/// ```
+pub fn scrub<T, Word, U, E>(
+	dev: &mut T,
+	range: AddressRange<U>,
+	scratch: &mut [Word],
+) -> Result<usize, Error<E>>
+where
+	T: EccStatus<Error = E>
+		+ MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>
+		+ ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	count_pages(dev, range)?;
+	let end = range.end();
+	let mut address = range.start;
+	let mut scrubbed = 0;
+	while address < end {
+		let size = nb::block!(dev.try_page_size(address))?;
+		let len = len_from_offset(size).ok_or(Error::Overflow)?;
+		let page = scratch.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+		nb::block!(dev.try_read_slice(address, page))?;
+		if let EccResult::Corrected(_) = nb::block!(dev.try_last_ecc())? {
+			nb::block!(dev.try_erase_address(address))?;
+			nb::block!(dev.try_write_slice(address, page))?;
+			scrubbed += 1;
+		}
+		address = next_page(dev, address)?;
+	}
+	Ok(scrubbed)
+}
+
+/// Find the first page in `range` that was left partially written.
+///
+/// Pages in `range` are expected to start with a two byte header: a start
+/// marker written before the rest of the page, and a commit marker written
+/// after it. A marker is set once it no longer reads as `erase_byte`.
+/// A page with a start marker but no commit marker was being written when
+/// the device lost power, and its address is returned.
+///
+/// The header of each page is read into `scratch`, which must hold at least
+/// two bytes. The range is checked as [`erase_range`] does.
+///
+/// ```
+/// use embedded_storage::helpers::scan_torn;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MultiWrite};
+///
+/// let mut storage = MemStorage::<64>::new(16);
+/// let range = AddressRange::new(Address(0), AddressOffset(64));
+/// // A complete page, with both markers around its contents
+/// storage.try_write_slice(Address(0), &mut [0x00, 0x00, 1, 2, 3]).unwrap();
+/// assert_eq!(scan_torn(&mut storage, range, 0xFF, &mut [0; 2]), Ok(None));
+///
+/// // Power lost before the commit marker of the third page
+/// storage.try_write_slice(Address(32), &mut [0x00, 0xFF, 4, 5]).unwrap();
+/// assert_eq!(scan_torn(&mut storage, range, 0xFF, &mut [0; 2]), Ok(Some(Address(32))));
+/// ```
+pub fn scan_torn<T, U, E>(
+	dev: &mut T,
+	range: AddressRange<U>,
+	erase_byte: u8,
+	scratch: &mut [u8],
+) -> Result<Option<Address<U>>, Error<E>>
+where
+	T: MultiRead<u8, U, Error = E> + StorageSize<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+{
+	let header = scratch.get_mut(..2).ok_or(Error::BufferTooSmall)?;
+	count_pages(dev, range)?;
+	let end = range.end();
+	let mut address = range.start;
+	while address < end {
+		nb::block!(dev.try_read_slice(address, header))?;
+		if header[0] != erase_byte && header[1] == erase_byte {
+			return Ok(Some(address));
+		}
+		address = next_page(dev, address)?;
+	}
+	Ok(None)
+}
+
+/// Find the end of the written data in an append only `range`.
+///
+/// The region is expected to be filled from its start, so that it holds
+/// written pages, then at most one partially written page, then erased
+/// pages. A binary search for the first erased page reads O(log n) pages,
+/// and the page before it is then searched for its last byte that differs
+/// from `erase_byte`. The address after that byte is returned, and
+/// `range.end()` minus it is the space left. Pages must all have the size of
+/// the first one.
+///
+/// Each page is read into `scratch`, which must hold a whole page. The range
+/// is checked as [`erase_range`] does.
+///
+/// ```
+/// use embedded_storage::helpers::first_erased_address;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MultiWrite};
+///
+/// let mut storage = MemStorage::<128>::new(16);
+/// let range = AddressRange::new(Address(0), AddressOffset(128));
+/// let mut scratch = [0; 16];
+/// assert_eq!(first_erased_address(&mut storage, range, 0xFF, &mut scratch), Ok(Address(0)));
+///
+/// storage.try_write_slice(Address(0), &mut [0x42; 45]).unwrap();
+/// assert_eq!(first_erased_address(&mut storage, range, 0xFF, &mut scratch), Ok(Address(45)));
+///
+/// storage.try_write_slice(Address(45), &mut [0x42; 83]).unwrap();
+/// assert_eq!(first_erased_address(&mut storage, range, 0xFF, &mut scratch), Ok(Address(128)));
+/// ```
+pub fn first_erased_address<T, U, E>(
+	dev: &mut T,
+	range: AddressRange<U>,
+	erase_byte: u8,
+	scratch: &mut [u8],
+) -> Result<Address<U>, Error<E>>
+where
+	T: MultiRead<u8, U, Error = E> + StorageSize<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let pages = count_pages(dev, range)?;
+	if pages == 0 {
+		return Ok(range.start);
+	}
+	let size = nb::block!(dev.try_page_size(range.start))?;
+	let size = len_from_offset(size).ok_or(Error::Overflow)?;
+	let page = scratch.get_mut(..size).ok_or(Error::BufferTooSmall)?;
+	if len_from_offset(range.len) != size.checked_mul(pages) {
+		return Err(Error::NotAligned);
+	}
+	let address = |index: usize| -> Result<Address<U>, Error<E>> {
+		let offset = index.checked_mul(size).ok_or(Error::Overflow)?;
+		Ok(&range.start + &offset_from_len(offset).ok_or(Error::Overflow)?)
+	};
+
+	// The first page that is entirely erased, or `pages` if there is none
+	let (mut low, mut high) = (0, pages);
+	while low < high {
+		let mid = low + (high - low) / 2;
+		nb::block!(dev.try_read_slice(address(mid)?, page))?;
+		if page.iter().all(|byte| *byte == erase_byte) {
+			high = mid;
+		} else {
+			low = mid + 1;
+		}
+	}
+	if low == 0 {
+		return Ok(range.start);
+	}
+
+	nb::block!(dev.try_read_slice(address(low - 1)?, page))?;
+	let used = page
+		.iter()
+		.rposition(|byte| *byte != erase_byte)
+		.map_or(0, |index| index + 1);
+	let offset = (low - 1) * size + used;
+	Ok(&range.start + &offset_from_len(offset).ok_or(Error::Overflow)?)
+}
+
+/// Where the part of a write handled by [`for_each_page_part`] falls in its page
+struct PagePart<U> {
+	/// The start of the page
+	page: Address<U>,
+	/// The address the part is written at
+	address: Address<U>,
+	/// The offset of the part from the start of the page
+	offset: usize,
+	/// The number of words from `address` to the end of the page
+	room: usize,
+}
+
+/// Split a write of `buf` at `address` at page boundaries, passing every part to `write`.
+///
+/// Returns [`Error::OutOfBounds`] without accessing the device if the write
+/// isn't within it.
+fn for_each_page_part<T, Word, U, E, F>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [Word],
+	mut write: F,
+) -> Result<(), Error<E>>
+where
+	T: StorageSize<Word, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + From<u8> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+	F: FnMut(&mut T, PagePart<U>, &mut [Word]) -> Result<(), Error<E>>,
+{
+	let start = nb::block!(dev.try_start_address())?;
+	let size = nb::block!(dev.try_total_size())?;
+	let end = &address + &offset_from_len(buf.len()).ok_or(Error::Overflow)?;
+	if address < start || end < address || end > &start + &size {
+		return Err(Error::OutOfBounds);
+	}
+
+	let mut page = start;
+	let mut page_end = next_page(dev, page)?;
+	while page_end <= address && page_end < end {
+		page = page_end;
+		page_end = next_page(dev, page)?;
+	}
+	let mut address = address;
+	let mut rest = buf;
+	while !rest.is_empty() {
+		let offset = len_from_offset(AddressOffset(address.0 - page.0)).ok_or(Error::Overflow)?;
+		let room = len_from_offset(AddressOffset(page_end.0 - address.0)).ok_or(Error::Overflow)?;
+		let (part, tail) = rest.split_at_mut(room.min(rest.len()));
+		let at = PagePart {
+			page,
+			address,
+			offset,
+			room,
+		};
+		write(dev, at, part)?;
+		address = page_end;
+		rest = tail;
+		if !rest.is_empty() {
+			page = page_end;
+			page_end = next_page(dev, page)?;
+		}
+	}
+	Ok(())
+}
+
+/// The number of pages in `range`, checking it starts and ends on page boundaries
+fn count_pages<T, Word, U, E>(dev: &mut T, range: AddressRange<U>) -> Result<usize, Error<E>>
+where
+	T: StorageSize<Word, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+{
+	let start = nb::block!(dev.try_start_address())?;
+	let size = nb::block!(dev.try_total_size())?;
+	let end = range.end();
+	if range.start < start || end < range.start || end > &start + &size {
+		return Err(Error::OutOfBounds);
+	}
+
+	let mut address = start;
+	while address < range.start {
+		address = next_page(dev, address)?;
+	}
+	if address != range.start {
+		return Err(Error::NotAligned);
+	}
+	let mut pages = 0;
+	while address < end {
+		address = next_page(dev, address)?;
+		pages += 1;
+	}
+	if address != end {
+		return Err(Error::NotAligned);
+	}
+	Ok(pages)
+}
+
+/// The start of the page following the one starting at `address`
+fn next_page<T, Word, U, E>(dev: &mut T, address: Address<U>) -> Result<Address<U>, Error<E>>
+where
+	T: StorageSize<Word, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + PartialEq + From<u8> + Copy,
+{
+	let size = nb::block!(dev.try_page_size(address))?;
+	if size.0 == U::from(0) {
+		// A zero sized page would never advance
+		return Err(Error::OutOfBounds);
+	}
+	Ok(&address + &size)
+}
@@ -0,0 +1,192 @@
+//! History
+//!
+//! Keep the last few committed versions of a record, to roll back to them.
+//!
+//! The region is split into slots of one page each, used as a ring. Every
+//! slot holds a version in the [`commit`](crate::commit) format, so a commit
+//! interrupted by power loss leaves the previous version current. Version
+//! `n` is written to slot `n % slots`, replacing the oldest one once the
+//! ring is full, and rolling back erases the slot of the current version.
+
+use crate::commit::{at, read_slot, write_slot, HEADER_SIZE};
+use crate::helpers::{self, len_from_offset, offset_from_len};
+use crate::storage::{
+	Address, AddressRange, ErasePage, ErrorKind, MultiRead, MultiWrite, StorageError, StorageSize,
+};
+use core::convert::TryFrom;
+use core::ops::Add;
+
+/// Errors returned by [`CheckpointStore`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// There is no older version to roll back to
+	NoHistory,
+	/// The data doesn't fit in a slot
+	TooLarge,
+	/// The current version doesn't fit in the buffer
+	BufferTooSmall,
+	/// The region isn't a whole number of pages of the same size
+	Layout,
+	/// An address doesn't fit in the address type
+	Overflow,
+}
+
+impl<E> From<E> for Error<E> {
+	fn from(e: E) -> Self {
+		Error::Device(e)
+	}
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::TooLarge => ErrorKind::OutOfBounds,
+			Error::Layout => ErrorKind::NotAligned,
+			_ => ErrorKind::Other,
+		}
+	}
+}
+
+/// The errors of the commit primitives used by the store
+fn lift<E>(e: helpers::Error<E>) -> Error<E> {
+	match e {
+		helpers::Error::Device(e) => Error::Device(e),
+		helpers::Error::CrossesPage => Error::TooLarge,
+		helpers::Error::BufferTooSmall => Error::BufferTooSmall,
+		_ => Error::Overflow,
+	}
+}
+
+/// A record keeping its last versions in a ring of slots, see the [module documentation](self).
+///
+/// ```
+/// use embedded_storage::history::{CheckpointStore, Error};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange};
+///
+/// let slots = AddressRange::new(Address(0), AddressOffset(96));
+/// let mut settings = CheckpointStore::new(MemStorage::<96>::new(32), slots).unwrap();
+/// let mut buf = [0u8; 20];
+/// assert_eq!(settings.current(&mut buf), Ok(None));
+///
+/// settings.commit(&mut b"brightness=1".clone()).unwrap();
+/// settings.commit(&mut b"brightness=2".clone()).unwrap();
+/// settings.commit(&mut b"brightness=3".clone()).unwrap();
+/// assert_eq!(settings.current(&mut buf), Ok(Some(12)));
+/// assert_eq!(&buf[..12], b"brightness=3");
+///
+/// settings.rollback().unwrap();
+/// settings.rollback().unwrap();
+/// assert_eq!(settings.current(&mut buf), Ok(Some(12)));
+/// assert_eq!(&buf[..12], b"brightness=1");
+/// assert_eq!(settings.rollback(), Err(Error::NoHistory));
+///
+/// // Versions are found again after a restart
+/// let mut settings = CheckpointStore::new(settings.into_inner(), slots).unwrap();
+/// settings.commit(&mut b"brightness=4".clone()).unwrap();
+/// settings.rollback().unwrap();
+/// assert_eq!(settings.current(&mut buf), Ok(Some(12)));
+/// assert_eq!(&buf[..12], b"brightness=1");
+/// ```
+pub struct CheckpointStore<T, U> {
+	dev: T,
+	start: Address<U>,
+	slots: usize,
+	slot_size: usize,
+}
+
+impl<T, U, E> CheckpointStore<T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Keep the versions in `slots`, one per page.
+	///
+	/// The range must hold at least one page, and its pages must all have the
+	/// size of the first one.
+	pub fn new(mut dev: T, slots: AddressRange<U>) -> Result<Self, Error<E>> {
+		let page_size = nb::block!(dev.try_page_size(slots.start))?;
+		let slot_size = len_from_offset(page_size).ok_or(Error::Overflow)?;
+		let len = len_from_offset(slots.len).ok_or(Error::Overflow)?;
+		if slot_size == 0 || len == 0 || len % slot_size != 0 {
+			return Err(Error::Layout);
+		}
+		Ok(CheckpointStore {
+			dev,
+			start: slots.start,
+			slots: len / slot_size,
+			slot_size,
+		})
+	}
+
+	/// Write `data` as the new current version, returning its sequence number
+	pub fn commit(&mut self, data: &mut [u8]) -> Result<u32, Error<E>> {
+		let seq = match self.latest()? {
+			Some((seq, _, _)) => seq.wrapping_add(1),
+			None => 0,
+		};
+		let slot = self.slot(seq as usize % self.slots)?;
+		write_slot(&mut self.dev, slot, seq, data).map_err(lift)?;
+		Ok(seq)
+	}
+
+	/// Discard the current version, making the one committed before it current again
+	pub fn rollback(&mut self) -> Result<(), Error<E>> {
+		let (_, current, _) = self.latest()?.ok_or(Error::NoHistory)?;
+		// Every other valid slot holds an older version
+		for index in (0..self.slots).filter(|index| *index != current) {
+			let slot = self.slot(index)?;
+			if read_slot(&mut self.dev, slot).map_err(lift)?.is_some() {
+				let slot = self.slot(current)?;
+				nb::block!(self.dev.try_erase_address(slot))?;
+				return Ok(());
+			}
+		}
+		Err(Error::NoHistory)
+	}
+
+	/// Read the current version into `buf`, returning its length, or `None` if nothing was committed
+	pub fn current(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error<E>> {
+		match self.latest()? {
+			Some((_, index, len)) => {
+				let data = buf.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+				let address = at(self.slot(index)?, HEADER_SIZE).map_err(lift)?;
+				nb::block!(self.dev.try_read_slice(address, data))?;
+				Ok(Some(len))
+			}
+			None => Ok(None),
+		}
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+
+	/// The sequence number, slot and length of the current version
+	fn latest(&mut self) -> Result<Option<(u32, usize, usize)>, Error<E>> {
+		let mut latest: Option<(u32, usize, usize)> = None;
+		for index in 0..self.slots {
+			let slot = self.slot(index)?;
+			if let Some((seq, len)) = read_slot(&mut self.dev, slot).map_err(lift)? {
+				if latest.map_or(true, |(newest, _, _)| seq > newest) {
+					latest = Some((seq, index, len));
+				}
+			}
+		}
+		Ok(latest)
+	}
+
+	/// The address of slot `index`
+	fn slot(&self, index: usize) -> Result<Address<U>, Error<E>> {
+		let offset = index.checked_mul(self.slot_size).ok_or(Error::Overflow)?;
+		Ok(&self.start + &offset_from_len(offset).ok_or(Error::Overflow)?)
+	}
+}
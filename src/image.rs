@@ -0,0 +1,169 @@
+//! Image
+//!
+//! Verify firmware images before booting them.
+//!
+//! An image is a 12 byte header followed by the body:
+//!
+//! | Offset | Size | Content                                     |
+//! |--------|------|---------------------------------------------|
+//! | 0      | 4    | Magic, the bytes `EIMG`                     |
+//! | 4      | 4    | Body length in bytes, little endian         |
+//! | 8      | 4    | CRC-32 (IEEE) of the body, little endian    |
+//! | 12     | len  | Body                                        |
+//!
+//! This format is stable, so images written by host tools keep verifying with
+//! later versions of this crate.
+
+use crate::crc::{Crc, Crc32Ieee};
+use crate::storage::{Address, AddressOffset, AddressRange, ErrorKind, MultiRead, StorageError};
+use core::convert::TryFrom;
+use core::ops::Add;
+
+/// The first four bytes of every image
+pub const MAGIC: [u8; 4] = *b"EIMG";
+
+/// Number of bytes read per transaction while checking the body
+const CHUNK_SIZE: usize = 32;
+
+/// Errors returned by [`verify_image`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// The header doesn't start with [`MAGIC`]
+	BadMagic,
+	/// The body doesn't match the checksum in the header
+	Crc,
+	/// The body length doesn't fit in the address type
+	Overflow,
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			_ => ErrorKind::Other,
+		}
+	}
+}
+
+/// The header of an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageHeader {
+	/// Length of the body in bytes
+	pub len: u32,
+	/// CRC-32 (IEEE) of the body
+	pub crc: u32,
+}
+
+impl ImageHeader {
+	/// Size of the header in bytes
+	pub const SIZE: usize = 12;
+
+	/// Create the header for `body`
+	///
+	/// Panics if `body` is longer than `u32::MAX` bytes.
+	pub fn for_body(body: &[u8]) -> Self {
+		let mut crc = Crc32Ieee::new();
+		crc.update(body);
+		ImageHeader {
+			len: u32::try_from(body.len()).expect("image body too long"),
+			crc: crc.finish(),
+		}
+	}
+
+	/// Parse a header, returning `None` if it doesn't start with [`MAGIC`]
+	pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Option<Self> {
+		if bytes[..4] != MAGIC {
+			return None;
+		}
+		let mut len = [0; 4];
+		len.copy_from_slice(&bytes[4..8]);
+		let mut crc = [0; 4];
+		crc.copy_from_slice(&bytes[8..]);
+		Some(ImageHeader {
+			len: u32::from_le_bytes(len),
+			crc: u32::from_le_bytes(crc),
+		})
+	}
+
+	/// The stored form of the header
+	pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+		let mut bytes = [0; Self::SIZE];
+		bytes[..4].copy_from_slice(&MAGIC);
+		bytes[4..8].copy_from_slice(&self.len.to_le_bytes());
+		bytes[8..].copy_from_slice(&self.crc.to_le_bytes());
+		bytes
+	}
+}
+
+/// A verified image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageInfo<U> {
+	/// The addresses of the body
+	pub body: AddressRange<U>,
+	/// CRC-32 (IEEE) of the body
+	pub crc: u32,
+}
+
+/// Read the image header at `start` and check the body against its checksum.
+///
+/// ```
+/// use embedded_storage::image::{verify_image, Error, ImageHeader};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, MultiWrite};
+///
+/// let mut body = [0x5Au8; 100];
+/// let header = ImageHeader::for_body(&body);
+///
+/// let mut storage = MemStorage::<256>::new(256);
+/// storage.try_write_slice(Address(16), &mut header.to_bytes()).unwrap();
+/// storage.try_write_slice(Address(16 + ImageHeader::SIZE), &mut body).unwrap();
+///
+/// let info = verify_image(&mut storage, Address(16)).unwrap();
+/// assert_eq!(info.body.start, Address(28));
+/// assert_eq!(info.body.len, AddressOffset(100));
+///
+/// // Corrupt a single bit of the body
+/// storage.as_mut_slice()[100] ^= 0x80;
+/// assert_eq!(verify_image(&mut storage, Address(16)), Err(Error::Crc));
+/// assert_eq!(verify_image(&mut storage, Address(0)), Err(Error::BadMagic));
+/// ```
+pub fn verify_image<T, U, E>(dev: &mut T, start: Address<U>) -> Result<ImageInfo<U>, Error<E>>
+where
+	T: MultiRead<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Ord + TryFrom<usize> + Copy,
+{
+	let mut bytes = [0; ImageHeader::SIZE];
+	nb::block!(dev.try_read_slice(start, &mut bytes)).map_err(Error::Device)?;
+	let header = ImageHeader::from_bytes(&bytes).ok_or(Error::BadMagic)?;
+
+	let body = AddressRange::new(
+		&start + &offset(ImageHeader::SIZE)?,
+		offset(usize::try_from(header.len).map_err(|_| Error::Overflow)?)?,
+	);
+	let mut crc = Crc32Ieee::new();
+	let mut address = body.start;
+	let mut left = header.len as usize;
+	let mut chunk = [0; CHUNK_SIZE];
+	while left > 0 {
+		let buf = &mut chunk[..left.min(CHUNK_SIZE)];
+		nb::block!(dev.try_read_slice(address, buf)).map_err(Error::Device)?;
+		crc.update(buf);
+		address += offset(buf.len())?;
+		left -= buf.len();
+	}
+	if crc.finish() != header.crc {
+		return Err(Error::Crc);
+	}
+	Ok(ImageInfo {
+		body,
+		crc: header.crc,
+	})
+}
+
+fn offset<U: TryFrom<usize>, E>(len: usize) -> Result<AddressOffset<U>, Error<E>> {
+	U::try_from(len)
+		.map(AddressOffset)
+		.map_err(|_| Error::Overflow)
+}
@@ -0,0 +1,167 @@
+//! IO
+//!
+//! `std::io` adapters for byte addressed devices, enabled by the `std` feature.
+
+use crate::storage::{Address, ErrorKind, MultiRead, MultiWrite, StorageError, StorageSize};
+use core::convert::TryFrom;
+use core::ops::Add;
+use std::format;
+use std::io;
+
+/// Maximum number of bytes written to the device per call of `write`
+const CHUNK_SIZE: usize = 64;
+
+/// Implements [`io::Read`], [`io::Write`] and [`io::Seek`] for a device.
+///
+/// The adapter keeps a position relative to the start address of the device.
+/// Reads and writes are truncated at the end of the device, so reading there
+/// returns `Ok(0)` and writing fails with [`io::ErrorKind::WriteZero`].
+/// Errors from the device are converted according to their [`ErrorKind`].
+///
+/// ```
+/// use embedded_storage::io::IoAdapter;
+/// use embedded_storage::mem::MemStorage;
+/// use std::io::{self, Read, Seek, SeekFrom};
+///
+/// let mut storage = IoAdapter::new(MemStorage::<16>::new(16)).unwrap();
+/// let mut image: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+/// assert_eq!(io::copy(&mut image, &mut storage).unwrap(), 8);
+///
+/// storage.seek(SeekFrom::Start(2)).unwrap();
+/// let mut buf = [0u8; 4];
+/// storage.read_exact(&mut buf).unwrap();
+/// assert_eq!(buf, [3, 4, 5, 6]);
+///
+/// storage.seek(SeekFrom::End(-2)).unwrap();
+/// let mut rest = Vec::new();
+/// storage.read_to_end(&mut rest).unwrap();
+/// assert_eq!(rest, [0xFF, 0xFF]);
+///
+/// // The device is full
+/// let mut image: &[u8] = &[0; 16];
+/// assert_eq!(io::copy(&mut image, &mut storage).unwrap_err().kind(), io::ErrorKind::WriteZero);
+/// ```
+pub struct IoAdapter<T, U> {
+	dev: T,
+	start: Address<U>,
+	len: u64,
+	position: u64,
+}
+
+impl<T, U> IoAdapter<T, U>
+where
+	U: Add<U, Output = U> + TryFrom<u64> + Copy,
+	u64: TryFrom<U>,
+{
+	/// Wrap `dev` with the position at its start address
+	pub fn new<E>(mut dev: T) -> io::Result<Self>
+	where
+		T: StorageSize<u8, U, Error = E>,
+		E: StorageError,
+	{
+		let start = nb::block!(dev.try_start_address()).map_err(to_io)?;
+		let size = nb::block!(dev.try_total_size()).map_err(to_io)?;
+		let len = u64::try_from(size.0).map_err(|_| overflow())?;
+		Ok(IoAdapter {
+			dev,
+			start,
+			len,
+			position: 0,
+		})
+	}
+
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+
+	/// Device address of the current position, and how many of `len` bytes fit from there
+	fn next(&self, len: usize) -> io::Result<(Address<U>, usize)> {
+		let left = self.len.saturating_sub(self.position);
+		let len = usize::try_from(left).map_or(len, |left| left.min(len));
+		let offset = U::try_from(self.position).map_err(|_| overflow())?;
+		Ok((Address(self.start.0 + offset), len))
+	}
+}
+
+impl<T, U, E> io::Read for IoAdapter<T, U>
+where
+	T: MultiRead<u8, U, Error = E>,
+	E: StorageError,
+	U: Add<U, Output = U> + TryFrom<u64> + Copy,
+	u64: TryFrom<U>,
+{
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let (address, len) = self.next(buf.len())?;
+		if len == 0 {
+			return Ok(0);
+		}
+		nb::block!(self.dev.try_read_slice(address, &mut buf[..len])).map_err(to_io)?;
+		self.position += len as u64;
+		Ok(len)
+	}
+}
+
+impl<T, U, E> io::Write for IoAdapter<T, U>
+where
+	T: MultiWrite<u8, U, Error = E>,
+	E: StorageError,
+	U: Add<U, Output = U> + TryFrom<u64> + Copy,
+	u64: TryFrom<U>,
+{
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let (address, len) = self.next(buf.len().min(CHUNK_SIZE))?;
+		if len == 0 {
+			return Ok(0);
+		}
+		let mut chunk = [0; CHUNK_SIZE];
+		chunk[..len].copy_from_slice(&buf[..len]);
+		nb::block!(self.dev.try_write_slice(address, &mut chunk[..len])).map_err(to_io)?;
+		self.position += len as u64;
+		Ok(len)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+impl<T, U> io::Seek for IoAdapter<T, U> {
+	fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+		let position = match pos {
+			io::SeekFrom::Start(position) => Some(position),
+			io::SeekFrom::End(offset) => offset_by(self.len, offset),
+			io::SeekFrom::Current(offset) => offset_by(self.position, offset),
+		};
+		self.position = position.ok_or_else(|| {
+			io::Error::new(io::ErrorKind::InvalidInput, "seek before start of device")
+		})?;
+		Ok(self.position)
+	}
+}
+
+fn offset_by(base: u64, offset: i64) -> Option<u64> {
+	if offset < 0 {
+		base.checked_sub(offset.unsigned_abs())
+	} else {
+		base.checked_add(offset as u64)
+	}
+}
+
+fn overflow() -> io::Error {
+	io::Error::new(
+		io::ErrorKind::InvalidInput,
+		"address doesn't fit the address type",
+	)
+}
+
+fn to_io<E: StorageError>(e: E) -> io::Error {
+	let kind = match e.kind() {
+		ErrorKind::OutOfBounds => io::ErrorKind::UnexpectedEof,
+		ErrorKind::NotAligned => io::ErrorKind::InvalidInput,
+		ErrorKind::WriteProtected => io::ErrorKind::PermissionDenied,
+		ErrorKind::Timeout => io::ErrorKind::TimedOut,
+		_ => io::ErrorKind::Other,
+	};
+	io::Error::new(kind, format!("{:?}", e))
+}
@@ -0,0 +1,457 @@
+//! KV
+//!
+//! A persistent map of small keys to small values.
+//!
+//! The device is split into two banks of equal size, each made of whole
+//! pages. One bank is active and holds a log of entries, the other is kept
+//! erased as the spare. Every bank starts with a 4 byte generation number,
+//! and every entry is stored as:
+//!
+//! | Offset        | Size | Content                     |
+//! |---------------|------|-----------------------------|
+//! | 0             | 1    | Key length                  |
+//! | 1             | 2    | Value length, little endian |
+//! | 3             | key  | Key                         |
+//! | 3 + key       | len  | Value                       |
+//! | 3 + key + len | 1    | Commit marker               |
+//!
+//! All header fields are inverted relative to the erase byte, like the
+//! [`log`](crate::log) headers, so erased memory reads as the end of the log.
+//! Removing a key appends an entry with the reserved value length `0xFFFE`
+//! and no value. The latest committed entry for a key wins. Entries missing
+//! the commit marker, because power was lost while writing them, are ignored.
+//!
+//! When the active bank is full, the latest value for every key is copied to
+//! the spare bank, the spare bank's generation number is written and the old
+//! bank is erased. If power is lost before the generation number is written
+//! the old bank stays active, afterwards the bank with the higher generation
+//! number wins.
+
+use crate::helpers::len_from_offset;
+use crate::storage::{
+	Address, AddressOffset, ErasePage, ErrorKind, MultiRead, MultiWrite, StorageError, StorageSize,
+};
+use core::convert::TryFrom;
+use core::ops::Add;
+
+/// Errors returned by [`KvStore`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// The entry doesn't fit in a bank, even after compaction
+	Full,
+	/// The key is longer than `0xFE` bytes or the value longer than `0xFFFD` bytes
+	TooLarge,
+	/// The value doesn't fit in the caller's buffer
+	BufferTooSmall,
+	/// The device can't be split into two banks on a page boundary
+	NotAligned,
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::NotAligned => ErrorKind::NotAligned,
+			_ => ErrorKind::Other,
+		}
+	}
+}
+
+impl<E> From<E> for Error<E> {
+	fn from(e: E) -> Self {
+		Error::Device(e)
+	}
+}
+
+const BANK_HEADER: usize = 4;
+const ENTRY_HEADER: usize = 3;
+const MAX_KEY: usize = 0xFE;
+const MAX_VALUE: usize = 0xFFFD;
+const TOMBSTONE: u16 = 0xFFFE;
+const CHUNK: usize = 16;
+
+/// An entry found in a bank
+#[derive(Clone, Copy)]
+struct Entry {
+	offset: usize,
+	key_len: usize,
+	/// `None` for a removed key
+	value_len: Option<usize>,
+	committed: bool,
+}
+
+impl Entry {
+	fn size(&self) -> usize {
+		ENTRY_HEADER + self.key_len + self.value_len.unwrap_or(0) + 1
+	}
+}
+
+enum Next {
+	Entry(Entry),
+	End,
+	/// A header that can't be valid, the rest of the bank can't be used
+	Torn,
+}
+
+/// A key-value store filling a whole device.
+///
+/// ```
+/// use embedded_storage::kv::KvStore;
+/// use embedded_storage::mem::MemStorage;
+///
+/// // Two banks of one 32 byte page each
+/// let mut kv = KvStore::open(MemStorage::<64>::new(32), 0xFF).unwrap();
+/// let mut buf = [0u8; 8];
+///
+/// kv.insert(b"a", &[1, 2]).unwrap();
+/// kv.insert(b"b", &[3]).unwrap();
+/// assert_eq!(kv.get(b"a", &mut buf), Ok(Some(2)));
+/// assert_eq!(buf[..2], [1, 2]);
+///
+/// // Overwrite until the bank is compacted
+/// for i in 0..8 {
+///     kv.insert(b"a", &[i]).unwrap();
+/// }
+/// assert_eq!(kv.get(b"a", &mut buf), Ok(Some(1)));
+/// assert_eq!(buf[0], 7);
+///
+/// kv.remove(b"b").unwrap();
+/// assert_eq!(kv.get(b"b", &mut buf), Ok(None));
+///
+/// // Reopening the store after a reboot
+/// let mut kv = KvStore::open(kv.into_inner(), 0xFF).unwrap();
+/// assert_eq!(kv.get(b"a", &mut buf), Ok(Some(1)));
+/// assert_eq!(buf[0], 7);
+/// assert_eq!(kv.get(b"b", &mut buf), Ok(None));
+/// ```
+pub struct KvStore<T, U> {
+	dev: T,
+	banks: [Address<U>; 2],
+	bank_len: usize,
+	active: usize,
+	generation: u32,
+	end: usize,
+	erase_byte: u8,
+}
+
+impl<T, U, E> KvStore<T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + Ord + From<u8> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Open the store on `dev`, formatting it if neither bank is in use.
+	///
+	/// `erase_byte` is the value of an erased byte, which is
+	/// [`NorFlash::ERASE_BYTE`](crate::storage::NorFlash::ERASE_BYTE) for NOR flash.
+	pub fn open(mut dev: T, erase_byte: u8) -> Result<Self, Error<E>> {
+		let start = nb::block!(dev.try_start_address())?;
+		let total = nb::block!(dev.try_total_size())?;
+		let total = len_from_offset(total).ok_or(Error::TooLarge)?;
+		let bank_len = total / 2;
+
+		let mut offset = 0;
+		let mut split = false;
+		while offset < total {
+			split |= offset == bank_len;
+			let size = nb::block!(dev.try_page_size(at(start, offset)?))?;
+			let size = len_from_offset(size).ok_or(Error::TooLarge)?;
+			if size == 0 {
+				// A zero sized page would never advance
+				break;
+			}
+			offset += size;
+		}
+		if !split || total % 2 != 0 || bank_len <= BANK_HEADER {
+			return Err(Error::NotAligned);
+		}
+
+		let mut kv = KvStore {
+			dev,
+			banks: [start, at(start, bank_len)?],
+			bank_len,
+			active: 0,
+			generation: 0,
+			end: BANK_HEADER,
+			erase_byte,
+		};
+		match (kv.read_generation(0)?, kv.read_generation(1)?) {
+			(Some(a), Some(b)) if b > a => kv.select(1, b),
+			(Some(a), _) => kv.select(0, a),
+			(None, Some(b)) => kv.select(1, b),
+			(None, None) => {
+				kv.erase_bank(0)?;
+				kv.write_generation(0, 0)?;
+			}
+		}
+		let mut offset = BANK_HEADER;
+		kv.end = loop {
+			match kv.entry_at(kv.active, offset)? {
+				Next::Entry(entry) => offset += entry.size(),
+				Next::End => break offset,
+				Next::Torn => break kv.bank_len,
+			}
+		};
+		Ok(kv)
+	}
+
+	/// Read the value of `key` into `buf`, returning its length or `None` if the key isn't stored
+	pub fn get(&mut self, key: &[u8], buf: &mut [u8]) -> Result<Option<usize>, Error<E>> {
+		match self.find(key)? {
+			Some(Entry {
+				offset,
+				key_len,
+				value_len: Some(len),
+				..
+			}) => {
+				let value = buf.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+				let address = self.address(self.active, offset + ENTRY_HEADER + key_len)?;
+				nb::block!(self.dev.try_read_slice(address, value))?;
+				Ok(Some(len))
+			}
+			_ => Ok(None),
+		}
+	}
+
+	/// Store `value` for `key`, replacing any previous value
+	pub fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error<E>> {
+		if value.len() > MAX_VALUE {
+			return Err(Error::TooLarge);
+		}
+		self.append(key, Some(value))
+	}
+
+	/// Remove `key` from the store
+	pub fn remove(&mut self, key: &[u8]) -> Result<(), Error<E>> {
+		match self.find(key)? {
+			Some(Entry {
+				value_len: Some(_), ..
+			}) => self.append(key, None),
+			_ => Ok(()),
+		}
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+
+	fn select(&mut self, bank: usize, generation: u32) {
+		self.active = bank;
+		self.generation = generation;
+	}
+
+	fn address(&self, bank: usize, offset: usize) -> Result<Address<U>, Error<E>> {
+		at(self.banks[bank], offset)
+	}
+
+	fn read_generation(&mut self, bank: usize) -> Result<Option<u32>, Error<E>> {
+		let mut bytes = [0; BANK_HEADER];
+		nb::block!(self.dev.try_read_slice(self.banks[bank], &mut bytes))?;
+		let generation = u32::from_le_bytes(bytes) ^ !u32::from_le_bytes([self.erase_byte; 4]);
+		Ok(Some(generation).filter(|&g| g != u32::MAX))
+	}
+
+	fn write_generation(&mut self, bank: usize, generation: u32) -> Result<(), Error<E>> {
+		let stored = generation ^ !u32::from_le_bytes([self.erase_byte; 4]);
+		nb::block!(self
+			.dev
+			.try_write_slice(self.banks[bank], &mut stored.to_le_bytes()))?;
+		Ok(())
+	}
+
+	/// Erase every page of a bank
+	fn erase_bank(&mut self, bank: usize) -> Result<(), Error<E>> {
+		let mut offset = 0;
+		while offset < self.bank_len {
+			let address = self.address(bank, offset)?;
+			nb::block!(self.dev.try_erase_address(address))?;
+			let size = nb::block!(self.dev.try_page_size(address))?;
+			// `open` checked that the pages advance
+			offset += len_from_offset(size).ok_or(Error::TooLarge)?;
+		}
+		Ok(())
+	}
+
+	fn entry_at(&mut self, bank: usize, offset: usize) -> Result<Next, Error<E>> {
+		if offset + ENTRY_HEADER > self.bank_len {
+			return Ok(Next::End);
+		}
+		let mut header = [0; ENTRY_HEADER];
+		let address = self.address(bank, offset)?;
+		nb::block!(self.dev.try_read_slice(address, &mut header))?;
+		let key_len = header[0] ^ !self.erase_byte;
+		if key_len == 0xFF {
+			return Ok(Next::End);
+		}
+		let value_len =
+			u16::from_le_bytes([header[1], header[2]]) ^ !u16::from_le_bytes([self.erase_byte; 2]);
+		let value_len = match value_len {
+			TOMBSTONE => None,
+			len if usize::from(len) <= MAX_VALUE => Some(usize::from(len)),
+			_ => return Ok(Next::Torn),
+		};
+		let mut entry = Entry {
+			offset,
+			key_len: usize::from(key_len),
+			value_len,
+			committed: false,
+		};
+		if offset + entry.size() > self.bank_len {
+			return Ok(Next::Torn);
+		}
+		let mut marker = [0];
+		let address = self.address(bank, offset + entry.size() - 1)?;
+		nb::block!(self.dev.try_read_slice(address, &mut marker))?;
+		entry.committed = marker[0] == !self.erase_byte;
+		Ok(Next::Entry(entry))
+	}
+
+	/// The latest committed entry for `key` in the active bank
+	fn find(&mut self, key: &[u8]) -> Result<Option<Entry>, Error<E>> {
+		let mut found = None;
+		let mut offset = BANK_HEADER;
+		while let Next::Entry(entry) = self.entry_at(self.active, offset)? {
+			if entry.committed && self.key_is(entry, key)? {
+				found = Some(entry);
+			}
+			offset += entry.size();
+		}
+		Ok(found)
+	}
+
+	fn key_is(&mut self, entry: Entry, key: &[u8]) -> Result<bool, Error<E>> {
+		if entry.key_len != key.len() {
+			return Ok(false);
+		}
+		let mut offset = entry.offset + ENTRY_HEADER;
+		for part in key.chunks(CHUNK) {
+			let mut buf = [0; CHUNK];
+			let stored = &mut buf[..part.len()];
+			let address = self.address(self.active, offset)?;
+			nb::block!(self.dev.try_read_slice(address, stored))?;
+			if stored != part {
+				return Ok(false);
+			}
+			offset += part.len();
+		}
+		Ok(true)
+	}
+
+	fn same_key(&mut self, a: Entry, b: Entry) -> Result<bool, Error<E>> {
+		if a.key_len != b.key_len {
+			return Ok(false);
+		}
+		let mut done = 0;
+		while done < a.key_len {
+			let len = (a.key_len - done).min(CHUNK);
+			let (mut x, mut y) = ([0; CHUNK], [0; CHUNK]);
+			let address = self.address(self.active, a.offset + ENTRY_HEADER + done)?;
+			nb::block!(self.dev.try_read_slice(address, &mut x[..len]))?;
+			let address = self.address(self.active, b.offset + ENTRY_HEADER + done)?;
+			nb::block!(self.dev.try_read_slice(address, &mut y[..len]))?;
+			if x[..len] != y[..len] {
+				return Ok(false);
+			}
+			done += len;
+		}
+		Ok(true)
+	}
+
+	fn append(&mut self, key: &[u8], value: Option<&[u8]>) -> Result<(), Error<E>> {
+		if key.len() > MAX_KEY {
+			return Err(Error::TooLarge);
+		}
+		let size = ENTRY_HEADER + key.len() + value.map_or(0, |v| v.len()) + 1;
+		if self.end + size > self.bank_len {
+			self.compact()?;
+			if self.end + size > self.bank_len {
+				return Err(Error::Full);
+			}
+		}
+		let offset = self.end;
+		// Never write over a partially written entry
+		self.end += size;
+		let len = value.map_or(TOMBSTONE, |v| v.len() as u16);
+		let len = (len ^ !u16::from_le_bytes([self.erase_byte; 2])).to_le_bytes();
+		let mut header = [key.len() as u8 ^ !self.erase_byte, len[0], len[1]];
+		let address = self.address(self.active, offset)?;
+		nb::block!(self.dev.try_write_slice(address, &mut header))?;
+		self.write_bytes(offset + ENTRY_HEADER, key)?;
+		if let Some(value) = value {
+			self.write_bytes(offset + ENTRY_HEADER + key.len(), value)?;
+		}
+		let address = self.address(self.active, offset + size - 1)?;
+		nb::block!(self.dev.try_write_slice(address, &mut [!self.erase_byte]))?;
+		Ok(())
+	}
+
+	fn write_bytes(&mut self, offset: usize, data: &[u8]) -> Result<(), Error<E>> {
+		let mut offset = offset;
+		for part in data.chunks(CHUNK) {
+			let mut buf = [0; CHUNK];
+			let buf = &mut buf[..part.len()];
+			buf.copy_from_slice(part);
+			let address = self.address(self.active, offset)?;
+			nb::block!(self.dev.try_write_slice(address, buf))?;
+			offset += part.len();
+		}
+		Ok(())
+	}
+
+	/// Copy the latest value of every key to the spare bank and make it the active one
+	fn compact(&mut self) -> Result<(), Error<E>> {
+		let spare = 1 - self.active;
+		self.erase_bank(spare)?;
+		let mut dst = BANK_HEADER;
+		let mut offset = BANK_HEADER;
+		while let Next::Entry(entry) = self.entry_at(self.active, offset)? {
+			offset += entry.size();
+			if !entry.committed || entry.value_len.is_none() {
+				continue;
+			}
+			let mut latest = true;
+			let mut later = offset;
+			while let Next::Entry(other) = self.entry_at(self.active, later)? {
+				if other.committed && self.same_key(entry, other)? {
+					latest = false;
+					break;
+				}
+				later += other.size();
+			}
+			if latest {
+				let mut copied = 0;
+				while copied < entry.size() {
+					let len = (entry.size() - copied).min(CHUNK);
+					let mut buf = [0; CHUNK];
+					let address = self.address(self.active, entry.offset + copied)?;
+					nb::block!(self.dev.try_read_slice(address, &mut buf[..len]))?;
+					let address = self.address(spare, dst + copied)?;
+					nb::block!(self.dev.try_write_slice(address, &mut buf[..len]))?;
+					copied += len;
+				}
+				dst += entry.size();
+			}
+		}
+		let generation = self.generation.wrapping_add(1);
+		self.write_generation(spare, generation)?;
+		let old = self.active;
+		self.select(spare, generation);
+		self.end = dst;
+		self.erase_bank(old)
+	}
+}
+
+/// The address `offset` words after `base`
+fn at<U: Add<U, Output = U> + TryFrom<usize> + Copy, E>(
+	base: Address<U>,
+	offset: usize,
+) -> Result<Address<U>, Error<E>> {
+	let offset = U::try_from(offset).map_err(|_| Error::TooLarge)?;
+	Ok(&base + &AddressOffset(offset))
+}
@@ -11,9 +11,54 @@
 #![deny(missing_docs)]
 #![deny(unsafe_code)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 use core::ops::{Add, BitOr, Sub};
-use nb;
+pub mod aliases;
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod blocking;
+pub mod buffer;
+pub mod buffered;
+pub mod cache;
+pub mod checked;
+pub mod commit;
+pub mod concat;
+pub mod counting;
+pub mod crc;
+pub mod cursor;
+#[cfg(feature = "digest")]
+pub mod digest;
+pub mod endian;
+#[cfg(feature = "fault")]
+pub mod fault;
+pub mod helpers;
+pub mod history;
+pub mod image;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod kv;
+pub mod log;
+pub mod mapped;
+pub mod mem;
+pub mod ota;
+pub mod overlay;
+pub mod partition;
+pub mod prelude;
+pub mod read_only;
+pub mod record;
+pub mod retry;
+#[cfg(feature = "shared")]
+pub mod shared;
 pub mod storage;
+#[cfg(any(test, feature = "std"))]
+pub mod test_util;
+pub mod transaction;
+#[cfg(feature = "zerocopy")]
+pub mod typed;
+pub mod view;
+pub mod wear;
 
 /// Trait to check if two entities are bitwise subset of another.
 pub trait BitSubset {
@@ -85,7 +130,7 @@ where
 	I: Iterator<Item = R>,
 {
 	/// Obtain an [`OverlapIterator`] over a subslice of `memory` that overlaps with the region in `self`
-	fn overlaps(self, memory: &'a [u8], base_address: Address) -> OverlapIterator<R, I>;
+	fn overlaps(self, memory: &'a [u8], base_address: Address) -> OverlapIterator<'a, R, I>;
 }
 
 impl<'a, R, I> Iterator for OverlapIterator<'a, R, I>
@@ -120,7 +165,7 @@ where
 	R: Region,
 	I: Iterator<Item = R>,
 {
-	fn overlaps(self, memory: &'a [u8], base_address: Address) -> OverlapIterator<R, I> {
+	fn overlaps(self, memory: &'a [u8], base_address: Address) -> OverlapIterator<'a, R, I> {
 		OverlapIterator {
 			memory,
 			regions: self,
@@ -0,0 +1,222 @@
+//! Log
+//!
+//! An append only log of variable length records.
+//!
+//! The log fills a whole device, which can be limited to part of a larger one
+//! with [`Partition`](crate::partition::Partition). Every record is stored as
+//! a two byte little endian length header followed by the data. The header is
+//! inverted relative to the erase byte, so an erased header marks the end of
+//! the log and records of any length up to `0xFFFE` bytes can be stored.
+//!
+//! The header of a record is written before its data. If power is lost in
+//! between, the record is kept with partially erased data and later records
+//! are appended after it. If power is lost while writing the header, the rest
+//! of the device is treated as used until it is erased.
+
+use crate::storage::{
+	Address, AddressOffset, AddressRange, ErrorKind, MultiRead, MultiWrite, StorageError,
+	StorageSize,
+};
+use core::convert::TryFrom;
+use core::ops::{Add, Sub};
+
+/// Errors returned by the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// The record doesn't fit in the remaining space, or is longer than `0xFFFE` bytes
+	Full,
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::Full => ErrorKind::Other,
+		}
+	}
+}
+
+const HEADER_SIZE: usize = 2;
+
+/// Appends records to the end of the log.
+///
+/// ```
+/// use embedded_storage::log::{Error, LogReader, LogWriter};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::MultiRead;
+///
+/// let mut log = LogWriter::new(MemStorage::<16>::new(16), 0xFF).unwrap();
+/// log.append(&mut [1, 2, 3]).unwrap();
+/// log.append(&mut []).unwrap();
+///
+/// // Opening the log again continues after the existing records
+/// let mut log = LogWriter::new(log.into_inner(), 0xFF).unwrap();
+/// log.append(&mut [4, 5]).unwrap();
+/// assert_eq!(log.append(&mut [0; 6]), Err(Error::Full));
+///
+/// let mut records = [[0u8; 3], [0; 3], [0; 3]];
+/// let mut lens = [0; 3];
+/// let mut storage = log.into_inner();
+/// let ranges: Result<Vec<_>, _> = LogReader::new(&mut storage, 0xFF).unwrap().collect();
+/// for (i, range) in ranges.unwrap().into_iter().enumerate() {
+///     lens[i] = range.len.0;
+///     storage.try_read_slice(range.start, &mut records[i][..range.len.0]).unwrap();
+/// }
+/// assert_eq!(lens, [3, 0, 2]);
+/// assert_eq!(records, [[1, 2, 3], [0, 0, 0], [4, 5, 0]]);
+/// ```
+pub struct LogWriter<T, U> {
+	dev: T,
+	next: Address<U>,
+	end: Address<U>,
+	erase_byte: u8,
+}
+
+impl<T, U, E> LogWriter<T, U>
+where
+	T: MultiRead<u8, U, Error = E> + MultiWrite<u8, U, Error = E> + StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Open the log on `dev`, scanning for the end of the existing records.
+	///
+	/// `erase_byte` is the value of an erased byte, which is
+	/// [`NorFlash::ERASE_BYTE`](crate::storage::NorFlash::ERASE_BYTE) for NOR flash.
+	pub fn new(mut dev: T, erase_byte: u8) -> Result<Self, Error<E>> {
+		let (next, end) = {
+			let mut reader = LogReader::new(&mut dev, erase_byte)?;
+			for record in &mut reader {
+				record?;
+			}
+			(reader.address, reader.end)
+		};
+		Ok(LogWriter {
+			dev,
+			next,
+			end,
+			erase_byte,
+		})
+	}
+
+	/// Append `data` as a new record, returning the address of the data
+	pub fn append(&mut self, data: &mut [u8]) -> Result<Address<U>, Error<E>> {
+		let len = u16::try_from(data.len())
+			.ok()
+			.filter(|&len| len != u16::MAX)
+			.ok_or(Error::Full)?;
+		let free = usize::try_from(self.end.0 - self.next.0).unwrap_or(usize::MAX);
+		if free < HEADER_SIZE || data.len() > free - HEADER_SIZE {
+			return Err(Error::Full);
+		}
+		let address = offset(self.next, HEADER_SIZE);
+		let mut header = encode(len, self.erase_byte);
+		nb::block!(self.dev.try_write_slice(self.next, &mut header)).map_err(Error::Device)?;
+		nb::block!(self.dev.try_write_slice(address, data)).map_err(Error::Device)?;
+		self.next = offset(address, data.len());
+		Ok(address)
+	}
+
+	/// The address the next record will be written to
+	pub fn next_address(&self) -> Address<U> {
+		self.next
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+}
+
+/// Iterator over the records of a log, yielding the address range of the data of each record.
+///
+/// Iteration stops at the first erased header, at a header claiming more data
+/// than is left on the device, or after yielding an error from the device.
+pub struct LogReader<'a, T, U> {
+	dev: &'a mut T,
+	address: Address<U>,
+	end: Address<U>,
+	erase_byte: u8,
+	done: bool,
+}
+
+impl<'a, T, U, E> LogReader<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E> + StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Iterate over the records of the log on `dev`
+	pub fn new(dev: &'a mut T, erase_byte: u8) -> Result<Self, Error<E>> {
+		let start = nb::block!(dev.try_start_address()).map_err(Error::Device)?;
+		let size = nb::block!(dev.try_total_size()).map_err(Error::Device)?;
+		Ok(LogReader {
+			dev,
+			address: start,
+			end: &start + &size,
+			erase_byte,
+			done: false,
+		})
+	}
+}
+
+impl<'a, T, U, E> Iterator for LogReader<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Item = Result<AddressRange<U>, Error<E>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+		let left = usize::try_from(self.end.0 - self.address.0).unwrap_or(usize::MAX);
+		if left < HEADER_SIZE {
+			self.done = true;
+			return None;
+		}
+		let mut header = [0; HEADER_SIZE];
+		if let Err(e) = nb::block!(self.dev.try_read_slice(self.address, &mut header)) {
+			self.done = true;
+			return Some(Err(Error::Device(e)));
+		}
+		if header == [self.erase_byte; HEADER_SIZE] {
+			self.done = true;
+			return None;
+		}
+		let len = usize::from(decode(header, self.erase_byte));
+		if len > left - HEADER_SIZE {
+			// A torn header, the rest of the device can't be used
+			self.address = self.end;
+			self.done = true;
+			return None;
+		}
+		let start = offset(self.address, HEADER_SIZE);
+		self.address = offset(start, len);
+		// `len` is at most `left`, which came from a `U`
+		let len = AddressOffset(U::try_from(len).ok()?);
+		Some(Ok(AddressRange::new(start, len)))
+	}
+}
+
+/// Advance `address` by `len` words known to be within the device
+fn offset<U>(address: Address<U>, len: usize) -> Address<U>
+where
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	match U::try_from(len) {
+		Ok(len) => &address + &AddressOffset(len),
+		Err(_) => unreachable!("offset within the device"),
+	}
+}
+
+fn encode(len: u16, erase_byte: u8) -> [u8; HEADER_SIZE] {
+	(len ^ !u16::from_le_bytes([erase_byte; HEADER_SIZE])).to_le_bytes()
+}
+
+fn decode(header: [u8; HEADER_SIZE], erase_byte: u8) -> u16 {
+	u16::from_le_bytes(header) ^ !u16::from_le_bytes([erase_byte; HEADER_SIZE])
+}
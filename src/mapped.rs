@@ -0,0 +1,193 @@
+//! Mapped
+//!
+//! Read only access to memory mapped storage, such as execute-in-place flash.
+//!
+//! Apart from the default [`MultiRead::try_read_uninit`], this is the only
+//! module containing unsafe code. Every access is checked against the mapped
+//! length before the memory is read.
+
+#![allow(unsafe_code)]
+
+use crate::storage::{
+	Address, AddressOffset, ErrorKind, MultiRead, SingleRead, StorageError, StorageSize,
+	ZeroCopyRead,
+};
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+/// Errors returned by [`MemoryMapped`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+	/// The access is not within the mapped region
+	OutOfBounds,
+}
+
+impl StorageError for Error {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::OutOfBounds => ErrorKind::OutOfBounds,
+		}
+	}
+}
+
+/// A memory mapped region, read through raw pointers.
+///
+/// Addresses are offsets from the base of the region, so the start address is
+/// zero. The whole region is reported as a single page. Writing and erasing
+/// aren't implemented, as memory mapped flash is read only from the CPU and
+/// is programmed through a separate interface.
+///
+/// Every byte is read with a volatile read, so changes made to the memory by
+/// that interface are seen. [`ZeroCopyRead`] borrows the memory directly, so
+/// it must not be reprogrammed while the returned slice is in use.
+///
+/// ```
+/// use embedded_storage::mapped::{Error, MemoryMapped};
+/// use embedded_storage::storage::{
+///     Address, AddressOffset, MultiRead, SingleRead, StorageSize, ZeroCopyRead,
+/// };
+///
+/// static FLASH: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+///
+/// let mut flash = MemoryMapped::<u32>::from_static(&FLASH);
+/// assert_eq!(flash.try_total_size(), Ok(AddressOffset(8)));
+/// assert_eq!(flash.try_read(Address(3)), Ok(3));
+///
+/// let mut buf = [0u8; 4];
+/// flash.try_read_slice(Address(4), &mut buf).unwrap();
+/// assert_eq!(buf, [4, 5, 6, 7]);
+/// assert_eq!(flash.try_read_slice(Address(6), &mut buf), Err(nb::Error::Other(Error::OutOfBounds)));
+/// assert_eq!(flash.try_read(Address(8)), Err(nb::Error::Other(Error::OutOfBounds)));
+///
+/// // Read without initializing the buffer first
+/// let mut buf = [core::mem::MaybeUninit::uninit(); 2];
+/// assert_eq!(flash.try_read_uninit(Address(1), &mut buf), Ok(&mut [1, 2][..]));
+///
+/// // Borrow the data in place, without copying
+/// assert_eq!(flash.try_read_ref(Address(2), 3), Ok(&FLASH[2..5]));
+/// ```
+pub struct MemoryMapped<U> {
+	base: *const u8,
+	len: usize,
+	_address: PhantomData<U>,
+}
+
+impl<U> MemoryMapped<U> {
+	/// Map `len` bytes starting at `base`.
+	///
+	/// # Safety
+	///
+	/// The `len` bytes from `base` must be readable for as long as the
+	/// returned value is used.
+	pub unsafe fn new(base: *const u8, len: usize) -> Self {
+		MemoryMapped {
+			base,
+			len,
+			_address: PhantomData,
+		}
+	}
+
+	/// Map a static byte slice, such as a linker defined region
+	pub fn from_static(region: &'static [u8]) -> Self {
+		// A static slice is readable for the rest of the program
+		unsafe { Self::new(region.as_ptr(), region.len()) }
+	}
+
+	/// Check that `len` bytes from `address` are mapped, returning the offset
+	fn offset(&self, address: Address<U>, len: usize) -> Result<usize, Error>
+	where
+		usize: TryFrom<U>,
+	{
+		let offset = usize::try_from(address.0).map_err(|_| Error::OutOfBounds)?;
+		if offset <= self.len && len <= self.len - offset {
+			Ok(offset)
+		} else {
+			Err(Error::OutOfBounds)
+		}
+	}
+}
+
+impl<U> SingleRead<u8, U> for MemoryMapped<U>
+where
+	usize: TryFrom<U>,
+{
+	type Error = Error;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<u8, Self::Error> {
+		let offset = self.offset(address, 1)?;
+		// The offset was checked to be within the mapped region
+		Ok(unsafe { self.base.add(offset).read_volatile() })
+	}
+}
+
+impl<U> MultiRead<u8, U> for MemoryMapped<U>
+where
+	usize: TryFrom<U>,
+{
+	type Error = Error;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [u8],
+	) -> nb::Result<(), Self::Error> {
+		let offset = self.offset(address, buf.len())?;
+		for (i, byte) in buf.iter_mut().enumerate() {
+			// The whole range was checked to be within the mapped region
+			*byte = unsafe { self.base.add(offset + i).read_volatile() };
+		}
+		Ok(())
+	}
+
+	fn try_read_uninit<'a>(
+		&mut self,
+		address: Address<U>,
+		buf: &'a mut [MaybeUninit<u8>],
+	) -> nb::Result<&'a mut [u8], Self::Error> {
+		let offset = self.offset(address, buf.len())?;
+		for (i, byte) in buf.iter_mut().enumerate() {
+			// The whole range was checked to be within the mapped region
+			*byte = MaybeUninit::new(unsafe { self.base.add(offset + i).read_volatile() });
+		}
+		// Every byte was initialized above
+		Ok(unsafe { &mut *(buf as *mut [MaybeUninit<u8>] as *mut [u8]) })
+	}
+}
+
+impl<U> ZeroCopyRead<u8, U> for MemoryMapped<U>
+where
+	usize: TryFrom<U>,
+{
+	type Error = Error;
+
+	fn try_read_ref(&mut self, address: Address<U>, len: usize) -> nb::Result<&[u8], Self::Error> {
+		let offset = self.offset(address, len)?;
+		// The range was checked to be within the mapped region, which is
+		// readable as long as `self` is
+		Ok(unsafe { core::slice::from_raw_parts(self.base.add(offset), len) })
+	}
+}
+
+impl<U> StorageSize<u8, U> for MemoryMapped<U>
+where
+	U: TryFrom<usize> + From<u8>,
+	usize: TryFrom<U>,
+{
+	type Error = Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		Ok(Address(U::from(0)))
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		U::try_from(self.len)
+			.map(AddressOffset)
+			.map_err(|_| nb::Error::Other(Error::OutOfBounds))
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.offset(address, 1)?;
+		self.try_total_size()
+	}
+}
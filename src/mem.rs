@@ -0,0 +1,329 @@
+//! Memory
+//!
+//! A RAM backed implementation of all storage traits, intended for host side
+//! testing of code that consumes storage.
+//!
+//! [`MemStorage`] emulates NOR flash: writes can only clear bits and erasing a
+//! page sets all of its bytes back to `0xFF`. [`MemStorageBuilder`] configures
+//! it to simulate other devices.
+
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, DurableWrites, ErasePage, ErrorKind, MultiRead,
+	MultiWrite, Page, ReadAccess, Semantics, SingleRead, SingleWrite, StorageError, StorageSize,
+	WriteSemantics,
+};
+
+/// The value of an erased byte, unless configured otherwise.
+const ERASE_BYTE: u8 = 0xFF;
+
+/// Errors returned by [`MemStorage`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+	/// The access is not within the backing buffer
+	OutOfBounds,
+	/// The address is not the start of a page
+	NotAligned,
+}
+
+impl StorageError for Error {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::OutOfBounds => ErrorKind::OutOfBounds,
+			Error::NotAligned => ErrorKind::NotAligned,
+		}
+	}
+}
+
+/// RAM backed storage of `N` bytes, addressed by `usize`.
+///
+/// ```
+/// use embedded_storage::mem::{Error, MemStorage};
+/// use embedded_storage::storage::{Address, ErasePage, MultiRead, MultiWrite, Page, SingleRead};
+///
+/// let mut storage = MemStorage::<64>::new(16);
+///
+/// storage.try_write_slice(Address(16), &mut [0x12, 0x34]).unwrap();
+/// // Writes can only clear bits
+/// storage.try_write_slice(Address(16), &mut [0xF0, 0xFF]).unwrap();
+///
+/// let mut buf = [0u8; 2];
+/// storage.try_read_slice(Address(16), &mut buf).unwrap();
+/// assert_eq!(buf, [0x10, 0x34]);
+///
+/// storage.try_erase_page(Page(1)).unwrap();
+/// assert!(storage.as_slice()[16..32].iter().all(|b| *b == 0xFF));
+///
+/// assert_eq!(storage.try_read(Address(64)), Err(nb::Error::Other(Error::OutOfBounds)));
+/// ```
+pub struct MemStorage<const N: usize> {
+	buf: [u8; N],
+	page_size: usize,
+	erase_byte: u8,
+	semantics: Semantics,
+	would_block_cycles: usize,
+	blocked: usize,
+}
+
+/// Configures a [`MemStorage`] to simulate a particular device.
+///
+/// The defaults match [`MemStorage::new`] with a single page: an erase byte
+/// of `0xFF`, writes that can only clear bits, and operations completing
+/// on the first call.
+///
+/// ```
+/// use embedded_storage::mem::MemStorageBuilder;
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite, Semantics, WriteSemantics};
+///
+/// let mut storage = MemStorageBuilder::<32>::new()
+///     .page_size(8)
+///     .erase_byte(0x00)
+///     .write_semantics(Semantics::Arbitrary)
+///     .would_block_cycles(2)
+///     .build();
+/// assert_eq!(storage.as_slice()[0], 0x00);
+/// assert_eq!(storage.write_semantics(), Semantics::Arbitrary);
+///
+/// nb::block!(storage.try_write_slice(Address(0), &mut [1, 2])).unwrap();
+/// let mut buf = [0u8; 2];
+/// assert_eq!(storage.try_read_slice(Address(0), &mut buf), Err(nb::Error::WouldBlock));
+/// assert_eq!(storage.try_read_slice(Address(0), &mut buf), Err(nb::Error::WouldBlock));
+/// assert_eq!(storage.try_read_slice(Address(0), &mut buf), Ok(()));
+/// assert_eq!(buf, [1, 2]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MemStorageBuilder<const N: usize> {
+	page_size: usize,
+	erase_byte: u8,
+	semantics: Semantics,
+	would_block_cycles: usize,
+}
+
+impl<const N: usize> MemStorageBuilder<N> {
+	/// The default configuration
+	pub fn new() -> Self {
+		MemStorageBuilder {
+			page_size: N,
+			erase_byte: ERASE_BYTE,
+			semantics: Semantics::BitClearOnly,
+			would_block_cycles: 0,
+		}
+	}
+
+	/// Split the storage into pages of `page_size` bytes
+	pub fn page_size(mut self, page_size: usize) -> Self {
+		self.page_size = page_size;
+		self
+	}
+
+	/// The value of an erased byte
+	pub fn erase_byte(mut self, erase_byte: u8) -> Self {
+		self.erase_byte = erase_byte;
+		self
+	}
+
+	/// How writes change the stored data.
+	///
+	/// With [`Semantics::BitClearOnly`], a write can only change bits away
+	/// from their value in the erase byte.
+	pub fn write_semantics(mut self, semantics: Semantics) -> Self {
+		self.semantics = semantics;
+		self
+	}
+
+	/// Return `WouldBlock` `cycles` times before completing each read, write and erase
+	pub fn would_block_cycles(mut self, cycles: usize) -> Self {
+		self.would_block_cycles = cycles;
+		self
+	}
+
+	/// Create the erased storage.
+	///
+	/// Panics if the page size is zero or doesn't evenly divide `N`.
+	pub fn build(self) -> MemStorage<N> {
+		assert!(self.page_size > 0 && N % self.page_size == 0);
+		MemStorage {
+			buf: [self.erase_byte; N],
+			page_size: self.page_size,
+			erase_byte: self.erase_byte,
+			semantics: self.semantics,
+			would_block_cycles: self.would_block_cycles,
+			blocked: 0,
+		}
+	}
+}
+
+impl<const N: usize> Default for MemStorageBuilder<N> {
+	fn default() -> Self {
+		MemStorageBuilder::new()
+	}
+}
+
+impl<const N: usize> MemStorage<N> {
+	/// Create an erased storage, split into pages of `page_size` bytes.
+	///
+	/// Panics if `page_size` is zero or doesn't evenly divide `N`.
+	pub fn new(page_size: usize) -> Self {
+		MemStorageBuilder::new().page_size(page_size).build()
+	}
+
+	/// The page size in bytes
+	pub fn page_size(&self) -> usize {
+		self.page_size
+	}
+
+	/// The value of an erased byte
+	pub fn erase_byte(&self) -> u8 {
+		self.erase_byte
+	}
+
+	/// The backing buffer
+	pub fn as_slice(&self) -> &[u8] {
+		&self.buf
+	}
+
+	/// The backing buffer, mutably. Writes through this bypass the flash emulation.
+	pub fn as_mut_slice(&mut self) -> &mut [u8] {
+		&mut self.buf
+	}
+
+	/// Count a call towards the configured `WouldBlock` cycles
+	fn poll(&mut self) -> nb::Result<(), Error> {
+		if self.blocked < self.would_block_cycles {
+			self.blocked += 1;
+			return Err(nb::Error::WouldBlock);
+		}
+		self.blocked = 0;
+		Ok(())
+	}
+
+	/// The value of a cell after writing `word` to it
+	fn program(&self, cell: u8, word: u8) -> u8 {
+		match self.semantics {
+			// Only bits still at their erased value can change
+			Semantics::BitClearOnly => {
+				cell ^ (!(cell ^ self.erase_byte) & (word ^ self.erase_byte))
+			}
+			Semantics::Arbitrary => word,
+		}
+	}
+
+	fn range(&self, address: usize, len: usize) -> Result<core::ops::Range<usize>, Error> {
+		match address.checked_add(len) {
+			Some(end) if end <= N => Ok(address..end),
+			_ => Err(Error::OutOfBounds),
+		}
+	}
+}
+
+impl<const N: usize> SingleRead<u8, usize> for MemStorage<N> {
+	type Error = Error;
+
+	fn try_read(&mut self, address: Address<usize>) -> nb::Result<u8, Self::Error> {
+		self.poll()?;
+		let range = self.range(address.0, 1)?;
+		Ok(self.buf[range.start])
+	}
+}
+
+impl<const N: usize> SingleWrite<u8, usize> for MemStorage<N> {
+	type Error = Error;
+
+	fn try_write(&mut self, address: Address<usize>, word: u8) -> nb::Result<(), Self::Error> {
+		self.poll()?;
+		let range = self.range(address.0, 1)?;
+		self.buf[range.start] = self.program(self.buf[range.start], word);
+		Ok(())
+	}
+}
+
+impl<const N: usize> MultiRead<u8, usize> for MemStorage<N> {
+	type Error = Error;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<usize>,
+		buf: &mut [u8],
+	) -> nb::Result<(), Self::Error> {
+		self.poll()?;
+		let range = self.range(address.0, buf.len())?;
+		buf.copy_from_slice(&self.buf[range]);
+		Ok(())
+	}
+}
+
+impl<const N: usize> MultiWrite<u8, usize> for MemStorage<N> {
+	type Error = Error;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<usize>,
+		buf: &mut [u8],
+	) -> nb::Result<(), Self::Error> {
+		self.poll()?;
+		let range = self.range(address.0, buf.len())?;
+		for (i, word) in range.zip(buf.iter()) {
+			self.buf[i] = self.program(self.buf[i], *word);
+		}
+		Ok(())
+	}
+}
+
+impl<const N: usize> ErasePage<usize> for MemStorage<N> {
+	type Error = Error;
+
+	fn try_erase_page(&mut self, page: Page<usize>) -> nb::Result<(), Self::Error> {
+		self.poll()?;
+		let start = page
+			.0
+			.checked_mul(self.page_size)
+			.ok_or(Error::OutOfBounds)?;
+		let range = self.range(start, self.page_size)?;
+		for cell in self.buf[range].iter_mut() {
+			*cell = self.erase_byte;
+		}
+		Ok(())
+	}
+
+	fn try_erase_address(&mut self, address: Address<usize>) -> nb::Result<(), Self::Error> {
+		if address.0 % self.page_size != 0 {
+			return Err(nb::Error::Other(Error::NotAligned));
+		}
+		self.try_erase_page(Page(address.0 / self.page_size))
+	}
+}
+
+impl<const N: usize> StorageSize<u8, usize> for MemStorage<N> {
+	type Error = Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<usize>, Self::Error> {
+		Ok(Address(0))
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<usize>, Self::Error> {
+		Ok(AddressOffset(N))
+	}
+
+	fn try_page_size(
+		&mut self,
+		address: Address<usize>,
+	) -> nb::Result<AddressOffset<usize>, Self::Error> {
+		self.range(address.0, 1)?;
+		Ok(AddressOffset(self.page_size))
+	}
+}
+
+impl<const N: usize> DurableWrites for MemStorage<N> {
+	type Error = Error;
+}
+
+impl<const N: usize> WriteSemantics for MemStorage<N> {
+	fn write_semantics(&self) -> Semantics {
+		self.semantics
+	}
+}
+
+impl<const N: usize> ReadAccess for MemStorage<N> {
+	fn access_pattern(&self) -> AccessPattern {
+		AccessPattern::Random
+	}
+}
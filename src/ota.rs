@@ -0,0 +1,283 @@
+//! OTA
+//!
+//! Receive a firmware update into the inactive slot of a double buffered
+//! layout, one chunk at a time.
+//!
+//! The update is streamed in the [`image`](crate::image) format, a header
+//! followed by the body. The body is written as it arrives while its CRC is
+//! accumulated, and the header is written only once the whole body matches
+//! it. A slot therefore holds a valid image, which
+//! [`verify_image`](crate::image::verify_image) accepts, only after a
+//! successful [`finish`](UpdateController::finish). A bootloader picks one
+//! of the slots holding a valid image, and the running firmware updates the
+//! other one.
+
+use crate::crc::{Crc, Crc32Ieee};
+use crate::helpers::{len_from_offset, offset_from_len};
+use crate::image::ImageHeader;
+use crate::storage::{
+	Address, AddressRange, ErasePage, ErrorKind, MultiRead, MultiWrite, StorageError, StorageSize,
+};
+use core::convert::TryFrom;
+use core::ops::Add;
+
+/// Number of bytes written per transaction
+const CHUNK_SIZE: usize = 32;
+
+/// Errors returned by [`UpdateController`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// The method was called in the wrong state, such as feeding before `start`
+	State,
+	/// The image doesn't fit in the slot, or more bytes were fed than announced
+	TooLarge,
+	/// `finish` was called before all announced bytes were fed
+	Incomplete,
+	/// The image doesn't start with [`MAGIC`](crate::image::MAGIC)
+	BadMagic,
+	/// The length in the header doesn't match the announced length
+	Length,
+	/// The body doesn't match the checksum in the header
+	Crc,
+	/// An address doesn't fit in the address type
+	Overflow,
+}
+
+impl<E> From<E> for Error<E> {
+	fn from(e: E) -> Self {
+		Error::Device(e)
+	}
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::TooLarge => ErrorKind::OutOfBounds,
+			_ => ErrorKind::Other,
+		}
+	}
+}
+
+/// How much of the update was received
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+	/// Bytes received so far, including the header
+	pub received: usize,
+	/// Bytes announced in [`start`](UpdateController::start)
+	pub total: usize,
+}
+
+impl Progress {
+	/// Whether every announced byte was received
+	pub fn is_complete(&self) -> bool {
+		self.received == self.total
+	}
+}
+
+enum State {
+	Idle,
+	Receiving,
+	Done,
+}
+
+/// Writes an incoming image to a slot, erasing its pages on demand.
+///
+/// Call [`start`](UpdateController::start) with the length of the image,
+/// including its header, [`feed`](UpdateController::feed) the image in
+/// chunks of any size, then [`finish`](UpdateController::finish) to check
+/// the CRC and mark the slot bootable. Calling `start` again abandons the
+/// update in progress. The operations block on the device.
+///
+/// Pages are erased as the image reaches them, so the slot should start on
+/// a page boundary.
+///
+/// ```
+/// use embedded_storage::image::{verify_image, ImageHeader};
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::ota::UpdateController;
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange};
+///
+/// let body = [0x5Au8; 100];
+/// let mut image = [0u8; ImageHeader::SIZE + 100];
+/// image[..ImageHeader::SIZE].copy_from_slice(&ImageHeader::for_body(&body).to_bytes());
+/// image[ImageHeader::SIZE..].copy_from_slice(&body);
+///
+/// // The second slot of two, while the first one is running
+/// let slot = AddressRange::new(Address(128), AddressOffset(128));
+/// let mut update = UpdateController::new(MemStorage::<256>::new(32), slot);
+/// update.start(image.len()).unwrap();
+/// for chunk in image.chunks(7) {
+///     update.feed(chunk).unwrap();
+/// }
+/// update.finish().unwrap();
+///
+/// let mut storage = update.into_inner();
+/// let info = verify_image(&mut storage, Address(128)).unwrap();
+/// assert_eq!(info.body.len, AddressOffset(100));
+/// ```
+///
+/// A corrupted image fails the check, and the slot is left without a header:
+///
+/// ```
+/// # use embedded_storage::image::{verify_image, ImageHeader};
+/// # use embedded_storage::mem::MemStorage;
+/// # use embedded_storage::ota::{Error, UpdateController};
+/// # use embedded_storage::storage::{Address, AddressOffset, AddressRange};
+/// let body = [0x5Au8; 20];
+/// let mut image = [0u8; ImageHeader::SIZE + 20];
+/// image[..ImageHeader::SIZE].copy_from_slice(&ImageHeader::for_body(&body).to_bytes());
+/// image[ImageHeader::SIZE..].copy_from_slice(&body);
+/// image[20] ^= 0x01;
+///
+/// let slot = AddressRange::new(Address(0), AddressOffset(64));
+/// let mut update = UpdateController::new(MemStorage::<64>::new(32), slot);
+/// update.start(image.len()).unwrap();
+/// let progress = update.feed(&image).unwrap();
+/// assert!(progress.is_complete());
+/// assert_eq!(update.finish(), Err(Error::Crc));
+/// assert!(verify_image(&mut update.into_inner(), Address(0)).is_err());
+/// ```
+pub struct UpdateController<T, U> {
+	dev: T,
+	slot: AddressRange<U>,
+	state: State,
+	total: usize,
+	received: usize,
+	header: [u8; ImageHeader::SIZE],
+	crc: Crc32Ieee,
+	erased: Address<U>,
+}
+
+impl<T, U, E> UpdateController<T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + Ord + From<u8> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Write updates to `slot` of `dev`
+	pub fn new(dev: T, slot: AddressRange<U>) -> Self {
+		UpdateController {
+			dev,
+			slot,
+			state: State::Idle,
+			total: 0,
+			received: 0,
+			header: [0; ImageHeader::SIZE],
+			crc: Crc32Ieee::new(),
+			erased: slot.start,
+		}
+	}
+
+	/// Start receiving an image of `total_len` bytes, including its header
+	pub fn start(&mut self, total_len: usize) -> Result<(), Error<E>> {
+		let capacity = len_from_offset(self.slot.len).ok_or(Error::Overflow)?;
+		if total_len > capacity {
+			return Err(Error::TooLarge);
+		}
+		if total_len < ImageHeader::SIZE {
+			return Err(Error::Length);
+		}
+		self.state = State::Receiving;
+		self.total = total_len;
+		self.received = 0;
+		self.crc.reset();
+		self.erased = self.slot.start;
+		Ok(())
+	}
+
+	/// Write the next chunk of the image
+	pub fn feed(&mut self, data: &[u8]) -> Result<Progress, Error<E>> {
+		if !matches!(self.state, State::Receiving) {
+			return Err(Error::State);
+		}
+		if data.len() > self.total - self.received {
+			return Err(Error::TooLarge);
+		}
+		let mut data = data;
+		if self.received < ImageHeader::SIZE {
+			let n = (ImageHeader::SIZE - self.received).min(data.len());
+			self.header[self.received..self.received + n].copy_from_slice(&data[..n]);
+			self.received += n;
+			data = &data[n..];
+			if self.received == ImageHeader::SIZE {
+				let header = ImageHeader::from_bytes(&self.header).ok_or(Error::BadMagic)?;
+				if u32::try_from(self.total - ImageHeader::SIZE) != Ok(header.len) {
+					return Err(Error::Length);
+				}
+			}
+		}
+		for chunk in data.chunks(CHUNK_SIZE) {
+			let address = self.address(self.received)?;
+			self.erase_to(self.address(self.received + chunk.len())?)?;
+			let mut buf = [0; CHUNK_SIZE];
+			let buf = &mut buf[..chunk.len()];
+			buf.copy_from_slice(chunk);
+			nb::block!(self.dev.try_write_slice(address, buf))?;
+			self.crc.update(chunk);
+			self.received += chunk.len();
+		}
+		Ok(self.progress())
+	}
+
+	/// Check the CRC of the received image and write its header, marking the slot bootable
+	pub fn finish(&mut self) -> Result<(), Error<E>> {
+		if !matches!(self.state, State::Receiving) {
+			return Err(Error::State);
+		}
+		if self.received != self.total {
+			return Err(Error::Incomplete);
+		}
+		self.state = State::Idle;
+		let header = ImageHeader::from_bytes(&self.header).ok_or(Error::BadMagic)?;
+		if self.crc.finish() != header.crc {
+			return Err(Error::Crc);
+		}
+		self.erase_to(self.address(ImageHeader::SIZE)?)?;
+		nb::block!(self.dev.try_write_slice(self.slot.start, &mut self.header))?;
+		self.state = State::Done;
+		Ok(())
+	}
+
+	/// How much of the current image was received
+	pub fn progress(&self) -> Progress {
+		Progress {
+			received: self.received,
+			total: self.total,
+		}
+	}
+
+	/// Whether the last image was received and marked bootable
+	pub fn is_done(&self) -> bool {
+		matches!(self.state, State::Done)
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+
+	/// The address of byte `offset` of the image
+	fn address(&self, offset: usize) -> Result<Address<U>, Error<E>> {
+		Ok(&self.slot.start + &offset_from_len(offset).ok_or(Error::Overflow)?)
+	}
+
+	/// Erase pages until the erased part of the slot reaches `end`
+	fn erase_to(&mut self, end: Address<U>) -> Result<(), Error<E>> {
+		while self.erased < end {
+			let size = nb::block!(self.dev.try_page_size(self.erased))?;
+			if size.0 == U::from(0) {
+				// A zero sized page would never advance
+				return Err(Error::TooLarge);
+			}
+			nb::block!(self.dev.try_erase_address(self.erased))?;
+			self.erased = &self.erased + &size;
+		}
+		Ok(())
+	}
+}
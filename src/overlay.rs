@@ -0,0 +1,337 @@
+//! Overlay
+//!
+//! Stage writes to a region in RAM, and write them to the device on demand.
+
+use crate::helpers::Error;
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, Flush, MultiRead, MultiWrite, ReadAccess,
+	StorageSize,
+};
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+
+/// Copy on write layer staging the writes to a region of a device in RAM.
+///
+/// The region starts at `base` and is as long as `arena`, which holds the
+/// staged words. Writes to the region replace the previous contents in the
+/// arena, as in RAM, and leave the device untouched. Writes outside the
+/// region fail with [`Error::OutOfBounds`]. Reads see the staged writes
+/// merged over the contents of the device.
+///
+/// The staged words are tracked as one dirty range, from the lowest to the
+/// highest word written. [`commit`](Overlay::commit) erases and programs
+/// every page overlapping it, so the region should start and end on page
+/// boundaries. [`discard`](Overlay::discard) drops the staged writes. The
+/// operations block on the device, and never return `WouldBlock`.
+///
+/// Pages are located relative to the start address of the device, assuming
+/// all pages up to an address are the size of the page containing it.
+///
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::overlay::Overlay;
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+///
+/// let mut storage = MemStorage::<32>::new(8);
+/// storage.try_write_slice(Address(8), &mut [1, 2, 3, 4]).unwrap();
+///
+/// let mut arena = [0u8; 16];
+/// let mut overlay = Overlay::new(storage, Address(8), &mut arena).unwrap();
+/// overlay.try_write_slice(Address(9), &mut [0xAA, 0xBB]).unwrap();
+///
+/// // Reads see the staged writes, the device doesn't
+/// let mut buf = [0u8; 4];
+/// overlay.try_read_slice(Address(8), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 0xAA, 0xBB, 4]);
+/// assert_eq!(overlay.get_ref().as_slice()[8..12], [1, 2, 3, 4]);
+///
+/// overlay.discard();
+/// overlay.try_read_slice(Address(8), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2, 3, 4]);
+///
+/// overlay.try_write_slice(Address(11), &mut [5, 6]).unwrap();
+/// overlay.commit().unwrap();
+/// assert!(!overlay.is_dirty());
+/// let storage = overlay.into_inner();
+/// assert_eq!(storage.as_slice()[8..14], [1, 2, 3, 5, 6, 0xFF]);
+/// ```
+///
+/// The same on a device returning `WouldBlock`, prepared and checked with
+/// the [`test_util`](crate::test_util) helpers:
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use embedded_storage::mem::MemStorageBuilder;
+/// use embedded_storage::overlay::Overlay;
+/// use embedded_storage::storage::{Address, MultiWrite};
+/// use embedded_storage::test_util::{block_erase, block_read, block_write};
+///
+/// let mut storage = MemStorageBuilder::<32>::new().page_size(8).would_block_cycles(2).build();
+/// block_write(&mut storage, Address(8), &mut [1, 2, 3, 4]).unwrap();
+/// // A stale byte after the region, erased before staging
+/// block_write(&mut storage, Address(16), &mut [7]).unwrap();
+/// block_erase(&mut storage, Address(16)).unwrap();
+///
+/// let mut arena = [0u8; 16];
+/// let mut overlay = Overlay::new(storage, Address(8), &mut arena).unwrap();
+/// overlay.try_write_slice(Address(9), &mut [0xAA, 0xBB]).unwrap();
+/// overlay.commit().unwrap();
+///
+/// let mut storage = overlay.into_inner();
+/// let mut buf = [0u8; 5];
+/// block_read(&mut storage, Address(8), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 0xAA, 0xBB, 4, 0xFF]);
+/// block_read(&mut storage, Address(16), &mut buf[..1]).unwrap();
+/// assert_eq!(buf[0], 0xFF);
+/// # }
+/// ```
+pub struct Overlay<'a, T, Word, U> {
+	dev: T,
+	start: usize,
+	base: usize,
+	arena: &'a mut [Word],
+	dirty: Option<(usize, usize)>,
+	_address: PhantomData<U>,
+}
+
+impl<'a, T, Word, U> Overlay<'a, T, Word, U> {
+	/// Whether there are staged writes
+	pub fn is_dirty(&self) -> bool {
+		self.dirty.is_some()
+	}
+
+	/// Drop the staged writes
+	pub fn discard(&mut self) {
+		self.dirty = None;
+	}
+
+	/// Borrow the device
+	pub fn get_ref(&self) -> &T {
+		&self.dev
+	}
+
+	/// Release the device, dropping the staged writes
+	///
+	/// Call [`commit`](Overlay::commit) first to keep them.
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+}
+
+impl<'a, T, Word, U, E> Overlay<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>,
+	Word: Copy,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Wrap `dev`, staging the writes to the region starting at `base` in `arena`
+	pub fn new(mut dev: T, base: Address<U>, arena: &'a mut [Word]) -> Result<Self, Error<E>> {
+		let start = nb::block!(dev.try_start_address())?;
+		Ok(Overlay {
+			dev,
+			start: index(start)?,
+			base: index(base)?,
+			arena,
+			dirty: None,
+			_address: PhantomData,
+		})
+	}
+
+	/// Erase and program every page overlapping the staged writes
+	pub fn commit(&mut self) -> Result<(), Error<E>> {
+		let (from, to) = match self.dirty {
+			Some(range) => range,
+			None => return Ok(()),
+		};
+		let mut offset = from;
+		while offset < to {
+			let address = address(self.base + offset)?;
+			let size = nb::block!(self.dev.try_page_size(address))?;
+			let size = usize::try_from(size.0).map_err(|_| Error::Overflow)?;
+			if size == 0 {
+				// A zero sized page would never advance
+				return Err(Error::OutOfBounds);
+			}
+			let at = self.base + offset;
+			let page = at - at.checked_sub(self.start).ok_or(Error::OutOfBounds)? % size;
+			if page < self.base || page - self.base + size > self.arena.len() {
+				return Err(Error::NotAligned);
+			}
+			let page = page - self.base;
+			self.extend(page, page + size)?;
+			let address = self::address(self.base + page)?;
+			nb::block!(self.dev.try_erase_address(address))?;
+			nb::block!(self
+				.dev
+				.try_write_slice(address, &mut self.arena[page..page + size]))?;
+			offset = page + size;
+		}
+		self.dirty = None;
+		Ok(())
+	}
+
+	/// Grow the dirty range to cover `from..to`, loading the words it didn't cover from the device
+	fn extend(&mut self, from: usize, to: usize) -> Result<(), Error<E>> {
+		let (start, end) = match self.dirty {
+			Some((start, end)) => {
+				if from < start {
+					self.load(from, start)?;
+				}
+				if to > end {
+					self.load(end, to)?;
+				}
+				(start.min(from), end.max(to))
+			}
+			None => {
+				self.load(from, to)?;
+				(from, to)
+			}
+		};
+		self.dirty = Some((start, end));
+		Ok(())
+	}
+
+	fn load(&mut self, from: usize, to: usize) -> Result<(), Error<E>> {
+		let address = address(self.base + from)?;
+		nb::block!(self.dev.try_read_slice(address, &mut self.arena[from..to]))?;
+		Ok(())
+	}
+}
+
+fn index<U, E>(address: Address<U>) -> Result<usize, Error<E>>
+where
+	usize: TryFrom<U>,
+{
+	usize::try_from(address.0).map_err(|_| Error::Overflow)
+}
+
+fn address<U: TryFrom<usize>, E>(index: usize) -> Result<Address<U>, Error<E>> {
+	U::try_from(index).map(Address).map_err(|_| Error::Overflow)
+}
+
+impl<'a, T, Word, U, E> MultiRead<Word, U> for Overlay<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>,
+	Word: Copy,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<E>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let mut at = index(address)?;
+		let mut buf = buf;
+		while !buf.is_empty() {
+			let n = match self.dirty {
+				Some((from, to)) if at >= self.base + from && at < self.base + to => {
+					let offset = at - self.base;
+					let n = (to - offset).min(buf.len());
+					buf[..n].copy_from_slice(&self.arena[offset..offset + n]);
+					n
+				}
+				dirty => {
+					let n = match dirty {
+						Some((from, _)) if at < self.base + from => {
+							(self.base + from - at).min(buf.len())
+						}
+						_ => buf.len(),
+					};
+					let address = self::address(at)?;
+					nb::block!(self.dev.try_read_slice(address, &mut buf[..n]))
+						.map_err(Error::Device)?;
+					n
+				}
+			};
+			at += n;
+			buf = &mut buf[n..];
+		}
+		Ok(())
+	}
+}
+
+impl<'a, T, Word, U, E> MultiWrite<Word, U> for Overlay<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>,
+	Word: Copy,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<E>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let at = index(address)?;
+		if at < self.base
+			|| buf.len() > self.arena.len()
+			|| at - self.base > self.arena.len() - buf.len()
+		{
+			return Err(nb::Error::Other(Error::OutOfBounds));
+		}
+		let offset = at - self.base;
+		self.extend(offset, offset + buf.len())?;
+		self.arena[offset..offset + buf.len()].copy_from_slice(buf);
+		Ok(())
+	}
+}
+
+impl<'a, T, Word, U, E> Flush for Overlay<'a, T, Word, U>
+where
+	T: MultiRead<Word, U, Error = E>
+		+ MultiWrite<Word, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<Word, U, Error = E>
+		+ Flush<Error = E>,
+	Word: Copy,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<E>;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.commit()?;
+		self.dev.try_flush().map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<'a, T, Word, U> StorageSize<Word, U> for Overlay<'a, T, Word, U>
+where
+	T: StorageSize<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		self.dev.try_start_address()
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.dev.try_total_size()
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.dev.try_page_size(address)
+	}
+}
+
+impl<'a, T, Word, U> ReadAccess for Overlay<'a, T, Word, U>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.dev.access_pattern()
+	}
+}
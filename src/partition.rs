@@ -0,0 +1,372 @@
+//! Partition
+//!
+//! A view of part of a device, with its own address space starting at zero.
+
+use crate::checked::Error;
+use crate::helpers::PageIter;
+#[cfg(feature = "shared")]
+use crate::shared::{self, BusManager, BusProxy};
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, Flush, MultiRead, MultiWrite, Page,
+	ReadAccess, Semantics, SingleRead, SingleWrite, StorageSize, WriteSemantics,
+};
+use core::convert::TryFrom;
+use core::ops::{Add, Sub};
+
+/// A region of a device, starting at `base` and `len` words long.
+///
+/// Addresses and pages are relative to the start of the partition, so
+/// address zero is `base` on the device. Accesses beyond the end of the
+/// partition are rejected with [`Error::OutOfBounds`] or
+/// [`Error::PageOutOfBounds`] without accessing the device.
+///
+/// ```
+/// use embedded_storage::checked::Error;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::partition::Partition;
+/// use embedded_storage::storage::{Address, AddressOffset, ErasePage, MultiWrite, Page, StorageSize};
+///
+/// let storage = MemStorage::<64>::new(16);
+/// let mut partition = Partition::new(storage, Address(16), AddressOffset(32)).unwrap();
+/// assert_eq!(partition.try_start_address(), Ok(Address(0)));
+/// assert_eq!(partition.try_total_size(), Ok(AddressOffset(32)));
+///
+/// partition.try_write_slice(Address(0), &mut [1, 2]).unwrap();
+/// assert_eq!(
+///     partition.try_write_slice(Address(31), &mut [3, 4]),
+///     Err(nb::Error::Other(Error::OutOfBounds { address: Address(31) }))
+/// );
+/// assert_eq!(
+///     partition.try_erase_page(Page(2)),
+///     Err(nb::Error::Other(Error::PageOutOfBounds { page: Page(2) }))
+/// );
+///
+/// let storage = partition.into_inner();
+/// assert_eq!(storage.as_slice()[16..18], [1, 2]);
+/// ```
+pub struct Partition<T, U> {
+	inner: T,
+	base: Address<U>,
+	len: AddressOffset<U>,
+	first_page: U,
+	pages: U,
+}
+
+impl<T, U> Partition<T, U>
+where
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	/// Create a partition of `inner` from `base`, `len` words long.
+	///
+	/// Both the start and the end of the partition must be on a page boundary,
+	/// so erasing a page of the partition never erases data outside of it.
+	/// The end of the device is a page boundary, so `base` may be the end of
+	/// the device for an empty partition.
+	pub fn new<Word>(
+		mut inner: T,
+		base: Address<U>,
+		len: AddressOffset<U>,
+	) -> Result<Self, Error<T::Error, U>>
+	where
+		T: StorageSize<Word, U>,
+	{
+		let start = nb::block!(inner.try_start_address()).map_err(Error::Device)?;
+		let size = nb::block!(inner.try_total_size()).map_err(Error::Device)?;
+		let device_end = &start + &size;
+		if base < start || base > device_end || len.0 > device_end.0 - base.0 {
+			return Err(Error::OutOfBounds { address: base });
+		}
+		let end = &base + &len;
+
+		let mut first_page = None;
+		let mut end_aligned = end == device_end;
+		let mut pages = U::from(0);
+		let mut device_pages = U::from(0);
+		let mut iter = PageIter::new(&mut inner).map_err(Error::Device)?;
+		for (page, address) in &mut iter {
+			device_pages = device_pages + U::from(1);
+			if address == base {
+				first_page = Some(page.0);
+			}
+			if address == end {
+				end_aligned = true;
+			}
+			if address >= end {
+				break;
+			}
+			if address >= base {
+				pages = pages + U::from(1);
+			}
+		}
+		if let Some(e) = iter.into_error() {
+			return Err(Error::Device(e));
+		}
+		let first_page = match first_page {
+			Some(page) if end_aligned => page,
+			// An empty partition at the end of the device, after its last page
+			None if base == device_end => device_pages,
+			Some(_) => return Err(Error::Misaligned { address: end }),
+			None => return Err(Error::Misaligned { address: base }),
+		};
+
+		Ok(Partition {
+			inner,
+			base,
+			len,
+			first_page,
+			pages,
+		})
+	}
+
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.inner
+	}
+
+	/// Check that `len` words from the partition relative `address` are
+	/// within the partition, returning the address on the device
+	fn map<E>(&self, address: Address<U>, len: usize) -> Result<Address<U>, Error<E, U>> {
+		let fits = match U::try_from(len) {
+			Ok(len) => address.0 <= self.len.0 && len <= self.len.0 - address.0,
+			Err(_) => false,
+		};
+		if fits {
+			Ok(&self.base + &AddressOffset(address.0))
+		} else {
+			Err(Error::OutOfBounds { address })
+		}
+	}
+}
+
+impl<T, Word, U> SingleRead<Word, U> for Partition<T, U>
+where
+	T: SingleRead<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = Error<T::Error, U>;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		let address = self.map(address, 1)?;
+		self.inner
+			.try_read(address)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, Word, U> SingleWrite<Word, U> for Partition<T, U>
+where
+	T: SingleWrite<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = Error<T::Error, U>;
+
+	fn try_write(&mut self, address: Address<U>, word: Word) -> nb::Result<(), Self::Error> {
+		let address = self.map(address, 1)?;
+		self.inner
+			.try_write(address, word)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for Partition<T, U>
+where
+	T: MultiRead<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = Error<T::Error, U>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let address = self.map(address, buf.len())?;
+		self.inner
+			.try_read_slice(address, buf)
+			.map_err(|e| e.map(Error::Device))
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		let address = self.map(address, buf.len())?;
+		self.inner
+			.try_read_partial(address, buf)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, Word, U> MultiWrite<Word, U> for Partition<T, U>
+where
+	T: MultiWrite<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = Error<T::Error, U>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let address = self.map(address, buf.len())?;
+		self.inner
+			.try_write_slice(address, buf)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, U> ErasePage<U> for Partition<T, U>
+where
+	T: ErasePage<U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = Error<T::Error, U>;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		if page.0 >= self.pages {
+			return Err(nb::Error::Other(Error::PageOutOfBounds { page }));
+		}
+		self.inner
+			.try_erase_page(Page(self.first_page + page.0))
+			.map_err(|e| e.map(Error::Device))
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		let address = self.map(address, 1)?;
+		self.inner
+			.try_erase_address(address)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, Word, U> StorageSize<Word, U> for Partition<T, U>
+where
+	T: StorageSize<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	type Error = Error<T::Error, U>;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		Ok(Address(U::from(0)))
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		Ok(self.len)
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		let address = self.map(address, 1)?;
+		self.inner
+			.try_page_size(address)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, U> Flush for Partition<T, U>
+where
+	T: Flush,
+{
+	type Error = Error<T::Error, U>;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.inner.try_flush().map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, U> WriteSemantics for Partition<T, U>
+where
+	T: WriteSemantics,
+{
+	fn write_semantics(&self) -> Semantics {
+		self.inner.write_semantics()
+	}
+}
+
+impl<T, U> ReadAccess for Partition<T, U>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.inner.access_pattern()
+	}
+}
+
+/// Split a device owned by a [`BusManager`] at `boundary` into two
+/// partitions, the first ending and the second starting at `boundary`.
+///
+/// The partitions reach the device through [`BusProxy`] handles, so a call
+/// made while the device is in use fails with [`shared::Error::Busy`]. The
+/// boundary must be on a page boundary, otherwise [`Error::Misaligned`] is
+/// returned.
+///
+/// ```
+/// # #[cfg(feature = "shared")]
+/// # {
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::partition::split_at;
+/// use embedded_storage::shared::{self, BusManager};
+/// use embedded_storage::storage::{Address, AddressOffset, ErasePage, MultiRead, MultiWrite, Page, StorageSize};
+///
+/// let manager = BusManager::new(MemStorage::<64>::new(16));
+/// let (mut low, mut high) = split_at(&manager, Address(16)).unwrap();
+/// assert_eq!(low.try_total_size(), Ok(AddressOffset(16)));
+/// assert_eq!(high.try_total_size(), Ok(AddressOffset(48)));
+///
+/// low.try_write_slice(Address(0), &mut [1, 2]).unwrap();
+/// high.try_write_slice(Address(0), &mut [3, 4]).unwrap();
+/// high.try_erase_page(Page(0)).unwrap();
+///
+/// let mut buf = [0u8; 2];
+/// low.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2]);
+/// high.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [0xFF, 0xFF]);
+///
+/// let lock = manager.try_lock().unwrap();
+/// assert_eq!(
+///     low.try_read_slice(Address(0), &mut buf),
+///     Err(nb::Error::Other(embedded_storage::checked::Error::Device(shared::Error::Busy)))
+/// );
+/// drop(lock);
+///
+/// assert!(split_at(&manager, Address(20)).is_err());
+///
+/// // Splitting at either end leaves one partition empty
+/// let (_, mut high) = split_at(&manager, Address(64)).unwrap();
+/// assert_eq!(high.try_total_size(), Ok(AddressOffset(0)));
+/// let (mut low, _) = split_at(&manager, Address(0)).unwrap();
+/// assert_eq!(low.try_total_size(), Ok(AddressOffset(0)));
+/// # }
+/// ```
+#[cfg(feature = "shared")]
+#[allow(clippy::type_complexity)]
+pub fn split_at<'a, T, Word, U>(
+	manager: &'a BusManager<T>,
+	boundary: Address<U>,
+) -> Result<
+	(Partition<BusProxy<'a, T>, U>, Partition<BusProxy<'a, T>, U>),
+	Error<shared::Error<T::Error>, U>,
+>
+where
+	T: StorageSize<Word, U>,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + From<u8> + Copy,
+{
+	let mut dev = manager.acquire();
+	let start =
+		nb::block!(StorageSize::<Word, U>::try_start_address(&mut dev)).map_err(Error::Device)?;
+	let size =
+		nb::block!(StorageSize::<Word, U>::try_total_size(&mut dev)).map_err(Error::Device)?;
+	let end = &start + &size;
+	if boundary < start || boundary > end {
+		return Err(Error::OutOfBounds { address: boundary });
+	}
+	let low = Partition::new(dev, start, AddressOffset(boundary.0 - start.0))?;
+	let high = Partition::new(
+		manager.acquire(),
+		boundary,
+		AddressOffset(end.0 - boundary.0),
+	)?;
+	Ok((low, high))
+}
@@ -0,0 +1,71 @@
+//! Prelude
+//!
+//! The common types and traits, for importing with
+//! `use embedded_storage::prelude::*;`.
+//!
+//! Only the generic types of [`storage`](crate::storage) are exported. The
+//! crate root has an older `Address` and `ReadWrite` of the same names, so
+//! don't glob import both. Error types are left out, as most modules have
+//! their own `Error`.
+//!
+//! ```
+//! use embedded_storage::prelude::*;
+//!
+//! fn copy_page<T, E>(dev: &mut T, from: Address<u32>, to: Address<u32>) -> nb::Result<(), E>
+//! where
+//!     T: MultiRead<u8, u32, Error = E> + MultiWrite<u8, u32, Error = E> + ErasePage<u32, Error = E>,
+//! {
+//!     let mut buf = [0u8; 16];
+//!     dev.try_read_slice(from, &mut buf)?;
+//!     dev.try_erase_address(to)?;
+//!     dev.try_write_slice(to, &mut buf)
+//! }
+//!
+//! fn first_byte<T: ByteRead>(dev: &mut T) -> nb::Result<u8, T::Error> {
+//!     let mut buf = [0u8; 1];
+//!     dev.try_read_slice(Address32::from(0), &mut buf)?;
+//!     Ok(buf[0])
+//! }
+//! # struct Flash([u8; 32]);
+//! # impl MultiRead<u8, u32> for Flash {
+//! #     type Error = ();
+//! #     fn try_read_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), ()> {
+//! #         let start = address.0 as usize;
+//! #         buf.copy_from_slice(&self.0[start..start + buf.len()]);
+//! #         Ok(())
+//! #     }
+//! # }
+//! # impl MultiWrite<u8, u32> for Flash {
+//! #     type Error = ();
+//! #     fn try_write_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), ()> {
+//! #         let start = address.0 as usize;
+//! #         self.0[start..start + buf.len()].copy_from_slice(buf);
+//! #         Ok(())
+//! #     }
+//! # }
+//! # impl ErasePage<u32> for Flash {
+//! #     type Error = ();
+//! #     fn try_erase_page(&mut self, page: Page<u32>) -> nb::Result<(), ()> {
+//! #         self.try_erase_address(Address(page.0 * 16))
+//! #     }
+//! #     fn try_erase_address(&mut self, address: Address<u32>) -> nb::Result<(), ()> {
+//! #         let start = address.0 as usize;
+//! #         self.0[start..start + 16].fill(0xFF);
+//! #         Ok(())
+//! #     }
+//! # }
+//!
+//! let mut flash = Flash([7; 32]);
+//! flash.0[0] = 1;
+//! copy_page(&mut flash, Address(0), Address(16)).unwrap();
+//! assert_eq!(flash.0[16], 1);
+//! assert_eq!(first_byte(&mut flash), Ok(1));
+//! ```
+
+pub use crate::aliases::{
+	Address32, AddressOffset32, ByteFlash, ByteRead, ByteStorage, ByteWrite, Page32,
+};
+pub use crate::storage::{
+	Address, AddressOffset, AddressRange, ErasePage, Flush, MultiRead, MultiWrite, Page, ReadWrite,
+	SingleRead, SingleWrite, StorageError, StorageSize,
+};
@@ -0,0 +1,135 @@
+//! Read Only
+//!
+//! A wrapper exposing only the read traits of a device.
+
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, MultiRead, ReadAccess, SingleRead, StorageSize,
+};
+
+/// Read only view of a device.
+///
+/// `ReadOnly` forwards [`SingleRead`], [`MultiRead`] and [`StorageSize`] to the
+/// wrapped device, and implements none of the write or erase traits. Code
+/// given a `ReadOnly` can't modify the device, which is checked at compile
+/// time rather than at run time. The device is private to the wrapper, and
+/// only [`get_ref`](ReadOnly::get_ref) reaches it, through a shared
+/// reference. This is the recommended way to hand out regions that must
+/// never be written, such as factory calibration data.
+///
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::read_only::ReadOnly;
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(0), &mut [1, 2]).unwrap();
+///
+/// let mut calibration = ReadOnly::new(storage);
+/// assert_eq!(calibration.get_ref().as_slice()[..2], [1, 2]);
+/// let mut buf = [0u8; 2];
+/// calibration.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [1, 2]);
+/// ```
+///
+/// Writing through the wrapper doesn't compile.
+/// ```compile_fail,E0599
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::read_only::ReadOnly;
+/// use embedded_storage::storage::{Address, MultiWrite};
+///
+/// let mut calibration = ReadOnly::new(MemStorage::<16>::new(16));
+/// calibration.try_write_slice(Address(0), &mut [1, 2]).unwrap();
+/// ```
+///
+/// Neither does writing to the wrapped device.
+/// ```compile_fail,E0616
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::read_only::ReadOnly;
+/// use embedded_storage::storage::{Address, MultiWrite};
+///
+/// let mut calibration = ReadOnly::new(MemStorage::<16>::new(16));
+/// calibration.0.try_write_slice(Address(0), &mut [1, 2]).unwrap();
+/// ```
+pub struct ReadOnly<T>(T);
+
+impl<T> ReadOnly<T> {
+	/// Wrap `dev`, leaving only its read traits reachable
+	pub fn new(dev: T) -> Self {
+		ReadOnly(dev)
+	}
+
+	/// Borrow the wrapped device
+	pub fn get_ref(&self) -> &T {
+		&self.0
+	}
+
+	/// Release the wrapped device.
+	///
+	/// This is the one way back to write access, so code meant to only read
+	/// should be given the wrapper, not be allowed to consume it.
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<T, Word, U> SingleRead<Word, U> for ReadOnly<T>
+where
+	T: SingleRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		self.0.try_read(address)
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for ReadOnly<T>
+where
+	T: MultiRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.0.try_read_slice(address, buf)
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		self.0.try_read_partial(address, buf)
+	}
+}
+
+impl<T, Word, U> StorageSize<Word, U> for ReadOnly<T>
+where
+	T: StorageSize<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		self.0.try_start_address()
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.0.try_total_size()
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.0.try_page_size(address)
+	}
+}
+
+impl<T> ReadAccess for ReadOnly<T>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.0.access_pattern()
+	}
+}
@@ -0,0 +1,274 @@
+//! Record
+//!
+//! Read sequences of records stored with a selectable framing.
+//!
+//! A [`Framing`] decides where a record starts and ends. [`LengthPrefixed`]
+//! stores a length before the data, [`Terminated`] ends every record with a
+//! terminator byte. Both treat erased memory as the end of the records, so
+//! records appended to erased flash can be read back with [`RecordReader`].
+
+use crate::helpers::{len_from_offset, offset_from_len, Error};
+use crate::storage::{Address, AddressOffset, AddressRange, MultiRead};
+use core::convert::TryFrom;
+use core::ops::{Add, Sub};
+
+/// A record found by a [`Framing`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+	/// The length of the record data, which was read into the start of the buffer
+	pub len: usize,
+	/// The number of bytes the framed record takes on the device
+	pub size: usize,
+}
+
+/// How records are delimited on the device.
+pub trait Framing {
+	/// Read the record at `address` into `buf`.
+	///
+	/// At most `available` bytes from `address` belong to the records.
+	/// Returns `None` if there are no more records.
+	fn read_frame<T, U>(
+		&mut self,
+		dev: &mut T,
+		address: Address<U>,
+		available: usize,
+		buf: &mut [u8],
+	) -> Result<Option<Frame>, Error<T::Error>>
+	where
+		T: MultiRead<u8, U> + ?Sized,
+		U: Add<U, Output = U> + TryFrom<usize> + Copy;
+}
+
+/// Records stored as a two byte little endian length followed by the data.
+///
+/// A length with both bytes equal to `erase_byte`, as left by an erase,
+/// ends the records. The default erase byte is `0xFF`.
+///
+/// ```
+/// use embedded_storage::helpers::Error;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::record::{LengthPrefixed, RecordReader};
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MultiWrite};
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(0), &mut [2, 0, 0xAA, 0xBB, 0, 0, 1, 0, 0xCC]).unwrap();
+///
+/// let mut buf = [0u8; 4];
+/// let range = AddressRange::new(Address(0), AddressOffset(16));
+/// let mut records = RecordReader::new(&mut storage, range, LengthPrefixed::default(), &mut buf);
+/// assert_eq!(records.next_record(), Some(Ok(&[0xAA, 0xBB][..])));
+/// assert_eq!(records.next_record(), Some(Ok(&[][..])));
+/// assert_eq!(records.next_record(), Some(Ok(&[0xCC][..])));
+/// assert_eq!(records.next_record(), None);
+///
+/// // A record longer than the buffer
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(0), &mut [8, 0]).unwrap();
+/// let mut records = RecordReader::new(&mut storage, range, LengthPrefixed::default(), &mut buf);
+/// assert_eq!(records.next_record(), Some(Err(Error::BufferTooSmall)));
+/// assert_eq!(records.next_record(), None);
+///
+/// // A device erasing to zero, where a length of zero ends the records
+/// let framing = LengthPrefixed { erase_byte: 0x00 };
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(0), &mut [1, 0, 0xDD, 0, 0]).unwrap();
+/// let mut records = RecordReader::new(&mut storage, range, framing, &mut buf);
+/// assert_eq!(records.next_record(), Some(Ok(&[0xDD][..])));
+/// assert_eq!(records.next_record(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthPrefixed {
+	/// The value of an erased byte on the device
+	pub erase_byte: u8,
+}
+
+impl Default for LengthPrefixed {
+	fn default() -> Self {
+		LengthPrefixed { erase_byte: 0xFF }
+	}
+}
+
+impl Framing for LengthPrefixed {
+	fn read_frame<T, U>(
+		&mut self,
+		dev: &mut T,
+		address: Address<U>,
+		available: usize,
+		buf: &mut [u8],
+	) -> Result<Option<Frame>, Error<T::Error>>
+	where
+		T: MultiRead<u8, U> + ?Sized,
+		U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	{
+		if available < 2 {
+			return Ok(None);
+		}
+		let mut header = [0; 2];
+		nb::block!(dev.try_read_slice(address, &mut header))?;
+		if header == [self.erase_byte; 2] {
+			return Ok(None);
+		}
+		let len = usize::from(u16::from_le_bytes(header));
+		if len > available - 2 {
+			return Err(Error::OutOfBounds);
+		}
+		let data = buf.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+		let offset = offset_from_len(2).ok_or(Error::Overflow)?;
+		nb::block!(dev.try_read_slice(&address + &offset, data))?;
+		Ok(Some(Frame { len, size: len + 2 }))
+	}
+}
+
+/// Records ended by a terminator byte, which can't appear in the data.
+///
+/// A record starting with the terminator ends the records, so records can't
+/// be empty. A terminator of `0xFF` makes erased memory the end of the
+/// records.
+///
+/// ```
+/// use embedded_storage::helpers::Error;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::record::{RecordReader, Terminated};
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, MultiWrite};
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(0), &mut b"ab\xFFcde\xFF".clone()).unwrap();
+///
+/// let mut buf = [0u8; 4];
+/// let range = AddressRange::new(Address(0), AddressOffset(16));
+/// let mut records = RecordReader::new(&mut storage, range, Terminated(0xFF), &mut buf);
+/// assert_eq!(records.next_record(), Some(Ok(&b"ab"[..])));
+/// assert_eq!(records.next_record(), Some(Ok(&b"cde"[..])));
+/// assert_eq!(records.next_record(), None);
+///
+/// // No terminator within the buffer
+/// storage.try_write_slice(Address(7), &mut b"fghij".clone()).unwrap();
+/// let mut records = RecordReader::new(&mut storage, range, Terminated(0xFF), &mut buf);
+/// records.next_record();
+/// records.next_record();
+/// assert_eq!(records.next_record(), Some(Err(Error::BufferTooSmall)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Terminated(pub u8);
+
+impl Framing for Terminated {
+	fn read_frame<T, U>(
+		&mut self,
+		dev: &mut T,
+		address: Address<U>,
+		available: usize,
+		buf: &mut [u8],
+	) -> Result<Option<Frame>, Error<T::Error>>
+	where
+		T: MultiRead<u8, U> + ?Sized,
+		U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	{
+		if available == 0 {
+			return Ok(None);
+		}
+		// Room for the longest record fitting in `buf` and its terminator
+		let window = available.min(buf.len().saturating_add(1));
+		let mut read = 0;
+		while read < window {
+			let offset = offset_from_len(read).ok_or(Error::Overflow)?;
+			let byte = &mut [0];
+			nb::block!(dev.try_read_slice(&address + &offset, byte))?;
+			if byte[0] == self.0 {
+				if read == 0 {
+					return Ok(None);
+				}
+				return Ok(Some(Frame {
+					len: read,
+					size: read + 1,
+				}));
+			}
+			if read == buf.len() {
+				break;
+			}
+			buf[read] = byte[0];
+			read += 1;
+		}
+		if read == available {
+			// The last record runs to the end without a terminator
+			Err(Error::OutOfBounds)
+		} else {
+			Err(Error::BufferTooSmall)
+		}
+	}
+}
+
+/// Reads the records in a range of a device one after another.
+///
+/// Every record is read into `buf` and returned as a slice of it, so the
+/// reader can't implement [`Iterator`]. Call
+/// [`next_record`](RecordReader::next_record) until it returns `None`. After
+/// an error no more records are returned.
+pub struct RecordReader<'a, T: ?Sized, U, F> {
+	dev: &'a mut T,
+	address: Address<U>,
+	end: Address<U>,
+	framing: F,
+	buf: &'a mut [u8],
+	done: bool,
+}
+
+impl<'a, T, U, F> RecordReader<'a, T, U, F>
+where
+	T: MultiRead<u8, U> + ?Sized,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Ord + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+	F: Framing,
+{
+	/// Read the records in `range` of `dev` with `framing`, using `buf` for the record data
+	pub fn new(dev: &'a mut T, range: AddressRange<U>, framing: F, buf: &'a mut [u8]) -> Self {
+		RecordReader {
+			dev,
+			address: range.start,
+			end: range.end(),
+			framing,
+			buf,
+			done: false,
+		}
+	}
+
+	/// The address of the next record
+	pub fn address(&self) -> Address<U> {
+		self.address
+	}
+
+	/// Read the next record, or `None` if there are no more records
+	pub fn next_record(&mut self) -> Option<Result<&[u8], Error<T::Error>>> {
+		if self.done {
+			return None;
+		}
+		let available = if self.address < self.end {
+			len_from_offset(AddressOffset(self.end.0 - self.address.0)).unwrap_or(usize::MAX)
+		} else {
+			0
+		};
+		let frame = self
+			.framing
+			.read_frame(self.dev, self.address, available, self.buf)
+			.and_then(|frame| match frame {
+				Some(frame) => {
+					let size = offset_from_len(frame.size).ok_or(Error::Overflow)?;
+					Ok(Some((frame, size)))
+				}
+				None => Ok(None),
+			});
+		match frame {
+			Ok(Some((frame, size))) => {
+				self.address += size;
+				Some(Ok(&self.buf[..frame.len]))
+			}
+			Ok(None) => {
+				self.done = true;
+				None
+			}
+			Err(e) => {
+				self.done = true;
+				Some(Err(e))
+			}
+		}
+	}
+}
@@ -0,0 +1,237 @@
+//! Retry
+//!
+//! Bound the time spent polling a device that keeps returning `WouldBlock`.
+
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, ErrorKind, Flush, MultiRead, MultiWrite,
+	Page, ReadAccess, Semantics, SingleRead, SingleWrite, StorageError, StorageSize,
+	WriteSemantics,
+};
+
+/// Errors returned by [`Retry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// The device still returned `WouldBlock` after the maximum number of attempts
+	Timeout,
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::Timeout => ErrorKind::Timeout,
+		}
+	}
+}
+
+/// Retries every operation on `WouldBlock`, up to a maximum number of attempts.
+///
+/// Each method calls the wrapped device until it returns something other than
+/// `WouldBlock`, or until it was called `attempts` times, in which case
+/// [`Error::Timeout`] is returned. Other errors are returned immediately. The
+/// wrapper itself never returns `WouldBlock`.
+///
+/// ```
+/// use embedded_storage::retry::{Error, Retry};
+/// use embedded_storage::storage::{Address, SingleRead};
+///
+/// // Busy for `busy` polls after every access
+/// struct Device {
+///     busy: u32,
+///     polls: u32,
+/// }
+///
+/// impl SingleRead<u8, u32> for Device {
+///     type Error = ();
+///
+///     fn try_read(&mut self, _address: Address<u32>) -> nb::Result<u8, Self::Error> {
+///         if self.polls < self.busy {
+///             self.polls += 1;
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         self.polls = 0;
+///         Ok(0xAA)
+///     }
+/// }
+///
+/// let mut storage = Retry::new(Device { busy: 3, polls: 0 }, 4);
+/// assert_eq!(storage.try_read(Address(0)), Ok(0xAA));
+///
+/// // A device that never completes times out
+/// let mut storage = Retry::new(Device { busy: u32::MAX, polls: 0 }, 4);
+/// assert_eq!(storage.try_read(Address(0)), Err(nb::Error::Other(Error::Timeout)));
+/// assert_eq!(storage.into_inner().polls, 4);
+/// ```
+pub struct Retry<T> {
+	inner: T,
+	attempts: usize,
+}
+
+impl<T> Retry<T> {
+	/// Wrap `inner`, calling it at most `attempts` times per operation
+	pub fn new(inner: T, attempts: usize) -> Self {
+		Retry { inner, attempts }
+	}
+
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.inner
+	}
+}
+
+fn retry<R, E, F>(attempts: usize, mut f: F) -> nb::Result<R, Error<E>>
+where
+	F: FnMut() -> nb::Result<R, E>,
+{
+	for _ in 0..attempts {
+		match f() {
+			Ok(value) => return Ok(value),
+			Err(nb::Error::Other(e)) => return Err(nb::Error::Other(Error::Device(e))),
+			Err(nb::Error::WouldBlock) => {}
+		}
+	}
+	Err(nb::Error::Other(Error::Timeout))
+}
+
+impl<T, Word, U> SingleRead<Word, U> for Retry<T>
+where
+	T: SingleRead<Word, U>,
+	U: Copy,
+{
+	type Error = Error<T::Error>;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_read(address))
+	}
+}
+
+impl<T, Word, U> SingleWrite<Word, U> for Retry<T>
+where
+	T: SingleWrite<Word, U>,
+	Word: Copy,
+	U: Copy,
+{
+	type Error = Error<T::Error>;
+
+	fn try_write(&mut self, address: Address<U>, word: Word) -> nb::Result<(), Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_write(address, word))
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for Retry<T>
+where
+	T: MultiRead<Word, U>,
+	U: Copy,
+{
+	type Error = Error<T::Error>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_read_slice(address, buf))
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_read_partial(address, buf))
+	}
+}
+
+impl<T, Word, U> MultiWrite<Word, U> for Retry<T>
+where
+	T: MultiWrite<Word, U>,
+	U: Copy,
+{
+	type Error = Error<T::Error>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_write_slice(address, buf))
+	}
+}
+
+impl<T, U> ErasePage<U> for Retry<T>
+where
+	T: ErasePage<U>,
+	U: Copy,
+{
+	type Error = Error<T::Error>;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_erase_page(page))
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_erase_address(address))
+	}
+}
+
+impl<T, Word, U> StorageSize<Word, U> for Retry<T>
+where
+	T: StorageSize<Word, U>,
+	U: Copy,
+{
+	type Error = Error<T::Error>;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_start_address())
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_total_size())
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_page_size(address))
+	}
+}
+
+impl<T> Flush for Retry<T>
+where
+	T: Flush,
+{
+	type Error = Error<T::Error>;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		let inner = &mut self.inner;
+		retry(self.attempts, || inner.try_flush())
+	}
+}
+
+impl<T> WriteSemantics for Retry<T>
+where
+	T: WriteSemantics,
+{
+	fn write_semantics(&self) -> Semantics {
+		self.inner.write_semantics()
+	}
+}
+
+impl<T> ReadAccess for Retry<T>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.inner.access_pattern()
+	}
+}
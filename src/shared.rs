@@ -0,0 +1,254 @@
+//! Shared
+//!
+//! Share one device between several drivers, such as flash chips on the same
+//! SPI bus.
+
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, ErrorKind, Flush, MultiRead, MultiWrite,
+	Page, ReadAccess, Semantics, SingleRead, SingleWrite, StorageError, StorageSize,
+	WriteSemantics,
+};
+use core::cell::{RefCell, RefMut};
+
+/// Errors returned by [`BusProxy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// The device is in use by another proxy or through [`BusManager::try_lock`]
+	Busy,
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::Busy => ErrorKind::Other,
+		}
+	}
+}
+
+/// Owns the shared device and hands out [`BusProxy`] handles to it.
+///
+/// The device is borrowed for the duration of each call made through a
+/// proxy. A call made while the device is already borrowed, for example from
+/// within [`try_lock`](BusManager::try_lock), fails with [`Error::Busy`]
+/// instead of panicking.
+///
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::shared::{BusManager, Error};
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+///
+/// let manager = BusManager::new(MemStorage::<32>::new(16));
+/// let mut first = manager.acquire();
+/// let mut second = manager.acquire();
+///
+/// first.try_write_slice(Address(0), &mut [1, 2]).unwrap();
+/// second.try_write_slice(Address(16), &mut [3, 4]).unwrap();
+///
+/// let mut buf = [0u8; 2];
+/// for _ in 0..2 {
+///     first.try_read_slice(Address(0), &mut buf).unwrap();
+///     assert_eq!(buf, [1, 2]);
+///     second.try_read_slice(Address(16), &mut buf).unwrap();
+///     assert_eq!(buf, [3, 4]);
+/// }
+///
+/// let lock = manager.try_lock().unwrap();
+/// assert_eq!(first.try_read_slice(Address(0), &mut buf), Err(nb::Error::Other(Error::Busy)));
+/// drop(lock);
+/// first.try_read_slice(Address(0), &mut buf).unwrap();
+/// ```
+pub struct BusManager<T> {
+	bus: RefCell<T>,
+}
+
+impl<T> BusManager<T> {
+	/// Take ownership of the shared device
+	pub fn new(dev: T) -> Self {
+		BusManager {
+			bus: RefCell::new(dev),
+		}
+	}
+
+	/// Create a new handle to the device
+	pub fn acquire(&self) -> BusProxy<'_, T> {
+		BusProxy { bus: &self.bus }
+	}
+
+	/// Borrow the device directly, or `None` if it is in use
+	pub fn try_lock(&self) -> Option<RefMut<'_, T>> {
+		self.bus.try_borrow_mut().ok()
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.bus.into_inner()
+	}
+}
+
+/// A handle to the device owned by a [`BusManager`].
+pub struct BusProxy<'a, T> {
+	bus: &'a RefCell<T>,
+}
+
+impl<'a, T> BusProxy<'a, T> {
+	/// Borrow the device for a single call
+	fn bus<E>(&self) -> Result<RefMut<'a, T>, nb::Error<Error<E>>> {
+		self.bus
+			.try_borrow_mut()
+			.map_err(|_| nb::Error::Other(Error::Busy))
+	}
+}
+
+impl<'a, T, Word, U> SingleRead<Word, U> for BusProxy<'a, T>
+where
+	T: SingleRead<Word, U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		self.bus()?
+			.try_read(address)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<'a, T, Word, U> SingleWrite<Word, U> for BusProxy<'a, T>
+where
+	T: SingleWrite<Word, U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_write(&mut self, address: Address<U>, word: Word) -> nb::Result<(), Self::Error> {
+		self.bus()?
+			.try_write(address, word)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<'a, T, Word, U> MultiRead<Word, U> for BusProxy<'a, T>
+where
+	T: MultiRead<Word, U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.bus()?
+			.try_read_slice(address, buf)
+			.map_err(|e| e.map(Error::Device))
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		self.bus()?
+			.try_read_partial(address, buf)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<'a, T, Word, U> MultiWrite<Word, U> for BusProxy<'a, T>
+where
+	T: MultiWrite<Word, U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.bus()?
+			.try_write_slice(address, buf)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<'a, T, U> ErasePage<U> for BusProxy<'a, T>
+where
+	T: ErasePage<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		self.bus()?
+			.try_erase_page(page)
+			.map_err(|e| e.map(Error::Device))
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		self.bus()?
+			.try_erase_address(address)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<'a, T, Word, U> StorageSize<Word, U> for BusProxy<'a, T>
+where
+	T: StorageSize<Word, U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		self.bus()?
+			.try_start_address()
+			.map_err(|e| e.map(Error::Device))
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.bus()?
+			.try_total_size()
+			.map_err(|e| e.map(Error::Device))
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.bus()?
+			.try_page_size(address)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<'a, T> Flush for BusProxy<'a, T>
+where
+	T: Flush,
+{
+	type Error = Error<T::Error>;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.bus()?.try_flush().map_err(|e| e.map(Error::Device))
+	}
+}
+
+/// While the device is in use, reports [`Semantics::BitClearOnly`], which is
+/// correct for any device, if not always the most efficient.
+impl<'a, T> WriteSemantics for BusProxy<'a, T>
+where
+	T: WriteSemantics,
+{
+	fn write_semantics(&self) -> Semantics {
+		self.bus
+			.try_borrow()
+			.map_or(Semantics::BitClearOnly, |dev| dev.write_semantics())
+	}
+}
+
+/// While the device is in use, reports [`AccessPattern::Random`], making no
+/// assumption about the order of reads.
+impl<'a, T> ReadAccess for BusProxy<'a, T>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.bus
+			.try_borrow()
+			.map_or(AccessPattern::Random, |dev| dev.access_pattern())
+	}
+}
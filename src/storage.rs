@@ -4,14 +4,324 @@
 use nb;
 
 /// Address represents an unsigned integer. This allows for devices that have bigger or smaller address spaces than the host.
+///
+/// Addresses are ordered by their inner value.
+/// ```
+/// use embedded_storage::storage::Address;
+///
+/// assert!(Address(10u32) < Address(20u32));
+/// assert!(Address(20u32) > Address(10u32));
+/// assert_eq!(Address(10u32), Address(10u32));
+/// assert_ne!(Address(10u32), Address(20u32));
+/// assert_eq!(Address(10u32).max(Address(20u32)), Address(20u32));
+/// ```
+///
+/// Addresses are `Copy`, so passing one by value doesn't move it.
+/// ```
+/// use embedded_storage::storage::Address;
+///
+/// fn next(address: Address<u32>) -> Address<u32> {
+///     Address(address.0 + 1)
+/// }
+///
+/// let address = Address(0x1000u32);
+/// assert_eq!(next(address), Address(0x1001));
+/// assert_eq!(format!("{:?}", address), "Address(4096)");
+/// assert_eq!(format!("{:x?}", address), "Address(1000)");
+/// ```
+///
+/// Addresses can be used as keys of hashed collections.
+/// ```
+/// use embedded_storage::storage::{Address, Page};
+/// use std::collections::{HashMap, HashSet};
+///
+/// let mut pages = HashMap::new();
+/// pages.insert(Address(0x1000u32), Page(1u32));
+/// pages.insert(Address(0x2000u32), Page(2u32));
+/// assert_eq!(pages.get(&Address(0x1000)), Some(&Page(1)));
+///
+/// let set: HashSet<_> = [Page(1u32), Page(2), Page(1)].iter().copied().collect();
+/// assert_eq!(set.len(), 2);
+/// ```
+///
+/// With the `serde` feature, addresses serialize as their inner value.
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use embedded_storage::storage::{Address, AddressOffset, Page};
+///
+/// assert_eq!(serde_json::to_string(&Address(4096u32)).unwrap(), "4096");
+/// let address: Address<u32> = serde_json::from_str("4096").unwrap();
+/// assert_eq!(address, Address(4096));
+///
+/// let offset: AddressOffset<u16> = serde_json::from_str(&serde_json::to_string(&AddressOffset(32u16)).unwrap()).unwrap();
+/// assert_eq!(offset, AddressOffset(32));
+/// let page: Page<u8> = serde_json::from_str("3").unwrap();
+/// assert_eq!(page, Page(3));
+/// # }
+/// ```
+///
+/// With the `defmt` feature, they implement `defmt::Format` whenever `U` does.
+/// ```
+/// # #[cfg(feature = "defmt")]
+/// # {
+/// use embedded_storage::storage::{Address, AddressOffset, ErrorKind, Geometry, Page};
+///
+/// fn loggable<T: defmt::Format>(_: &T) {}
+///
+/// loggable(&Address(0x1000u32));
+/// loggable(&AddressOffset(16u16));
+/// loggable(&Page(3u8));
+/// loggable(&ErrorKind::OutOfBounds);
+/// loggable(&Geometry {
+///     start: Address(0u32),
+///     total_size: AddressOffset(4096),
+///     page_size: AddressOffset(256),
+///     is_uniform: true,
+/// });
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Address<U>(pub U);
 /// Address Offset represents an unsigned integer that is used as an optional offset from the base address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct AddressOffset<U>(pub U);
 
-use core::ops::{Add, Sub};
+/// Create an Address from its inner value.
+///
+/// ```
+/// use embedded_storage::storage::{Address, AddressOffset, Page};
+///
+/// let address: Address<u32> = 0x1000.into();
+/// assert_eq!(address, Address(0x1000));
+/// assert_eq!(address.into_inner(), 0x1000);
+///
+/// let offset: AddressOffset<u16> = 0x20.into();
+/// assert_eq!(offset.into_inner(), 0x20);
+///
+/// let page = Page::from(3u8);
+/// assert_eq!(page.into_inner(), 3);
+/// ```
+impl<U> From<U> for Address<U> {
+    fn from(value: U) -> Self {
+        Address(value)
+    }
+}
+
+impl<U> Address<U> {
+    /// Returns the inner value
+    pub fn into_inner(self) -> U {
+        self.0
+    }
+}
+
+impl<U> Address<U>
+where
+    U: Div<U, Output = U> + Rem<U, Output = U> + Copy,
+{
+    /// Returns the page containing the address, for pages of `page_size` words from address zero
+    ///
+    /// Panics if `page_size` is zero.
+    /// ```
+    /// use embedded_storage::storage::{Address, AddressOffset, Page};
+    ///
+    /// assert_eq!(Address(0u32).page(0x1000), Page(0));
+    /// assert_eq!(Address(0x1FFFu32).page(0x1000), Page(1));
+    /// assert_eq!(Address(0x2000u32).page(0x1000), Page(2));
+    /// assert_eq!(Address(0x2010u32).offset_in_page(0x1000), AddressOffset(0x10));
+    /// ```
+    pub fn page(&self, page_size: U) -> Page<U> {
+        Page(self.0 / page_size)
+    }
+
+    /// Returns the offset of the address from the start of its page
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn offset_in_page(&self, page_size: U) -> AddressOffset<U> {
+        AddressOffset(self.0 % page_size)
+    }
+}
+
+impl<U: CheckedArith + Copy> Address<U> {
+    /// Add an offset, returning `None` if the result overflows `U`
+    ///
+    /// ```
+    /// use embedded_storage::storage::{Address, AddressOffset};
+    ///
+    /// let address = Address(u32::MAX - 4);
+    /// assert_eq!(address.checked_add(&AddressOffset(4)), Some(Address(u32::MAX)));
+    /// assert_eq!(address.checked_add(&AddressOffset(5)), None);
+    /// ```
+    pub fn checked_add(&self, offset: &AddressOffset<U>) -> Option<Address<U>> {
+        self.0.checked_add(offset.0).map(Address)
+    }
+
+    /// Subtract an offset, returning `None` if the result underflows `U`
+    ///
+    /// ```
+    /// use embedded_storage::storage::{Address, AddressOffset};
+    ///
+    /// let address = Address(4u32);
+    /// assert_eq!(address.checked_sub(&AddressOffset(4)), Some(Address(0)));
+    /// assert_eq!(address.checked_sub(&AddressOffset(5)), None);
+    /// ```
+    pub fn checked_sub(&self, offset: &AddressOffset<U>) -> Option<Address<U>> {
+        self.0.checked_sub(offset.0).map(Address)
+    }
+}
+
+/// Integers supporting addition and subtraction with overflow checks.
+///
+/// Implemented for the primitive unsigned integers, so [`Address::checked_add`]
+/// and [`Address::checked_sub`] work for all usual address types.
+pub trait CheckedArith: Sized {
+    /// Add, returning `None` on overflow
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Subtract, returning `None` on underflow
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+impl<U: Saturating + Copy> Address<U> {
+    /// Add an offset, clamping at the largest value of `U`
+    ///
+    /// ```
+    /// use embedded_storage::storage::{Address, AddressOffset};
+    ///
+    /// assert_eq!(Address(u32::MAX - 4).saturating_add(&AddressOffset(16)), Address(u32::MAX));
+    /// assert_eq!(Address(4u32).saturating_add(&AddressOffset(16)), Address(20));
+    /// ```
+    pub fn saturating_add(&self, offset: &AddressOffset<U>) -> Address<U> {
+        Address(self.0.saturating_add(offset.0))
+    }
+
+    /// Subtract an offset, clamping at zero
+    ///
+    /// ```
+    /// use embedded_storage::storage::{Address, AddressOffset};
+    ///
+    /// assert_eq!(Address(0x10u32).saturating_sub(&AddressOffset(0x1000)), Address(0));
+    /// assert_eq!(Address(0x1010u32).saturating_sub(&AddressOffset(0x1000)), Address(0x10));
+    /// ```
+    pub fn saturating_sub(&self, offset: &AddressOffset<U>) -> Address<U> {
+        Address(self.0.saturating_sub(offset.0))
+    }
+}
+
+/// Integers supporting addition and subtraction that clamp instead of overflowing.
+///
+/// Implemented for the primitive unsigned integers, so [`Address::saturating_add`]
+/// and [`Address::saturating_sub`] work for all usual address types.
+pub trait Saturating {
+    /// Add, clamping at the largest value
+    fn saturating_add(self, other: Self) -> Self;
+
+    /// Subtract, clamping at the smallest value
+    fn saturating_sub(self, other: Self) -> Self;
+}
+
+macro_rules! impl_arith {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedArith for $t {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+            }
+
+            impl Saturating for $t {
+                fn saturating_add(self, other: Self) -> Self {
+                    <$t>::saturating_add(self, other)
+                }
+
+                fn saturating_sub(self, other: Self) -> Self {
+                    <$t>::saturating_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_arith!(u8, u16, u32, u64, u128, usize);
+
+/// Format an Address as zero padded hex, with as many digits as `U` can hold.
+///
+/// ```
+/// use embedded_storage::storage::{Address, AddressOffset};
+///
+/// assert_eq!(Address(0x1000u32).to_string(), "0x00001000");
+/// assert_eq!(Address(0xABu16).to_string(), "0x00ab");
+/// assert_eq!(AddressOffset(0x20u32).to_string(), "0x00000020");
+/// ```
+impl<U: fmt::LowerHex> fmt::Display for Address<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:0width$x}", self.0, width = core::mem::size_of::<U>() * 2)
+    }
+}
+
+/// Format an AddressOffset as zero padded hex, with as many digits as `U` can hold.
+impl<U: fmt::LowerHex> fmt::Display for AddressOffset<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:0width$x}", self.0, width = core::mem::size_of::<U>() * 2)
+    }
+}
+
+/// Create an AddressOffset from its inner value.
+impl<U> From<U> for AddressOffset<U> {
+    fn from(value: U) -> Self {
+        AddressOffset(value)
+    }
+}
+
+impl<U> AddressOffset<U> {
+    /// Returns the inner value
+    pub fn into_inner(self) -> U {
+        self.0
+    }
+
+    /// Returns the number of bytes in this many words, or `None` on overflow
+    ///
+    /// ```
+    /// use embedded_storage::storage::AddressOffset;
+    ///
+    /// assert_eq!(AddressOffset(6u32).byte_len::<u16>(), Some(12));
+    /// assert_eq!(AddressOffset(6u32).byte_len::<u32>(), Some(24));
+    /// assert_eq!(AddressOffset(u64::MAX).byte_len::<u32>(), None);
+    /// ```
+    pub fn byte_len<Word: StorageWord>(self) -> Option<usize>
+    where
+        U: TryInto<usize>,
+    {
+        self.0
+            .try_into()
+            .ok()
+            .and_then(|words: usize| words.checked_mul(Word::BYTES))
+    }
+}
+
+use core::convert::TryInto;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::{Add, AddAssign, Div, Mul, Rem, Sub, SubAssign};
 
 /// Implement add for the Address and AddressOffset Types.
-impl<'a, 'b, U> Add<&'b AddressOffset<U>> for &'a Address<U>
+impl<'b, U> Add<&'b AddressOffset<U>> for &Address<U>
 where
     U: Add<U, Output = U> + Copy,
 {
@@ -23,7 +333,7 @@ where
 }
 
 /// Implement subtraction for the Address and AddressOffset Types.
-impl<'a, 'b, U> Sub<&'b AddressOffset<U>> for &'a Address<U>
+impl<'b, U> Sub<&'b AddressOffset<U>> for &Address<U>
 where
     U: Sub<U, Output = U> + Copy,
 {
@@ -34,9 +344,222 @@ where
     }
 }
 
+/// Implement add assign for the Address and AddressOffset Types.
+///
+/// ```
+/// use embedded_storage::storage::{Address, AddressOffset};
+///
+/// let mut address = Address(0x1000u32);
+/// for _ in 0..4 {
+///     address += AddressOffset(4);
+/// }
+/// assert_eq!(address, Address(0x1010));
+///
+/// address += &AddressOffset(0x10);
+/// assert_eq!(address, Address(0x1020));
+/// ```
+impl<U> AddAssign<AddressOffset<U>> for Address<U>
+where
+    U: Add<U, Output = U> + Copy,
+{
+    fn add_assign(&mut self, other: AddressOffset<U>) {
+        self.0 = self.0 + other.0;
+    }
+}
+
+/// Implement add assign for the Address and borrowed AddressOffset Types.
+impl<'b, U> AddAssign<&'b AddressOffset<U>> for Address<U>
+where
+    U: Add<U, Output = U> + Copy,
+{
+    fn add_assign(&mut self, other: &'b AddressOffset<U>) {
+        self.0 = self.0 + other.0;
+    }
+}
+
+/// Implement subtract assign for the Address and AddressOffset Types.
+///
+/// ```
+/// use embedded_storage::storage::{Address, AddressOffset};
+///
+/// let mut address = Address(0x1010u32);
+/// address -= AddressOffset(0x8);
+/// address -= &AddressOffset(0x8);
+/// assert_eq!(address, Address(0x1000));
+/// ```
+impl<U> SubAssign<AddressOffset<U>> for Address<U>
+where
+    U: Sub<U, Output = U> + Copy,
+{
+    fn sub_assign(&mut self, other: AddressOffset<U>) {
+        self.0 = self.0 - other.0;
+    }
+}
+
+/// Implement subtract assign for the Address and borrowed AddressOffset Types.
+impl<'b, U> SubAssign<&'b AddressOffset<U>> for Address<U>
+where
+    U: Sub<U, Output = U> + Copy,
+{
+    fn sub_assign(&mut self, other: &'b AddressOffset<U>) {
+        self.0 = self.0 - other.0;
+    }
+}
+
 /// Page represents an unsigned integer that is a Page ID in the device memory space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Page<U>(pub U);
 
+/// Create a Page from its inner value.
+impl<U> From<U> for Page<U> {
+    fn from(value: U) -> Self {
+        Page(value)
+    }
+}
+
+impl<U> Page<U> {
+    /// Returns the inner value
+    pub fn into_inner(self) -> U {
+        self.0
+    }
+}
+
+impl<U> Page<U>
+where
+    U: Mul<U, Output = U> + Copy,
+{
+    /// Returns the first address of the page, for pages of `page_size` words from address zero
+    ///
+    /// ```
+    /// use embedded_storage::storage::{Address, Page};
+    ///
+    /// assert_eq!(Page(3u32).base_address(0x1000), Address(0x3000));
+    /// for address in [0u32, 0xFFF, 0x1000, 0x2ABC].iter() {
+    ///     let address = Address(*address);
+    ///     let page = address.page(0x1000);
+    ///     assert_eq!(&page.base_address(0x1000) + &address.offset_in_page(0x1000), address);
+    /// }
+    /// ```
+    pub fn base_address(&self, page_size: U) -> Address<U> {
+        Address(self.0 * page_size)
+    }
+}
+
+/// An Address that is known to be a multiple of the page size.
+///
+/// Only the start of a page is a valid address for erasing, so
+/// [`ErasePage::try_erase_aligned`] takes this type to rule out erasing from
+/// the middle of a page.
+///
+/// ```
+/// use embedded_storage::storage::{Address, PageAlignedAddress};
+///
+/// let aligned = PageAlignedAddress::new(0x2000u32, 0x1000).unwrap();
+/// assert_eq!(Address::from(aligned), Address(0x2000));
+///
+/// assert_eq!(PageAlignedAddress::new(0x2010u32, 0x1000), None);
+/// assert_eq!(PageAlignedAddress::new(0x2000u32, 0), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PageAlignedAddress<U>(U);
+
+impl<U> PageAlignedAddress<U>
+where
+    U: Rem<U, Output = U> + PartialEq + From<u8> + Copy,
+{
+    /// Returns `None` unless `address` is a multiple of the non zero `page_size`
+    pub fn new(address: U, page_size: U) -> Option<Self> {
+        if page_size != U::from(0) && address % page_size == U::from(0) {
+            Some(PageAlignedAddress(address))
+        } else {
+            None
+        }
+    }
+}
+
+impl<U> PageAlignedAddress<U> {
+    /// Returns the inner value
+    pub fn into_inner(self) -> U {
+        self.0
+    }
+}
+
+/// Convert a PageAlignedAddress to a plain Address.
+impl<U> From<PageAlignedAddress<U>> for Address<U> {
+    fn from(address: PageAlignedAddress<U>) -> Self {
+        Address(address.0)
+    }
+}
+
+/// A contiguous range of addresses, from `start` up to but not including `start + len`.
+///
+/// ```
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange};
+///
+/// let range = AddressRange::new(Address(0x100u32), AddressOffset(0x10));
+/// assert_eq!(range.end(), Address(0x110));
+/// assert!(range.contains(&Address(0x100)));
+/// assert!(range.contains(&Address(0x10F)));
+/// assert!(!range.contains(&Address(0x110)));
+///
+/// // Adjacent ranges don't overlap
+/// let next = AddressRange::new(Address(0x110u32), AddressOffset(0x10));
+/// assert!(!range.overlaps(&next));
+/// assert!(range.overlaps(&AddressRange::new(Address(0x10Fu32), AddressOffset(0x10))));
+///
+/// // Empty ranges contain and overlap nothing
+/// let empty = AddressRange::new(Address(0x108u32), AddressOffset(0));
+/// assert!(!empty.contains(&Address(0x108)));
+/// assert!(!empty.overlaps(&range));
+/// assert!(!range.overlaps(&empty));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressRange<U> {
+    /// The first address of the range
+    pub start: Address<U>,
+    /// The number of words in the range
+    pub len: AddressOffset<U>,
+}
+
+impl<U> AddressRange<U>
+where
+    U: Add<U, Output = U> + Ord + Copy,
+{
+    /// Create a range of `len` words starting at `start`
+    pub fn new(start: Address<U>, len: AddressOffset<U>) -> Self {
+        AddressRange { start, len }
+    }
+
+    /// The first address after the range
+    pub fn end(&self) -> Address<U> {
+        &self.start + &self.len
+    }
+
+    /// Check if the range contains no addresses
+    pub fn is_empty(&self) -> bool {
+        self.end() == self.start
+    }
+
+    /// Check if `address` lies within the range
+    pub fn contains(&self, address: &Address<U>) -> bool {
+        self.start <= *address && *address < self.end()
+    }
+
+    /// Check if the two ranges share at least one address
+    pub fn overlaps(&self, other: &AddressRange<U>) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.start < other.end()
+            && other.start < self.end()
+    }
+}
+
 /// Read a single word from the device.
 ///
 /// `Word` type allows any word size to be used.
@@ -45,7 +568,7 @@ pub trait SingleRead<Word, U> {
     type Error;
 
     /// Reads the word stored at the address
-    /// ```
+    /// ```ignore
     /// pub fn try_read(&mut self, address: Address) -> nb::Result<u8, Self::Error>
     ///     let address = address.0 as *const _;
     ///     unsafe {
@@ -55,6 +578,21 @@ pub trait SingleRead<Word, U> {
     fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error>;
 }
 
+/// Read words in place, for devices whose contents are directly addressable.
+///
+/// Unlike [`MultiRead`], no copy is made into a caller provided buffer.
+/// Only devices that can safely hand out a reference to their contents, such
+/// as memory mapped flash, should implement this. The device can't be
+/// accessed while the returned slice is borrowed.
+pub trait ZeroCopyRead<Word, U> {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Returns the `len` words stored from the address
+    fn try_read_ref(&mut self, address: Address<U>, len: usize)
+        -> nb::Result<&[Word], Self::Error>;
+}
+
 /// Write a single word to the device.
 ///
 /// `Word` type allows any word size to be used.
@@ -76,7 +614,7 @@ pub trait MultiRead<Word, U> {
     type Error;
 
     /// Reads the words stored at the address to fill the buffer
-    /// ```
+    /// ```ignore
     /// pub fn try_read_slice(
     ///     &mut self,
     ///     address: Address,  
@@ -95,6 +633,98 @@ pub trait MultiRead<Word, U> {
         address: Address<U>,
         buf: &mut [Word],
     ) -> nb::Result<(), Self::Error>;
+
+    /// Reads the words stored at the address into uninitialized memory,
+    /// returning the now initialized buffer.
+    ///
+    /// The default implementation initializes the buffer with `Word::default()`
+    /// and calls [`try_read_slice`](MultiRead::try_read_slice). Devices able
+    /// to write every word directly can override it to skip that step.
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use embedded_storage::mem::MemStorage;
+    /// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+    ///
+    /// let mut storage = MemStorage::<16>::new(16);
+    /// storage.try_write_slice(Address(4), &mut [1, 2, 3, 4]).unwrap();
+    ///
+    /// let mut buf = [MaybeUninit::<u8>::uninit(); 4];
+    /// let data = storage.try_read_uninit(Address(4), &mut buf).unwrap();
+    /// assert_eq!(data, [1, 2, 3, 4]);
+    /// ```
+    #[allow(unsafe_code)]
+    fn try_read_uninit<'a>(
+        &mut self,
+        address: Address<U>,
+        buf: &'a mut [MaybeUninit<Word>],
+    ) -> nb::Result<&'a mut [Word], Self::Error>
+    where
+        Word: Default,
+    {
+        for word in buf.iter_mut() {
+            *word = MaybeUninit::new(Word::default());
+        }
+        // Every word was initialized above, and `MaybeUninit<Word>` has the
+        // same layout as `Word`
+        let buf = unsafe { &mut *(buf as *mut [MaybeUninit<Word>] as *mut [Word]) };
+        self.try_read_slice(address, buf)?;
+        Ok(buf)
+    }
+
+    /// Reads the words stored at the address into the start of the buffer,
+    /// returning how many words were read.
+    ///
+    /// Devices that can't fill the whole buffer, such as at the end of their
+    /// address space or before a retired block, return a smaller count
+    /// instead of failing. [`try_read_slice`](MultiRead::try_read_slice)
+    /// still fails on such short reads. The default implementation reads the
+    /// whole buffer with `try_read_slice`. Wrappers passing reads through
+    /// forward this method, as the default would turn a short read of the
+    /// wrapped device into an error.
+    /// ```
+    /// use embedded_storage::read_only::ReadOnly;
+    /// use embedded_storage::storage::{Address, MultiRead};
+    ///
+    /// struct Eeprom([u8; 8]);
+    ///
+    /// impl MultiRead<u8, usize> for Eeprom {
+    ///     type Error = ();
+    ///
+    ///     fn try_read_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+    ///         if self.try_read_partial(address, buf)? < buf.len() {
+    ///             return Err(nb::Error::Other(()));
+    ///         }
+    ///         Ok(())
+    ///     }
+    ///
+    ///     // Truncates reads at the end of the device
+    ///     fn try_read_partial(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<usize, Self::Error> {
+    ///         let data = self.0.get(address.0..).ok_or(nb::Error::Other(()))?;
+    ///         let len = data.len().min(buf.len());
+    ///         buf[..len].copy_from_slice(&data[..len]);
+    ///         Ok(len)
+    ///     }
+    /// }
+    ///
+    /// let mut eeprom = Eeprom([1, 2, 3, 4, 5, 6, 7, 8]);
+    /// let mut buf = [0u8; 4];
+    /// assert_eq!(eeprom.try_read_partial(Address(6), &mut buf), Ok(2));
+    /// assert_eq!(buf[..2], [7, 8]);
+    /// assert_eq!(eeprom.try_read_slice(Address(6), &mut buf), Err(nb::Error::Other(())));
+    /// assert_eq!(eeprom.try_read_partial(Address(2), &mut buf), Ok(4));
+    ///
+    /// // Wrappers keep the short read
+    /// let mut eeprom = ReadOnly::new(eeprom);
+    /// assert_eq!(eeprom.try_read_partial(Address(6), &mut buf), Ok(2));
+    /// ```
+    fn try_read_partial(
+        &mut self,
+        address: Address<U>,
+        buf: &mut [Word],
+    ) -> nb::Result<usize, Self::Error> {
+        self.try_read_slice(address, buf)?;
+        Ok(buf.len())
+    }
 }
 
 /// Write multiple bytes to the device.
@@ -115,6 +745,46 @@ pub trait MultiWrite<Word, U> {
     ) -> nb::Result<(), Self::Error>;
 }
 
+/// [`MultiRead`] and [`MultiWrite`] sharing a single error type.
+///
+/// Implemented for every type implementing both traits with the same error,
+/// so generic code can take `T: ReadWrite<Word, U>` instead of listing both
+/// traits. The error is named `ReadWriteError` so it can be used as
+/// `T::ReadWriteError` without clashing with the `Error` of the supertraits.
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite, ReadWrite};
+///
+/// fn copy<T: ReadWrite<u8, usize>>(
+///     dev: &mut T,
+///     from: Address<usize>,
+///     to: Address<usize>,
+/// ) -> nb::Result<(), T::ReadWriteError> {
+///     let mut buf = [0u8; 4];
+///     dev.try_read_slice(from, &mut buf)?;
+///     dev.try_write_slice(to, &mut buf)
+/// }
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// storage.try_write_slice(Address(0), &mut [1, 2, 3, 4]).unwrap();
+/// copy(&mut storage, Address(0), Address(8)).unwrap();
+/// assert_eq!(storage.as_slice()[8..12], [1, 2, 3, 4]);
+/// ```
+pub trait ReadWrite<Word, U>:
+    MultiRead<Word, U, Error = <Self as ReadWrite<Word, U>>::ReadWriteError>
+    + MultiWrite<Word, U, Error = <Self as ReadWrite<Word, U>>::ReadWriteError>
+{
+    /// The error of [`MultiRead`] and [`MultiWrite`]
+    type ReadWriteError;
+}
+
+impl<T, Word, U, E> ReadWrite<Word, U> for T
+where
+    T: MultiRead<Word, U, Error = E> + MultiWrite<Word, U, Error = E> + ?Sized,
+{
+    type ReadWriteError = E;
+}
+
 /// A common interface to erase pages or memory locations.
 ///
 /// For Flash storage, the write functions can't set a bit to 1.
@@ -129,6 +799,24 @@ pub trait ErasePage<U> {
 
     /// Erase the page of memory at the address. Note: The only valid address is the start of the page (If the storage is page based)
     fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error>;
+
+    /// Erase the page of memory starting at the page aligned address.
+    ///
+    /// The default implementation forwards to [`try_erase_address`](ErasePage::try_erase_address).
+    ///
+    /// ```
+    /// use embedded_storage::mem::MemStorage;
+    /// use embedded_storage::storage::{ErasePage, PageAlignedAddress};
+    ///
+    /// let mut storage = MemStorage::<32>::new(16);
+    /// storage.as_mut_slice().fill(0);
+    /// storage.try_erase_aligned(PageAlignedAddress::new(16, 16).unwrap()).unwrap();
+    /// assert_eq!(storage.as_slice()[..16], [0; 16]);
+    /// assert_eq!(storage.as_slice()[16..], [0xFF; 16]);
+    /// ```
+    fn try_erase_aligned(&mut self, address: PageAlignedAddress<U>) -> nb::Result<(), Self::Error> {
+        self.try_erase_address(address.into())
+    }
 }
 
 /// Allow for checking that data can fit before writing to the device.
@@ -148,4 +836,1414 @@ pub trait StorageSize<Word, U> {
     ///
     /// For non paged devices, this should return the AddressOffset in ```try_total_size```
     fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error>;
+
+    /// Returns the capacity of the device in bytes, regardless of the size of `Word`
+    ///
+    /// Returns [`SizeError::Overflow`] if the capacity in bytes doesn't fit in a `usize`.
+    /// ```
+    /// use embedded_storage::storage::{Address, AddressOffset, SizeError, StorageSize};
+    ///
+    /// struct Device(u64);
+    ///
+    /// impl StorageSize<u16, u64> for Device {
+    ///     type Error = ();
+    ///
+    ///     fn try_start_address(&mut self) -> nb::Result<Address<u64>, Self::Error> {
+    ///         Ok(Address(0))
+    ///     }
+    ///
+    ///     fn try_total_size(&mut self) -> nb::Result<AddressOffset<u64>, Self::Error> {
+    ///         Ok(AddressOffset(self.0))
+    ///     }
+    ///
+    ///     fn try_page_size(&mut self, _address: Address<u64>) -> nb::Result<AddressOffset<u64>, Self::Error> {
+    ///         Ok(AddressOffset(self.0))
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Device(512).try_total_bytes(), Ok(1024));
+    /// assert_eq!(
+    ///     Device(u64::MAX).try_total_bytes(),
+    ///     Err(nb::Error::Other(SizeError::Overflow))
+    /// );
+    /// ```
+    fn try_total_bytes(&mut self) -> nb::Result<usize, SizeError<Self::Error>>
+    where
+        U: TryInto<usize>,
+    {
+        let words = self
+            .try_total_size()
+            .map_err(|e| e.map(SizeError::Device))?
+            .0;
+        words
+            .try_into()
+            .ok()
+            .and_then(|words: usize| words.checked_mul(core::mem::size_of::<Word>()))
+            .ok_or(nb::Error::Other(SizeError::Overflow))
+    }
+
+    /// Check whether `len` words starting at `address` fit in the device and in a single page
+    ///
+    /// The offset in the page is computed from the start address, assuming all pages up to
+    /// `address` are the size of the page containing it.
+    /// ```
+    /// use embedded_storage::mem::MemStorage;
+    /// use embedded_storage::storage::{Address, AddressOffset, FitResult, StorageSize};
+    ///
+    /// let mut storage = MemStorage::<32>::new(16);
+    /// assert_eq!(storage.try_fits(Address(4), AddressOffset(12)), Ok(FitResult::Fits));
+    /// assert_eq!(storage.try_fits(Address(4), AddressOffset(13)), Ok(FitResult::CrossesPage));
+    /// assert_eq!(storage.try_fits(Address(24), AddressOffset(9)), Ok(FitResult::OutOfBounds));
+    /// assert_eq!(storage.try_fits(Address(32), AddressOffset(0)), Ok(FitResult::Fits));
+    /// ```
+    fn try_fits(
+        &mut self,
+        address: Address<U>,
+        len: AddressOffset<U>,
+    ) -> nb::Result<FitResult, Self::Error>
+    where
+        U: Add<U, Output = U> + Sub<U, Output = U> + Rem<U, Output = U> + Ord + From<u8> + Copy,
+    {
+        let start = self.try_start_address()?;
+        let end = &start + &self.try_total_size()?;
+        if address < start || address > end || len.0 > end.0 - address.0 {
+            return Ok(FitResult::OutOfBounds);
+        }
+        if len.0 == U::from(0) {
+            return Ok(FitResult::Fits);
+        }
+        let page_size = self.try_page_size(address)?;
+        if page_size.0 == U::from(0) {
+            return Ok(FitResult::CrossesPage);
+        }
+        let offset = (address.0 - start.0) % page_size.0;
+        if len.0 > page_size.0 - offset {
+            Ok(FitResult::CrossesPage)
+        } else {
+            Ok(FitResult::Fits)
+        }
+    }
+
+    /// Returns the start address, total size and page size of the device in one call
+    ///
+    /// The default implementation walks the pages from the start address to
+    /// find out whether they all have the size of the first one, so it makes
+    /// one `try_page_size` call per page. Devices that know their layout can
+    /// override it.
+    /// ```
+    /// use embedded_storage::mem::MemStorage;
+    /// use embedded_storage::storage::{Address, AddressOffset, Geometry, StorageSize};
+    ///
+    /// let mut storage = MemStorage::<64>::new(16);
+    /// assert_eq!(
+    ///     storage.try_geometry(),
+    ///     Ok(Geometry {
+    ///         start: Address(0),
+    ///         total_size: AddressOffset(64),
+    ///         page_size: AddressOffset(16),
+    ///         is_uniform: true,
+    ///     })
+    /// );
+    /// ```
+    fn try_geometry(&mut self) -> nb::Result<Geometry<U>, Self::Error>
+    where
+        U: Add<U, Output = U> + Ord + From<u8> + Copy,
+    {
+        let start = self.try_start_address()?;
+        let total_size = self.try_total_size()?;
+        let end = &start + &total_size;
+        let page_size = self.try_page_size(start)?;
+        let mut is_uniform = true;
+        if page_size.0 == U::from(0) {
+            // A zero sized page would never advance
+            is_uniform = start == end;
+        } else {
+            let mut address = &start + &page_size;
+            while address < end {
+                if self.try_page_size(address)? != page_size {
+                    is_uniform = false;
+                    break;
+                }
+                address = &address + &page_size;
+            }
+        }
+        Ok(Geometry {
+            start,
+            total_size,
+            page_size,
+            is_uniform,
+        })
+    }
+}
+
+/// The layout of a device, see [`StorageSize::try_geometry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Geometry<U> {
+    /// The start address of the device
+    pub start: Address<U>,
+    /// The number of words stored by the device
+    pub total_size: AddressOffset<U>,
+    /// The size of the first page, and of every page if `is_uniform` is set
+    pub page_size: AddressOffset<U>,
+    /// Whether all pages have the same size
+    pub is_uniform: bool,
+}
+
+/// Errors returned by [`StorageSize::try_total_bytes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeError<E> {
+    /// An error reported by the device
+    Device(E),
+    /// The capacity in bytes doesn't fit in a `usize`
+    Overflow,
+}
+
+impl<E> From<E> for SizeError<E> {
+    fn from(e: E) -> Self {
+        SizeError::Device(e)
+    }
+}
+
+impl<E: StorageError> StorageError for SizeError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            SizeError::Device(e) => e.kind(),
+            SizeError::Overflow => ErrorKind::Other,
+        }
+    }
+}
+
+/// Result of [`StorageSize::try_fits`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitResult {
+    /// The range is within the device and within a single page
+    Fits,
+    /// The range extends beyond the device
+    OutOfBounds,
+    /// The range is within the device, but extends beyond the page it starts in
+    CrossesPage,
+}
+
+/// The erase granularity of a device, where it differs from the page size.
+///
+/// [`StorageSize::try_page_size`] reports the unit of programming. On many NOR
+/// flash parts the smallest erasable sector is larger than that, so erasing
+/// one page clears the whole sector containing it. Read-modify-write
+/// algorithms should size their buffers to the erase size.
+/// ```
+/// use embedded_storage::storage::{Address, AddressOffset, EraseGeometry, StorageSize};
+///
+/// // 256 byte program pages in 4KB erase sectors
+/// struct Flash;
+///
+/// impl StorageSize<u8, u32> for Flash {
+///     type Error = ();
+///
+///     fn try_start_address(&mut self) -> nb::Result<Address<u32>, Self::Error> {
+///         Ok(Address(0))
+///     }
+///
+///     fn try_total_size(&mut self) -> nb::Result<AddressOffset<u32>, Self::Error> {
+///         Ok(AddressOffset(0x10_0000))
+///     }
+///
+///     fn try_page_size(&mut self, _address: Address<u32>) -> nb::Result<AddressOffset<u32>, Self::Error> {
+///         Ok(AddressOffset(256))
+///     }
+/// }
+///
+/// impl EraseGeometry<u32> for Flash {
+///     type Error = ();
+///
+///     fn try_erase_size(&mut self, _address: Address<u32>) -> nb::Result<AddressOffset<u32>, Self::Error> {
+///         Ok(AddressOffset(4096))
+///     }
+/// }
+///
+/// let mut flash = Flash;
+/// assert_eq!(flash.try_page_size(Address(0x1000)), Ok(AddressOffset(256)));
+/// assert_eq!(flash.try_erase_size(Address(0x1000)), Ok(AddressOffset(4096)));
+/// ```
+pub trait EraseGeometry<U> {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// The number of words cleared by erasing the page at the address
+    fn try_erase_size(&mut self, address: Address<U>)
+        -> nb::Result<AddressOffset<U>, Self::Error>;
+}
+
+/// Suspend an erase in progress to access the device, then resume it.
+///
+/// Erasing a sector of NOR flash can take tens of milliseconds. Devices
+/// supporting it can suspend the erase, serve time critical reads and resume
+/// the erase afterwards. While the erase is in progress `try_erase_address`
+/// returns `WouldBlock`, and polling it again after resuming completes it.
+/// ```
+/// use embedded_storage::storage::{Address, ErasePage, EraseSuspend, Page, SingleRead};
+///
+/// #[derive(Default)]
+/// struct Flash {
+///     erasing: bool,
+///     suspended: bool,
+///     polls: u32,
+///     suspends: u32,
+///     resumes: u32,
+/// }
+///
+/// impl ErasePage<u32> for Flash {
+///     type Error = ();
+///
+///     fn try_erase_page(&mut self, page: Page<u32>) -> nb::Result<(), Self::Error> {
+///         self.try_erase_address(Address(page.0 * 4096))
+///     }
+///
+///     fn try_erase_address(&mut self, _address: Address<u32>) -> nb::Result<(), Self::Error> {
+///         // Completes on the third poll that isn't suspended
+///         if !self.suspended {
+///             self.erasing = true;
+///             self.polls += 1;
+///         }
+///         if self.polls < 3 {
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         self.erasing = false;
+///         Ok(())
+///     }
+/// }
+///
+/// impl EraseSuspend for Flash {
+///     type Error = ();
+///
+///     fn try_suspend(&mut self) -> nb::Result<(), Self::Error> {
+///         self.suspended = true;
+///         self.suspends += 1;
+///         Ok(())
+///     }
+///
+///     fn try_resume(&mut self) -> nb::Result<(), Self::Error> {
+///         self.suspended = false;
+///         self.resumes += 1;
+///         Ok(())
+///     }
+/// }
+///
+/// impl SingleRead<u8, u32> for Flash {
+///     type Error = ();
+///
+///     fn try_read(&mut self, _address: Address<u32>) -> nb::Result<u8, Self::Error> {
+///         if self.erasing && !self.suspended {
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         Ok(0x42)
+///     }
+/// }
+///
+/// let mut flash = Flash::default();
+/// assert_eq!(flash.try_erase_address(Address(0)), Err(nb::Error::WouldBlock));
+/// assert_eq!(flash.try_read(Address(0x8000)), Err(nb::Error::WouldBlock));
+///
+/// flash.try_suspend().unwrap();
+/// assert_eq!(flash.try_read(Address(0x8000)), Ok(0x42));
+/// flash.try_resume().unwrap();
+///
+/// nb::block!(flash.try_erase_address(Address(0))).unwrap();
+/// assert_eq!((flash.suspends, flash.resumes), (1, 1));
+/// ```
+pub trait EraseSuspend {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Suspend the erase in progress
+    fn try_suspend(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Resume the suspended erase
+    fn try_resume(&mut self) -> nb::Result<(), Self::Error>;
+}
+
+/// Query whether a program or erase is in progress, such as the write in progress flag of SPI flash.
+///
+/// Callers issuing several operations can poll it to schedule other work
+/// while the device is busy, instead of retrying an operation until it stops
+/// returning `WouldBlock`.
+/// ```
+/// use embedded_storage::storage::Busy;
+///
+/// // Busy for a number of polls after each operation
+/// struct Flash {
+///     polls: u32,
+/// }
+///
+/// impl Busy for Flash {
+///     type Error = ();
+///
+///     fn try_is_busy(&mut self) -> nb::Result<bool, Self::Error> {
+///         if self.polls == 0 {
+///             return Ok(false);
+///         }
+///         self.polls -= 1;
+///         Ok(true)
+///     }
+/// }
+///
+/// let mut flash = Flash { polls: 3 };
+/// let mut waited = 0;
+/// while flash.try_is_busy().unwrap() {
+///     waited += 1;
+/// }
+/// assert_eq!(waited, 3);
+/// assert_eq!(flash.try_is_busy(), Ok(false));
+/// ```
+pub trait Busy {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Returns whether the device is still completing an operation
+    fn try_is_busy(&mut self) -> nb::Result<bool, Self::Error>;
+}
+
+/// Erase the whole device with a single command.
+///
+/// Many flash chips have a bulk erase opcode, far faster than erasing every
+/// sector. Devices without one don't implement this trait, and
+/// [`erase_all`](crate::helpers::erase_all) erases them page by page.
+/// ```
+/// use embedded_storage::helpers::erase_all;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MassErase, MultiWrite};
+///
+/// struct Flash {
+///     mass_erased: bool,
+/// }
+///
+/// impl MassErase for Flash {
+///     type Error = ();
+///
+///     fn try_mass_erase(&mut self) -> nb::Result<(), Self::Error> {
+///         self.mass_erased = true;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut flash = Flash { mass_erased: false };
+/// flash.try_mass_erase().unwrap();
+/// assert!(flash.mass_erased);
+///
+/// // MemStorage has no bulk erase, so it's erased page by page
+/// let mut storage = MemStorage::<32>::new(8);
+/// storage.try_write_slice(Address(12), &mut [0; 16]).unwrap();
+/// erase_all(&mut storage).unwrap();
+/// assert_eq!(storage.as_slice(), [0xFF; 32]);
+/// ```
+pub trait MassErase {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Erase every page of the device
+    fn try_mass_erase(&mut self) -> nb::Result<(), Self::Error>;
+}
+
+/// Flash split into banks that operate independently, such as dual bank MCU flash or multi die chips.
+///
+/// While one bank is being programmed or erased, the others can still be
+/// read, which the flat address model of the other traits hides. Banks all
+/// have the same size and follow each other from the start address of the
+/// device, see [`locate_bank`](crate::helpers::locate_bank) to find the
+/// bank holding an address.
+/// ```
+/// use embedded_storage::helpers::locate_bank;
+/// use embedded_storage::storage::{Address, AddressOffset, Banked, StorageSize};
+///
+/// // Two banks of 64 KiB, erased in the background
+/// struct Flash {
+///     erasing: [u32; 2],
+/// }
+///
+/// impl Banked<u32> for Flash {
+///     type Error = ();
+///
+///     fn bank_count(&self) -> usize {
+///         2
+///     }
+///
+///     fn bank_size(&self) -> AddressOffset<u32> {
+///         AddressOffset(0x1_0000)
+///     }
+///
+///     fn try_erase_bank(&mut self, bank: usize) -> nb::Result<(), Self::Error> {
+///         self.erasing[bank] = 3;
+///         Ok(())
+///     }
+///
+///     fn try_is_bank_busy(&mut self, bank: usize) -> nb::Result<bool, Self::Error> {
+///         let polls = &mut self.erasing[bank];
+///         *polls = polls.saturating_sub(1);
+///         Ok(*polls > 0)
+///     }
+/// }
+/// # impl StorageSize<u8, u32> for Flash {
+/// #     type Error = ();
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<u32>, ()> {
+/// #         Ok(Address(0x0800_0000))
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<u32>, ()> {
+/// #         Ok(AddressOffset(0x2_0000))
+/// #     }
+/// #     fn try_page_size(&mut self, _address: Address<u32>) -> nb::Result<AddressOffset<u32>, ()> {
+/// #         Ok(AddressOffset(0x800))
+/// #     }
+/// # }
+///
+/// let mut flash = Flash { erasing: [0; 2] };
+/// assert_eq!(locate_bank::<_, u8, _, _>(&mut flash, Address(0x0800_0010)), Ok((0, AddressOffset(0x10))));
+/// assert_eq!(locate_bank::<_, u8, _, _>(&mut flash, Address(0x0801_2345)), Ok((1, AddressOffset(0x2345))));
+///
+/// // The second bank stays available while the first one is erased
+/// flash.try_erase_bank(0).unwrap();
+/// assert_eq!(flash.try_is_bank_busy(0), Ok(true));
+/// assert_eq!(flash.try_is_bank_busy(1), Ok(false));
+/// ```
+pub trait Banked<U> {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// The number of banks of the device
+    fn bank_count(&self) -> usize;
+
+    /// The number of words in each bank
+    fn bank_size(&self) -> AddressOffset<U>;
+
+    /// Erase every page of the bank
+    fn try_erase_bank(&mut self, bank: usize) -> nb::Result<(), Self::Error>;
+
+    /// Returns whether the bank is still completing a program or erase
+    fn try_is_bank_busy(&mut self, bank: usize) -> nb::Result<bool, Self::Error>;
+}
+
+/// Program one bank of a [`Banked`] device while the others stay readable, including for executing code.
+///
+/// On parts supporting it, firmware running from one bank can update
+/// another in place, without copying a programming routine to RAM first.
+/// `try_program_other_bank` is safe to call while code executes from any
+/// bank other than `bank`, and until it completes reads of those banks
+/// still succeed. `address` must be within `bank`.
+/// ```
+/// use embedded_storage::storage::{Address, AddressOffset, Banked, MultiRead, ReadWhileWrite};
+///
+/// // Two banks of 16 bytes, programming taking a few polls
+/// struct Flash {
+///     data: [u8; 32],
+///     programming: Option<usize>,
+///     polls: u32,
+/// }
+///
+/// impl Banked<u32> for Flash {
+///     type Error = ();
+///
+///     fn bank_count(&self) -> usize {
+///         2
+///     }
+///
+///     fn bank_size(&self) -> AddressOffset<u32> {
+///         AddressOffset(16)
+///     }
+///
+///     fn try_erase_bank(&mut self, bank: usize) -> nb::Result<(), Self::Error> {
+///         self.data[bank * 16..bank * 16 + 16].fill(0xFF);
+///         Ok(())
+///     }
+///
+///     fn try_is_bank_busy(&mut self, bank: usize) -> nb::Result<bool, Self::Error> {
+///         Ok(self.programming == Some(bank))
+///     }
+/// }
+///
+/// impl ReadWhileWrite<u8, u32> for Flash {
+///     fn try_program_other_bank(
+///         &mut self,
+///         bank: usize,
+///         address: Address<u32>,
+///         buf: &mut [u8],
+///     ) -> nb::Result<(), Self::Error> {
+///         let start = address.0 as usize;
+///         if start / 16 != bank || (start + buf.len() - 1) / 16 != bank {
+///             return Err(nb::Error::Other(()));
+///         }
+///         if self.programming.is_none() {
+///             self.programming = Some(bank);
+///             self.polls = 3;
+///         }
+///         if self.polls > 0 {
+///             self.polls -= 1;
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         self.data[start..start + buf.len()].copy_from_slice(buf);
+///         self.programming = None;
+///         Ok(())
+///     }
+/// }
+///
+/// impl MultiRead<u8, u32> for Flash {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         let start = address.0 as usize;
+///         if self.programming == Some(start / 16) {
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         buf.copy_from_slice(&self.data[start..start + buf.len()]);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut flash = Flash { data: [0xFF; 32], programming: None, polls: 0 };
+/// flash.data[..4].copy_from_slice(&[1, 2, 3, 4]);
+///
+/// let mut update = [9, 8, 7];
+/// assert_eq!(flash.try_program_other_bank(1, Address(16), &mut update), Err(nb::Error::WouldBlock));
+/// assert_eq!(flash.try_is_bank_busy(1), Ok(true));
+///
+/// // Bank 0 can still be read while bank 1 is being programmed
+/// let mut code = [0u8; 4];
+/// flash.try_read_slice(Address(0), &mut code).unwrap();
+/// assert_eq!(code, [1, 2, 3, 4]);
+/// assert_eq!(flash.try_read_slice(Address(16), &mut [0u8; 3]), Err(nb::Error::WouldBlock));
+///
+/// nb::block!(flash.try_program_other_bank(1, Address(16), &mut update)).unwrap();
+/// assert_eq!(flash.data[16..19], [9, 8, 7]);
+/// ```
+pub trait ReadWhileWrite<Word, U>: Banked<U> {
+    /// Program `buf` at the address, which is in `bank`, leaving the other banks readable
+    fn try_program_other_bank(
+        &mut self,
+        bank: usize,
+        address: Address<U>,
+        buf: &mut [Word],
+    ) -> nb::Result<(), Self::Error>;
+}
+
+/// Access to the one time programmable region of a device, in an address space separate from the main array.
+///
+/// Flash parts often provide a small OTP or security region with dedicated
+/// commands, used for serial numbers and keys. Locking it makes it read
+/// only for good: writing it afterwards fails, and so does locking it again.
+/// ```
+/// use embedded_storage::storage::{Address, OtpStorage};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Error {
+///     Locked,
+///     OutOfBounds,
+/// }
+///
+/// struct Flash {
+///     otp: [u8; 32],
+///     locked: bool,
+/// }
+///
+/// impl OtpStorage<u8> for Flash {
+///     type Error = Error;
+///
+///     fn try_otp_read(&mut self, address: Address<u8>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         let start = usize::from(address.0);
+///         let area = self.otp.get(start..start + buf.len()).ok_or(Error::OutOfBounds)?;
+///         buf.copy_from_slice(area);
+///         Ok(())
+///     }
+///
+///     fn try_otp_write(&mut self, address: Address<u8>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         if self.locked {
+///             return Err(nb::Error::Other(Error::Locked));
+///         }
+///         let start = usize::from(address.0);
+///         let area = self.otp.get_mut(start..start + buf.len()).ok_or(Error::OutOfBounds)?;
+///         // Bits can only be cleared, like on the real part
+///         area.iter_mut().zip(buf.iter()).for_each(|(old, new)| *old &= *new);
+///         Ok(())
+///     }
+///
+///     fn try_otp_lock(&mut self) -> nb::Result<(), Self::Error> {
+///         if self.locked {
+///             return Err(nb::Error::Other(Error::Locked));
+///         }
+///         self.locked = true;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut flash = Flash { otp: [0xFF; 32], locked: false };
+/// flash.try_otp_write(Address(4), &mut b"SN-0042".clone()).unwrap();
+///
+/// let mut serial = [0u8; 7];
+/// flash.try_otp_read(Address(4), &mut serial).unwrap();
+/// assert_eq!(&serial, b"SN-0042");
+///
+/// flash.try_otp_lock().unwrap();
+/// assert_eq!(flash.try_otp_write(Address(16), &mut [0]), Err(nb::Error::Other(Error::Locked)));
+/// assert_eq!(flash.try_otp_lock(), Err(nb::Error::Other(Error::Locked)));
+///
+/// // Still readable once locked
+/// flash.try_otp_read(Address(4), &mut serial).unwrap();
+/// assert_eq!(&serial, b"SN-0042");
+/// ```
+pub trait OtpStorage<U> {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Read a slice of the OTP region, starting at the address
+    fn try_otp_read(&mut self, address: Address<U>, buf: &mut [u8]) -> nb::Result<(), Self::Error>;
+
+    /// Program a slice of the OTP region, starting at the address.
+    ///
+    /// Returns an error if the region is locked.
+    fn try_otp_write(&mut self, address: Address<U>, buf: &mut [u8])
+        -> nb::Result<(), Self::Error>;
+
+    /// Lock the OTP region permanently.
+    ///
+    /// Returns an error if the region is already locked.
+    fn try_otp_lock(&mut self) -> nb::Result<(), Self::Error>;
+}
+
+/// Worst case durations of the operations of a device, in microseconds.
+///
+/// Real time schedulers can use them to reserve a slot long enough for an
+/// access. Each constant is the longest a single call can take to complete,
+/// including the time spent returning `WouldBlock`: a read or write of up to
+/// a page, or the erase of one page.
+/// ```
+/// use embedded_storage::storage::Timing;
+///
+/// struct Flash;
+///
+/// impl Timing for Flash {
+///     const MAX_READ_US: u32 = 10;
+///     const MAX_WRITE_US: u32 = 400;
+///     const MAX_ERASE_US: u32 = 45_000;
+/// }
+///
+/// // Budget for rewriting one page
+/// assert_eq!(Flash::MAX_ERASE_US + Flash::MAX_WRITE_US, 45_400);
+/// ```
+pub trait Timing {
+    /// The longest time a read takes
+    const MAX_READ_US: u32;
+    /// The longest time a write takes
+    const MAX_WRITE_US: u32;
+    /// The longest time erasing a page takes
+    const MAX_ERASE_US: u32;
+}
+
+/// The largest number of words a single read or write can transfer, such as the limit of a DMA or SPI controller.
+///
+/// Without specialization, helpers can't check whether a device implements
+/// this trait, so devices without it are treated as unlimited by
+/// [`read_chunked`](crate::helpers::read_chunked) and
+/// [`fill`](crate::helpers::fill). Their
+/// [`read_chunked_limited`](crate::helpers::read_chunked_limited) and
+/// [`fill_limited`](crate::helpers::fill_limited) variants require it, and
+/// split every transfer to stay within the limit.
+/// ```
+/// use embedded_storage::storage::MaxTransfer;
+///
+/// struct Flash;
+///
+/// impl MaxTransfer for Flash {
+///     const MAX_TRANSFER_WORDS: usize = 0x1_0000;
+/// }
+///
+/// assert_eq!(Flash::MAX_TRANSFER_WORDS, 64 * 1024);
+/// ```
+pub trait MaxTransfer {
+    /// The most words moved by one call, at least 1
+    const MAX_TRANSFER_WORDS: usize;
+}
+
+/// Read the identification of the device, such as the JEDEC ID of SPI flash.
+///
+/// `N` is the length of the identification in bytes. Firmware can use it to
+/// check that it's talking to the expected part before erasing it.
+/// ```
+/// use embedded_storage::storage::DeviceId;
+///
+/// struct Flash;
+///
+/// impl DeviceId<3> for Flash {
+///     type Error = ();
+///
+///     fn try_read_id(&mut self) -> nb::Result<[u8; 3], Self::Error> {
+///         // Manufacturer, memory type and capacity
+///         Ok([0xEF, 0x40, 0x18])
+///     }
+/// }
+///
+/// assert_eq!(Flash.try_read_id(), Ok([0xEF, 0x40, 0x18]));
+/// ```
+pub trait DeviceId<const N: usize> {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Reads the identification bytes of the device
+    fn try_read_id(&mut self) -> nb::Result<[u8; N], Self::Error>;
+}
+
+/// Read the factory programmed unique ID of the device, separate from its [`DeviceId`].
+///
+/// `N` is the length of the ID in bytes, commonly 8 or 16. Unlike the
+/// JEDEC ID it differs between parts of the same model, so firmware can
+/// derive per device keys from it. It isn't part of the address space.
+/// ```
+/// use embedded_storage::storage::UniqueId;
+///
+/// struct Flash;
+///
+/// impl UniqueId<8> for Flash {
+///     type Error = ();
+///
+///     fn try_unique_id(&mut self) -> nb::Result<[u8; 8], Self::Error> {
+///         Ok([0xD2, 0x65, 0x38, 0x44, 0x1B, 0x0E, 0x29, 0x7C])
+///     }
+/// }
+///
+/// let id = Flash.try_unique_id().unwrap();
+/// assert_eq!(id, [0xD2, 0x65, 0x38, 0x44, 0x1B, 0x0E, 0x29, 0x7C]);
+/// assert_eq!(u64::from_be_bytes(id), 0xD265_3844_1B0E_297C);
+/// ```
+pub trait UniqueId<const N: usize> {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Reads the unique ID bytes of the device
+    fn try_unique_id(&mut self) -> nb::Result<[u8; N], Self::Error>;
+}
+
+/// Raw access to the status register of the device, such as the status register of SPI NOR flash.
+///
+/// This is a low level escape hatch for configuring bits without a
+/// dedicated trait, such as quad enable, block protection or hold. The
+/// meaning of each bit is specific to the device.
+/// ```
+/// use embedded_storage::storage::StatusRegister;
+///
+/// struct Flash {
+///     status: u8,
+/// }
+///
+/// impl StatusRegister for Flash {
+///     type Error = ();
+///
+///     fn try_read_status(&mut self) -> nb::Result<u8, Self::Error> {
+///         Ok(self.status)
+///     }
+///
+///     fn try_write_status(&mut self, value: u8) -> nb::Result<(), Self::Error> {
+///         // The write in progress bit is read only
+///         self.status = value & !0x01;
+///         Ok(())
+///     }
+/// }
+///
+/// const QUAD_ENABLE: u8 = 0x40;
+/// let mut flash = Flash { status: 0x00 };
+/// let status = flash.try_read_status().unwrap();
+/// flash.try_write_status(status | QUAD_ENABLE).unwrap();
+/// assert_eq!(flash.try_read_status(), Ok(QUAD_ENABLE));
+/// ```
+pub trait StatusRegister {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Reads the status register
+    fn try_read_status(&mut self) -> nb::Result<u8, Self::Error>;
+
+    /// Writes `value` to the status register
+    fn try_write_status(&mut self, value: u8) -> nb::Result<(), Self::Error>;
+}
+
+/// Force buffered writes to be persisted.
+///
+/// Devices that buffer writes internally, such as some SPI flash and EEPROMs,
+/// only guarantee that written data survives power loss after a flush.
+/// Wrappers buffering writes themselves flush them first and then flush the
+/// wrapped device.
+///
+/// Devices where every write is durable once it completes can implement
+/// [`DurableWrites`] instead, which provides an implementation that does
+/// nothing.
+pub trait Flush {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Persist all completed writes
+    fn try_flush(&mut self) -> nb::Result<(), Self::Error>;
+}
+
+/// Marker for devices where writes are durable as soon as they complete.
+///
+/// Implementing it provides a [`Flush`] implementation that does nothing.
+/// ```
+/// use embedded_storage::storage::{DurableWrites, Flush};
+///
+/// struct Eeprom;
+///
+/// impl DurableWrites for Eeprom {
+///     type Error = ();
+/// }
+///
+/// assert_eq!(Eeprom.try_flush(), Ok(()));
+/// ```
+pub trait DurableWrites {
+    /// The error type of the provided [`Flush`] implementation
+    type Error;
+}
+
+impl<T: DurableWrites> Flush for T {
+    type Error = T::Error;
+
+    fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// How a write changes the stored data, see [`WriteSemantics`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Semantics {
+    /// Writes can only clear bits, setting them again needs an erase, as on NOR flash
+    BitClearOnly,
+    /// Writes replace the stored data, as on RAM or EEPROM
+    Arbitrary,
+}
+
+/// Report whether the device needs an erase before overwriting data.
+///
+/// Generic code can use it to skip erasing on devices where writes replace
+/// the data, such as [`write_smart`](crate::helpers::write_smart) does.
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Semantics, WriteSemantics};
+///
+/// let storage = MemStorage::<16>::new(16);
+/// assert_eq!(storage.write_semantics(), Semantics::BitClearOnly);
+/// ```
+pub trait WriteSemantics {
+    /// How writes change the stored data
+    fn write_semantics(&self) -> Semantics;
+}
+
+/// How efficiently a device serves reads at arbitrary addresses, see [`ReadAccess`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessPattern {
+    /// Reads at any address are equally fast, as on RAM or parallel NOR flash
+    Random,
+    /// Reads continuing from the previous one are much faster than seeking, as on serial EEPROM
+    SequentialOptimized,
+}
+
+/// Report whether the device favours sequential reads.
+///
+/// Generic algorithms can use it to choose between seeking and scanning,
+/// for example reading a whole region in order instead of looking up
+/// scattered entries.
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{AccessPattern, ReadAccess};
+///
+/// // A serial EEPROM with an auto incrementing address counter
+/// struct Eeprom;
+///
+/// impl ReadAccess for Eeprom {
+///     fn access_pattern(&self) -> AccessPattern {
+///         AccessPattern::SequentialOptimized
+///     }
+/// }
+///
+/// assert_eq!(Eeprom.access_pattern(), AccessPattern::SequentialOptimized);
+/// assert_eq!(MemStorage::<16>::new(16).access_pattern(), AccessPattern::Random);
+/// ```
+pub trait ReadAccess {
+    /// How the device serves reads
+    fn access_pattern(&self) -> AccessPattern;
+}
+
+/// Query how many times a page has been erased.
+///
+/// Flash pages wear out after a limited number of erase cycles. Devices
+/// keeping erase counters can report them, and
+/// [`WearTracking`](crate::wear::WearTracking) counts erases in RAM for
+/// devices that don't.
+/// ```
+/// use embedded_storage::storage::{Page, Wear};
+///
+/// struct Flash {
+///     counts: [u32; 4],
+/// }
+///
+/// impl Wear<u32> for Flash {
+///     type Error = ();
+///
+///     fn try_erase_count(&mut self, page: Page<u32>) -> nb::Result<u32, Self::Error> {
+///         self.counts.get(page.0 as usize).copied().ok_or(nb::Error::Other(()))
+///     }
+/// }
+///
+/// let mut flash = Flash { counts: [3, 0, 7, 1] };
+/// assert_eq!(flash.try_erase_count(Page(2)), Ok(7));
+/// ```
+pub trait Wear<U> {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// The number of times the page has been erased
+    fn try_erase_count(&mut self, page: Page<U>) -> nb::Result<u32, Self::Error>;
+}
+
+/// The outcome of error correction on the last read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EccResult {
+    /// The data was read without errors
+    Clean,
+    /// The given number of bit errors were corrected
+    Corrected(u8),
+    /// The data had more errors than could be corrected
+    Uncorrectable,
+}
+
+/// Report the error correction status of the last read.
+///
+/// NAND and some NOR flash correct bit errors on every read. Higher layers
+/// can check the status after reading a block, and rewrite the block before
+/// the errors become uncorrectable.
+/// ```
+/// use embedded_storage::storage::{Address, EccResult, EccStatus, MultiRead};
+///
+/// // Reports a corrected bit on reads of the first word
+/// struct Nand {
+///     last: EccResult,
+/// }
+///
+/// impl MultiRead<u8, u32> for Nand {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, address: Address<u32>, buf: &mut [u8]) -> nb::Result<(), Self::Error> {
+///         buf.fill(0);
+///         self.last = if address.0 == 0 { EccResult::Corrected(1) } else { EccResult::Clean };
+///         Ok(())
+///     }
+/// }
+///
+/// impl EccStatus for Nand {
+///     type Error = ();
+///
+///     fn try_last_ecc(&mut self) -> nb::Result<EccResult, Self::Error> {
+///         Ok(self.last)
+///     }
+/// }
+///
+/// let mut nand = Nand { last: EccResult::Clean };
+/// let mut buf = [0u8; 4];
+/// nand.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(nand.try_last_ecc(), Ok(EccResult::Corrected(1)));
+/// nand.try_read_slice(Address(4), &mut buf).unwrap();
+/// assert_eq!(nand.try_last_ecc(), Ok(EccResult::Clean));
+/// ```
+pub trait EccStatus {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// The error correction status of the last completed read
+    fn try_last_ecc(&mut self) -> nb::Result<EccResult, Self::Error>;
+}
+
+/// An unsigned integer word of a device, with a known size in bytes.
+///
+/// The storage traits are generic over any `Word`. Generic code that needs to
+/// convert between words and bytes can require `Word: StorageWord`.
+/// ```
+/// use embedded_storage::storage::StorageWord;
+///
+/// fn byte_offset<W: StorageWord>(words: usize) -> usize {
+///     words * W::BYTES
+/// }
+///
+/// assert_eq!(byte_offset::<u16>(3), 6);
+/// assert_eq!(byte_offset::<u32>(3), 12);
+/// assert_eq!(0x1234u16.to_le_bytes(), [0x34, 0x12]);
+/// assert_eq!(<u32 as StorageWord>::from_le_bytes([0xEF, 0xBE, 0xAD, 0xDE]), 0xDEADBEEF);
+/// ```
+pub trait StorageWord: Copy {
+    /// The size of a word in bytes
+    const BYTES: usize;
+
+    /// The byte array holding a word
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default + Copy;
+
+    /// Returns the little endian bytes of the word
+    fn to_le_bytes(self) -> Self::Bytes;
+
+    /// Creates a word from its little endian bytes
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+}
+
+impl StorageWord for u8 {
+    const BYTES: usize = 1;
+    type Bytes = [u8; 1];
+
+    fn to_le_bytes(self) -> Self::Bytes {
+        u8::to_le_bytes(self)
+    }
+
+    fn from_le_bytes(bytes: Self::Bytes) -> Self {
+        u8::from_le_bytes(bytes)
+    }
+}
+
+impl StorageWord for u16 {
+    const BYTES: usize = 2;
+    type Bytes = [u8; 2];
+
+    fn to_le_bytes(self) -> Self::Bytes {
+        u16::to_le_bytes(self)
+    }
+
+    fn from_le_bytes(bytes: Self::Bytes) -> Self {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+impl StorageWord for u32 {
+    const BYTES: usize = 4;
+    type Bytes = [u8; 4];
+
+    fn to_le_bytes(self) -> Self::Bytes {
+        u32::to_le_bytes(self)
+    }
+
+    fn from_le_bytes(bytes: Self::Bytes) -> Self {
+        u32::from_le_bytes(bytes)
+    }
+}
+
+impl StorageWord for u64 {
+    const BYTES: usize = 8;
+    type Bytes = [u8; 8];
+
+    fn to_le_bytes(self) -> Self::Bytes {
+        u64::to_le_bytes(self)
+    }
+
+    fn from_le_bytes(bytes: Self::Bytes) -> Self {
+        u64::from_le_bytes(bytes)
+    }
+}
+
+/// NOR flash memory, byte addressed, where writes can only clear bits and erasing sets them again.
+///
+/// Generic algorithms can use the constants to decide whether a region needs erasing or how to
+/// align writes.
+///
+/// NAND flash doesn't guarantee an erased value and has bad blocks, so NAND devices should not
+/// implement this trait.
+/// ```
+/// use embedded_storage::storage::{
+///     Address, AddressOffset, AddressRange, NorFlash, SingleRead, StorageSize,
+/// };
+///
+/// struct Flash([u8; 8]);
+///
+/// impl SingleRead<u8, u32> for Flash {
+///     type Error = ();
+///
+///     fn try_read(&mut self, address: Address<u32>) -> nb::Result<u8, Self::Error> {
+///         Ok(self.0[address.0 as usize])
+///     }
+/// }
+///
+/// impl StorageSize<u8, u32> for Flash {
+///     type Error = ();
+///
+///     fn try_start_address(&mut self) -> nb::Result<Address<u32>, Self::Error> {
+///         Ok(Address(0))
+///     }
+///
+///     fn try_total_size(&mut self) -> nb::Result<AddressOffset<u32>, Self::Error> {
+///         Ok(AddressOffset(8))
+///     }
+///
+///     fn try_page_size(&mut self, _address: Address<u32>) -> nb::Result<AddressOffset<u32>, Self::Error> {
+///         Ok(AddressOffset(4))
+///     }
+/// }
+///
+/// impl NorFlash<u32> for Flash {
+///     const ERASE_BYTE: u8 = 0xFF;
+///     const WRITE_SIZE: usize = 1;
+///     const ERASE_SIZE: usize = 4;
+/// }
+///
+/// let mut flash = Flash([0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0xFF, 0xFF]);
+/// assert_eq!(flash.try_is_erased(AddressRange::new(Address(0), AddressOffset(4))), Ok(true));
+/// assert_eq!(flash.try_is_erased(AddressRange::new(Address(4), AddressOffset(4))), Ok(false));
+/// ```
+pub trait NorFlash<U>: StorageSize<u8, U> + SingleRead<u8, U> {
+    /// The value of every byte after erasing
+    const ERASE_BYTE: u8;
+
+    /// The minimum number of bytes that can be written at once. Writes must be aligned to this.
+    const WRITE_SIZE: usize;
+
+    /// The minimum number of bytes that can be erased at once. Erases must be aligned to this.
+    const ERASE_SIZE: usize;
+
+    /// Check if every byte in `range` holds [`ERASE_BYTE`](NorFlash::ERASE_BYTE)
+    ///
+    /// The default implementation reads the range byte by byte.
+    fn try_is_erased(
+        &mut self,
+        range: AddressRange<U>,
+    ) -> nb::Result<bool, <Self as SingleRead<u8, U>>::Error>
+    where
+        U: Add<U, Output = U> + Ord + From<u8> + Copy,
+    {
+        let mut address = range.start;
+        while address < range.end() {
+            if self.try_read(address)? != Self::ERASE_BYTE {
+                return Ok(false);
+            }
+            address += AddressOffset(U::from(1));
+        }
+        Ok(true)
+    }
+}
+
+/// A common categorization of storage errors, allowing generic code to branch on the cause of a failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The access is not within the address range of the device
+    OutOfBounds,
+    /// The address or length isn't aligned to what the device requires
+    NotAligned,
+    /// The target region is write protected
+    WriteProtected,
+    /// The device didn't complete the operation in time
+    Timeout,
+    /// Any other error
+    Other,
+}
+
+/// Storage errors that can be mapped to an [`ErrorKind`].
+///
+/// ```
+/// use embedded_storage::storage::{ErrorKind, StorageError};
+///
+/// #[derive(Debug)]
+/// enum SpiFlashError {
+///     Bus,
+///     Locked,
+///     AddressTooLarge,
+/// }
+///
+/// impl StorageError for SpiFlashError {
+///     fn kind(&self) -> ErrorKind {
+///         match self {
+///             SpiFlashError::Bus => ErrorKind::Other,
+///             SpiFlashError::Locked => ErrorKind::WriteProtected,
+///             SpiFlashError::AddressTooLarge => ErrorKind::OutOfBounds,
+///         }
+///     }
+/// }
+///
+/// assert_eq!(SpiFlashError::Locked.kind(), ErrorKind::WriteProtected);
+/// assert_eq!(SpiFlashError::AddressTooLarge.kind(), ErrorKind::OutOfBounds);
+/// assert_eq!(SpiFlashError::Bus.kind(), ErrorKind::Other);
+/// ```
+pub trait StorageError: core::fmt::Debug {
+    /// The kind of this error
+    fn kind(&self) -> ErrorKind;
+}
+
+impl StorageError for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+/// [`SingleRead`] with an error implementing [`StorageError`].
+///
+/// Implemented for every [`SingleRead`] with a suitable error. Generic code
+/// bounding on this can call `kind` on errors.
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, ErrorKind, KindedSingleRead, StorageError};
+///
+/// fn read_or_zero<T: KindedSingleRead<u8, usize>>(dev: &mut T, address: Address<usize>) -> u8 {
+///     match nb::block!(dev.try_read(address)) {
+///         Ok(word) => word,
+///         Err(e) if e.kind() == ErrorKind::OutOfBounds => 0,
+///         Err(e) => panic!("{:?}", e),
+///     }
+/// }
+///
+/// let mut storage = MemStorage::<16>::new(16);
+/// assert_eq!(read_or_zero(&mut storage, Address(4)), 0xFF);
+/// assert_eq!(read_or_zero(&mut storage, Address(16)), 0);
+/// ```
+pub trait KindedSingleRead<Word, U>:
+    SingleRead<Word, U, Error = <Self as KindedSingleRead<Word, U>>::KindedError>
+{
+    /// The error of [`SingleRead`]
+    type KindedError: StorageError;
+}
+
+impl<T, Word, U> KindedSingleRead<Word, U> for T
+where
+    T: SingleRead<Word, U> + ?Sized,
+    T::Error: StorageError,
+{
+    type KindedError = T::Error;
+}
+
+/// [`SingleWrite`] with an error implementing [`StorageError`].
+pub trait KindedSingleWrite<Word, U>:
+    SingleWrite<Word, U, Error = <Self as KindedSingleWrite<Word, U>>::KindedError>
+{
+    /// The error of [`SingleWrite`]
+    type KindedError: StorageError;
+}
+
+impl<T, Word, U> KindedSingleWrite<Word, U> for T
+where
+    T: SingleWrite<Word, U> + ?Sized,
+    T::Error: StorageError,
+{
+    type KindedError = T::Error;
+}
+
+/// [`MultiRead`] with an error implementing [`StorageError`].
+pub trait KindedMultiRead<Word, U>:
+    MultiRead<Word, U, Error = <Self as KindedMultiRead<Word, U>>::KindedError>
+{
+    /// The error of [`MultiRead`]
+    type KindedError: StorageError;
+}
+
+impl<T, Word, U> KindedMultiRead<Word, U> for T
+where
+    T: MultiRead<Word, U> + ?Sized,
+    T::Error: StorageError,
+{
+    type KindedError = T::Error;
+}
+
+/// [`MultiWrite`] with an error implementing [`StorageError`].
+pub trait KindedMultiWrite<Word, U>:
+    MultiWrite<Word, U, Error = <Self as KindedMultiWrite<Word, U>>::KindedError>
+{
+    /// The error of [`MultiWrite`]
+    type KindedError: StorageError;
+}
+
+impl<T, Word, U> KindedMultiWrite<Word, U> for T
+where
+    T: MultiWrite<Word, U> + ?Sized,
+    T::Error: StorageError,
+{
+    type KindedError = T::Error;
+}
+
+/// [`ErasePage`] with an error implementing [`StorageError`].
+pub trait KindedErasePage<U>:
+    ErasePage<U, Error = <Self as KindedErasePage<U>>::KindedError>
+{
+    /// The error of [`ErasePage`]
+    type KindedError: StorageError;
+}
+
+impl<T, U> KindedErasePage<U> for T
+where
+    T: ErasePage<U> + ?Sized,
+    T::Error: StorageError,
+{
+    type KindedError = T::Error;
+}
+
+/// [`StorageSize`] with an error implementing [`StorageError`].
+pub trait KindedStorageSize<Word, U>:
+    StorageSize<Word, U, Error = <Self as KindedStorageSize<Word, U>>::KindedError>
+{
+    /// The error of [`StorageSize`]
+    type KindedError: StorageError;
+}
+
+impl<T, Word, U> KindedStorageSize<Word, U> for T
+where
+    T: StorageSize<Word, U> + ?Sized,
+    T::Error: StorageError,
+{
+    type KindedError = T::Error;
+}
+
+/// Lock regions of the device against writing and erasing, for devices with block protection.
+///
+/// Devices that can't protect regions should not implement this trait.
+/// ```
+/// use embedded_storage::storage::{Address, AddressOffset, AddressRange, WriteProtect};
+///
+/// // Protects in blocks of 0x1000 words
+/// struct Flash {
+///     locked: [bool; 4],
+/// }
+///
+/// impl Flash {
+///     fn blocks(range: AddressRange<u32>) -> core::ops::Range<usize> {
+///         (range.start.0 / 0x1000) as usize..((range.end().0 + 0xFFF) / 0x1000) as usize
+///     }
+/// }
+///
+/// impl WriteProtect<u32> for Flash {
+///     type Error = ();
+///
+///     fn try_protect(&mut self, range: AddressRange<u32>) -> nb::Result<(), Self::Error> {
+///         Flash::blocks(range).for_each(|block| self.locked[block] = true);
+///         Ok(())
+///     }
+///
+///     fn try_unprotect(&mut self, range: AddressRange<u32>) -> nb::Result<(), Self::Error> {
+///         Flash::blocks(range).for_each(|block| self.locked[block] = false);
+///         Ok(())
+///     }
+///
+///     fn try_is_protected(&mut self, address: Address<u32>) -> nb::Result<bool, Self::Error> {
+///         Ok(self.locked[(address.0 / 0x1000) as usize])
+///     }
+/// }
+///
+/// let mut flash = Flash { locked: [false; 4] };
+/// let bootloader = AddressRange::new(Address(0), AddressOffset(0x2000));
+///
+/// flash.try_protect(bootloader).unwrap();
+/// assert_eq!(flash.try_is_protected(Address(0x1FFF)), Ok(true));
+/// assert_eq!(flash.try_is_protected(Address(0x2000)), Ok(false));
+///
+/// flash.try_unprotect(bootloader).unwrap();
+/// assert_eq!(flash.try_is_protected(Address(0x0000)), Ok(false));
+/// ```
+pub trait WriteProtect<U> {
+    /// An enumeration of Storage errors
+    type Error;
+
+    /// Protect the range against writing and erasing.
+    ///
+    /// Devices protecting in blocks may protect more than `range`.
+    fn try_protect(&mut self, range: AddressRange<U>) -> nb::Result<(), Self::Error>;
+
+    /// Remove the protection from the range.
+    ///
+    /// Devices protecting in blocks may unprotect more than `range`.
+    fn try_unprotect(&mut self, range: AddressRange<U>) -> nb::Result<(), Self::Error>;
+
+    /// Check if the word at the address is protected
+    fn try_is_protected(&mut self, address: Address<U>) -> nb::Result<bool, Self::Error>;
 }
@@ -0,0 +1,63 @@
+//! Test utilities
+//!
+//! Blocking wrappers around single operations, to keep tests of code using
+//! the storage traits free of `nb::block!`. Production code should keep
+//! handling `WouldBlock` itself. The examples of
+//! [`CachedPage`](crate::cache::CachedPage) and
+//! [`Overlay`](crate::overlay::Overlay) use them on a device returning
+//! `WouldBlock`.
+//!
+//! ```
+//! use embedded_storage::mem::MemStorageBuilder;
+//! use embedded_storage::storage::Address;
+//! use embedded_storage::test_util::{block_erase, block_read, block_write};
+//!
+//! // Every operation returns `WouldBlock` twice before completing
+//! let mut storage = MemStorageBuilder::<32>::new().page_size(16).would_block_cycles(2).build();
+//! block_write(&mut storage, Address(16), &mut [1, 2, 3]).unwrap();
+//!
+//! let mut buf = [0u8; 3];
+//! block_read(&mut storage, Address(16), &mut buf).unwrap();
+//! assert_eq!(buf, [1, 2, 3]);
+//!
+//! block_erase(&mut storage, Address(16)).unwrap();
+//! block_read(&mut storage, Address(16), &mut buf).unwrap();
+//! assert_eq!(buf, [0xFF; 3]);
+//! ```
+
+use crate::storage::{Address, ErasePage, MultiRead, MultiWrite};
+
+/// Read `buf` at `address`, retrying while the device returns `WouldBlock`
+pub fn block_read<T, Word, U>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [Word],
+) -> Result<(), T::Error>
+where
+	T: MultiRead<Word, U> + ?Sized,
+	U: Copy,
+{
+	nb::block!(dev.try_read_slice(address, buf))
+}
+
+/// Write `buf` at `address`, retrying while the device returns `WouldBlock`
+pub fn block_write<T, Word, U>(
+	dev: &mut T,
+	address: Address<U>,
+	buf: &mut [Word],
+) -> Result<(), T::Error>
+where
+	T: MultiWrite<Word, U> + ?Sized,
+	U: Copy,
+{
+	nb::block!(dev.try_write_slice(address, buf))
+}
+
+/// Erase the page starting at `address`, retrying while the device returns `WouldBlock`
+pub fn block_erase<T, U>(dev: &mut T, address: Address<U>) -> Result<(), T::Error>
+where
+	T: ErasePage<U> + ?Sized,
+	U: Copy,
+{
+	nb::block!(dev.try_erase_address(address))
+}
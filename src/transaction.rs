@@ -0,0 +1,297 @@
+//! Transaction
+//!
+//! Replace the contents of a critical page so that it always holds either
+//! the old or the new contents, even if power is lost during the update.
+//!
+//! A transaction uses three pages: the target, a spare page the new contents
+//! are staged in, and a journal page holding three marker bytes. Each marker
+//! is written as the inverted erase byte once its step is complete:
+//!
+//! | Offset | Marker    | Written after                            |
+//! |--------|-----------|------------------------------------------|
+//! | 0      | Begun     | erasing the spare and journal pages      |
+//! | 1      | Committed | staging is complete, before the copy     |
+//! | 2      | Done      | copying the spare page to the target     |
+//!
+//! [`recover_on_boot`] finishes the copy of a committed transaction and rolls
+//! back one that was begun but never committed.
+
+use crate::helpers::{len_from_offset, offset_from_len, Error};
+use crate::storage::{Address, ErasePage, MultiRead, MultiWrite, StorageSize};
+use core::convert::TryFrom;
+use core::ops::Add;
+
+const BEGUN: usize = 0;
+const COMMITTED: usize = 1;
+const DONE: usize = 2;
+const CHUNK: usize = 16;
+
+/// The pages used by a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pages<U> {
+	/// The page being updated
+	pub target: Address<U>,
+	/// The page the new contents are staged in, at least as large as the target
+	pub spare: Address<U>,
+	/// The page holding the markers
+	pub journal: Address<U>,
+}
+
+/// The outcome of [`recover_on_boot`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+	/// No transaction was interrupted
+	Clean,
+	/// A transaction was begun but not committed, the target keeps its old contents
+	RolledBack,
+	/// A committed transaction was interrupted while copying, and the copy was finished
+	Completed,
+}
+
+/// An update of the target page in progress.
+///
+/// Bytes of the target page not written with [`stage`](Transaction::stage)
+/// are erased once the transaction is committed. Dropping the transaction
+/// without committing leaves the target untouched, and the next
+/// [`recover_on_boot`] rolls it back.
+///
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, MultiWrite};
+/// use embedded_storage::transaction::{recover_on_boot, Pages, Recovery, Transaction};
+///
+/// let pages = Pages { target: Address(0), spare: Address(16), journal: Address(32) };
+/// let mut storage = MemStorage::<48>::new(16);
+/// storage.try_write_slice(Address(0), &mut [1; 16]).unwrap();
+///
+/// let mut tx = Transaction::begin(&mut storage, pages, 0xFF).unwrap();
+/// tx.stage(0, &mut [2; 8]).unwrap();
+/// tx.stage(8, &mut [3; 8]).unwrap();
+/// tx.commit().unwrap();
+/// assert_eq!(storage.as_slice()[..16], [2, 2, 2, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3]);
+/// assert_eq!(recover_on_boot(&mut storage, pages, 0xFF), Ok(Recovery::Clean));
+///
+/// // Power lost before committing
+/// let mut tx = Transaction::begin(&mut storage, pages, 0xFF).unwrap();
+/// tx.stage(0, &mut [4; 16]).unwrap();
+/// drop(tx);
+/// assert_eq!(recover_on_boot(&mut storage, pages, 0xFF), Ok(Recovery::RolledBack));
+/// assert_eq!(storage.as_slice()[..2], [2, 2]);
+/// ```
+///
+/// Losing power after any write or erase leaves the target with either the
+/// old or the new contents once recovered:
+///
+/// ```
+/// use embedded_storage::mem::{Error, MemStorage};
+/// use embedded_storage::storage::{
+///     Address, AddressOffset, ErasePage, MultiRead, MultiWrite, Page, StorageSize,
+/// };
+/// use embedded_storage::transaction::{recover_on_boot, Pages, Transaction};
+///
+/// // Fails every write and erase after `ops` of them
+/// struct Crashing<'a> {
+///     mem: &'a mut MemStorage<48>,
+///     ops: usize,
+/// }
+///
+/// impl Crashing<'_> {
+///     fn op(&mut self) -> nb::Result<(), Error> {
+///         if self.ops == 0 {
+///             return Err(nb::Error::Other(Error::OutOfBounds));
+///         }
+///         self.ops -= 1;
+///         Ok(())
+///     }
+/// }
+///
+/// impl MultiWrite<u8, usize> for Crashing<'_> {
+///     type Error = Error;
+///
+///     fn try_write_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Error> {
+///         self.op()?;
+///         self.mem.try_write_slice(address, buf)
+///     }
+/// }
+///
+/// impl ErasePage<usize> for Crashing<'_> {
+///     type Error = Error;
+///
+///     fn try_erase_page(&mut self, page: Page<usize>) -> nb::Result<(), Error> {
+///         self.op()?;
+///         self.mem.try_erase_page(page)
+///     }
+///
+///     fn try_erase_address(&mut self, address: Address<usize>) -> nb::Result<(), Error> {
+///         self.op()?;
+///         self.mem.try_erase_address(address)
+///     }
+/// }
+/// # impl MultiRead<u8, usize> for Crashing<'_> {
+/// #     type Error = Error;
+/// #     fn try_read_slice(&mut self, address: Address<usize>, buf: &mut [u8]) -> nb::Result<(), Error> {
+/// #         self.mem.try_read_slice(address, buf)
+/// #     }
+/// # }
+/// # impl StorageSize<u8, usize> for Crashing<'_> {
+/// #     type Error = Error;
+/// #     fn try_start_address(&mut self) -> nb::Result<Address<usize>, Error> {
+/// #         self.mem.try_start_address()
+/// #     }
+/// #     fn try_total_size(&mut self) -> nb::Result<AddressOffset<usize>, Error> {
+/// #         self.mem.try_total_size()
+/// #     }
+/// #     fn try_page_size(&mut self, address: Address<usize>) -> nb::Result<AddressOffset<usize>, Error> {
+/// #         self.mem.try_page_size(address)
+/// #     }
+/// # }
+///
+/// let pages = Pages { target: Address(0), spare: Address(16), journal: Address(32) };
+/// for ops in 0..12 {
+///     let mut mem = MemStorage::<48>::new(16);
+///     mem.try_write_slice(Address(0), &mut [1; 16]).unwrap();
+///
+///     let mut dev = Crashing { mem: &mut mem, ops };
+///     let _ = Transaction::begin(&mut dev, pages, 0xFF).and_then(|mut tx| {
+///         tx.stage(0, &mut [2; 16])?;
+///         tx.commit()
+///     });
+///
+///     recover_on_boot(&mut mem, pages, 0xFF).unwrap();
+///     let target = &mem.as_slice()[..16];
+///     assert!(target == [1; 16] || target == [2; 16], "crash after {} operations", ops);
+/// }
+/// ```
+pub struct Transaction<'a, T: ?Sized, U> {
+	dev: &'a mut T,
+	pages: Pages<U>,
+	erase_byte: u8,
+	len: usize,
+}
+
+impl<'a, T, U, E> Transaction<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>
+		+ ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Start a transaction, erasing the spare and journal pages.
+	///
+	/// `erase_byte` is the value of an erased byte, which is
+	/// [`NorFlash::ERASE_BYTE`](crate::storage::NorFlash::ERASE_BYTE) for NOR flash.
+	pub fn begin(dev: &'a mut T, pages: Pages<U>, erase_byte: u8) -> Result<Self, Error<E>> {
+		let len = nb::block!(dev.try_page_size(pages.target))?;
+		let len = len_from_offset(len).ok_or(Error::Overflow)?;
+		let spare = nb::block!(dev.try_page_size(pages.spare))?;
+		if len_from_offset(spare).ok_or(Error::Overflow)? < len {
+			return Err(Error::BufferTooSmall);
+		}
+		nb::block!(dev.try_erase_address(pages.spare))?;
+		nb::block!(dev.try_erase_address(pages.journal))?;
+		mark(dev, pages.journal, BEGUN, erase_byte)?;
+		Ok(Transaction {
+			dev,
+			pages,
+			erase_byte,
+			len,
+		})
+	}
+
+	/// Write `data` at `offset` bytes into the new contents of the target page
+	pub fn stage(&mut self, offset: usize, data: &mut [u8]) -> Result<(), Error<E>> {
+		if offset > self.len || data.len() > self.len - offset {
+			return Err(Error::CrossesPage);
+		}
+		let address = at(self.pages.spare, offset)?;
+		nb::block!(self.dev.try_write_slice(address, data))?;
+		Ok(())
+	}
+
+	/// Commit the staged contents and copy them to the target page
+	pub fn commit(self) -> Result<(), Error<E>> {
+		mark(self.dev, self.pages.journal, COMMITTED, self.erase_byte)?;
+		copy(self.dev, self.pages, self.len)?;
+		mark(self.dev, self.pages.journal, DONE, self.erase_byte)
+	}
+}
+
+/// Finish or roll back a transaction interrupted by a power loss.
+///
+/// Call it on boot, before reading the target page.
+pub fn recover_on_boot<T, U, E>(
+	dev: &mut T,
+	pages: Pages<U>,
+	erase_byte: u8,
+) -> Result<Recovery, Error<E>>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>
+		+ ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let mut markers = [0; 3];
+	nb::block!(dev.try_read_slice(pages.journal, &mut markers))?;
+	let set = |marker: usize| markers[marker] == !erase_byte;
+	if set(DONE) || !set(BEGUN) {
+		Ok(Recovery::Clean)
+	} else if set(COMMITTED) {
+		let len = nb::block!(dev.try_page_size(pages.target))?;
+		let len = len_from_offset(len).ok_or(Error::Overflow)?;
+		copy(dev, pages, len)?;
+		mark(dev, pages.journal, DONE, erase_byte)?;
+		Ok(Recovery::Completed)
+	} else {
+		nb::block!(dev.try_erase_address(pages.spare))?;
+		nb::block!(dev.try_erase_address(pages.journal))?;
+		Ok(Recovery::RolledBack)
+	}
+}
+
+/// Erase the target page and copy `len` bytes of the spare page to it
+fn copy<T, U, E>(dev: &mut T, pages: Pages<U>, len: usize) -> Result<(), Error<E>>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	nb::block!(dev.try_erase_address(pages.target))?;
+	let mut done = 0;
+	while done < len {
+		let part = (len - done).min(CHUNK);
+		let mut buf = [0; CHUNK];
+		nb::block!(dev.try_read_slice(at(pages.spare, done)?, &mut buf[..part]))?;
+		nb::block!(dev.try_write_slice(at(pages.target, done)?, &mut buf[..part]))?;
+		done += part;
+	}
+	Ok(())
+}
+
+fn mark<T, U, E>(
+	dev: &mut T,
+	journal: Address<U>,
+	marker: usize,
+	erase_byte: u8,
+) -> Result<(), Error<E>>
+where
+	T: MultiWrite<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	nb::block!(dev.try_write_slice(at(journal, marker)?, &mut [!erase_byte]))?;
+	Ok(())
+}
+
+fn at<U, E>(base: Address<U>, offset: usize) -> Result<Address<U>, Error<E>>
+where
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+{
+	Ok(&base + &offset_from_len(offset).ok_or(Error::Overflow)?)
+}
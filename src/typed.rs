@@ -0,0 +1,125 @@
+//! Typed
+//!
+//! Store and load plain data types at an address, enabled by the `zerocopy` feature.
+//!
+//! The conversion between values and bytes is done by the
+//! [`zerocopy`](https://docs.rs/zerocopy) traits, so no unsafe code is needed.
+
+use crate::helpers::{offset_from_len, Error};
+use crate::storage::{Address, AddressOffset, MultiRead, MultiWrite, StorageSize};
+use core::convert::TryFrom;
+use core::ops::{Add, Rem, Sub};
+use zerocopy::{AsBytes, FromBytes};
+
+/// Number of bytes written per transaction by [`store`]
+const CHUNK_SIZE: usize = 32;
+
+/// Write the bytes of `value` at `address`.
+///
+/// Returns [`Error::CrossesPage`] if `value` is larger than the space
+/// remaining in the page from `address` onwards, and [`Error::OutOfBounds`]
+/// if `address` is before the start of the device or the device reports a
+/// zero sized page.
+///
+/// ```
+/// use embedded_storage::helpers::Error;
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::Address;
+/// use embedded_storage::typed::{load, store};
+/// use zerocopy::{AsBytes, FromBytes, FromZeroes};
+///
+/// #[derive(AsBytes, FromBytes, FromZeroes, Debug, Clone, Copy, PartialEq)]
+/// #[repr(C)]
+/// struct Config {
+///     id: u32,
+///     gain: u16,
+///     flags: u16,
+/// }
+///
+/// let mut storage = MemStorage::<64>::new(32);
+/// let config = Config { id: 0xDEADBEEF, gain: 12, flags: 0b101 };
+///
+/// store(&mut storage, Address(8), &config).unwrap();
+/// assert_eq!(load::<Config, _, _, _>(&mut storage, Address(8)), Ok(config));
+///
+/// // Only 4 bytes remain in the first page
+/// assert_eq!(store(&mut storage, Address(28), &config), Err(Error::CrossesPage));
+/// ```
+///
+/// Zero sized pages and addresses before the start of the device are rejected:
+///
+/// ```
+/// use embedded_storage::helpers::Error;
+/// use embedded_storage::storage::{Address, AddressOffset, MultiWrite, StorageSize};
+/// use embedded_storage::typed::store;
+///
+/// struct Device;
+///
+/// impl StorageSize<u8, u32> for Device {
+///     type Error = ();
+///
+///     fn try_start_address(&mut self) -> nb::Result<Address<u32>, ()> {
+///         Ok(Address(0x100))
+///     }
+///
+///     fn try_total_size(&mut self) -> nb::Result<AddressOffset<u32>, ()> {
+///         Ok(AddressOffset(64))
+///     }
+///
+///     // A broken page table, reporting an empty first page
+///     fn try_page_size(&mut self, address: Address<u32>) -> nb::Result<AddressOffset<u32>, ()> {
+///         Ok(AddressOffset(if address.0 == 0x100 { 0 } else { 16 }))
+///     }
+/// }
+/// # impl MultiWrite<u8, u32> for Device {
+/// #     type Error = ();
+/// #     fn try_write_slice(&mut self, _address: Address<u32>, _buf: &mut [u8]) -> nb::Result<(), ()> {
+/// #         unreachable!()
+/// #     }
+/// # }
+///
+/// assert_eq!(store(&mut Device, Address(0x100), &7u32), Err(Error::OutOfBounds));
+/// // Before the start of the device
+/// assert_eq!(store(&mut Device, Address(0x10), &7u32), Err(Error::OutOfBounds));
+/// ```
+pub fn store<T, D, U, E>(dev: &mut D, address: Address<U>, value: &T) -> Result<(), Error<E>>
+where
+	T: AsBytes + ?Sized,
+	D: MultiWrite<u8, U, Error = E> + StorageSize<u8, U, Error = E> + ?Sized,
+	U: Add<U, Output = U> + Sub<U, Output = U> + Rem<U, Output = U> + Ord + TryFrom<usize> + Copy,
+{
+	let bytes = value.as_bytes();
+	let start = nb::block!(dev.try_start_address())?;
+	let page_size = nb::block!(dev.try_page_size(address))?;
+	let zero: AddressOffset<U> = offset_from_len(0).ok_or(Error::Overflow)?;
+	if address < start || page_size.0 == zero.0 {
+		return Err(Error::OutOfBounds);
+	}
+	let offset = (address.0 - start.0) % page_size.0;
+	let len: AddressOffset<U> = offset_from_len(bytes.len()).ok_or(Error::Overflow)?;
+	if len.0 > page_size.0 - offset {
+		return Err(Error::CrossesPage);
+	}
+
+	let mut address = address;
+	let mut chunk = [0u8; CHUNK_SIZE];
+	for part in bytes.chunks(CHUNK_SIZE) {
+		let buf = &mut chunk[..part.len()];
+		buf.copy_from_slice(part);
+		nb::block!(dev.try_write_slice(address, buf))?;
+		address += offset_from_len(part.len()).ok_or(Error::Overflow)?;
+	}
+	Ok(())
+}
+
+/// Read a value from the bytes stored at `address`.
+pub fn load<T, D, U, E>(dev: &mut D, address: Address<U>) -> Result<T, Error<E>>
+where
+	T: AsBytes + FromBytes,
+	D: MultiRead<u8, U, Error = E> + ?Sized,
+	U: Copy,
+{
+	let mut value = T::new_zeroed();
+	nb::block!(dev.try_read_slice(address, value.as_bytes_mut()))?;
+	Ok(value)
+}
@@ -0,0 +1,486 @@
+//! View
+//!
+//! Present a device with multi byte words as a byte device, and the reverse.
+//!
+//! Addresses count words of the device they are given to, so byte `n` of a
+//! [`ByteView`] is in word `n / Word::BYTES` of the wrapped device, and word
+//! `n` of a [`WordView`] starts at byte `n * Word::BYTES`. The operations block
+//! on the device, and never return `WouldBlock`.
+
+use crate::helpers::Error;
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, Flush, MultiRead, MultiWrite, Page,
+	ReadAccess, StorageSize, StorageWord,
+};
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+
+/// The order of the bytes in a word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+	/// Least significant byte at the lowest address
+	Little,
+	/// Most significant byte at the lowest address
+	Big,
+}
+
+impl ByteOrder {
+	fn unpack<Word: StorageWord>(self, word: Word) -> Word::Bytes {
+		let mut bytes = word.to_le_bytes();
+		if self == ByteOrder::Big {
+			bytes.as_mut().reverse();
+		}
+		bytes
+	}
+
+	fn pack<Word: StorageWord>(self, mut bytes: Word::Bytes) -> Word {
+		if self == ByteOrder::Big {
+			bytes.as_mut().reverse();
+		}
+		Word::from_le_bytes(bytes)
+	}
+}
+
+fn index<U, E>(address: Address<U>) -> Result<usize, Error<E>>
+where
+	usize: TryFrom<U>,
+{
+	usize::try_from(address.0).map_err(|_| Error::Overflow)
+}
+
+fn address<U: TryFrom<usize>, E>(index: usize) -> Result<Address<U>, Error<E>> {
+	U::try_from(index).map(Address).map_err(|_| Error::Overflow)
+}
+
+fn scale<U, E>(offset: AddressOffset<U>, by: usize) -> Result<AddressOffset<U>, Error<E>>
+where
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let len = usize::try_from(offset.0).map_err(|_| Error::Overflow)?;
+	let len = len.checked_mul(by).ok_or(Error::Overflow)?;
+	U::try_from(len)
+		.map(AddressOffset)
+		.map_err(|_| Error::Overflow)
+}
+
+fn shrink<U, E>(offset: AddressOffset<U>, by: usize) -> Result<AddressOffset<U>, Error<E>>
+where
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	let len = usize::try_from(offset.0).map_err(|_| Error::Overflow)?;
+	if len % by != 0 {
+		return Err(Error::NotAligned);
+	}
+	U::try_from(len / by)
+		.map(AddressOffset)
+		.map_err(|_| Error::Overflow)
+}
+
+/// Byte addressed view of a device storing `Word`s.
+///
+/// Writes covering part of a word read the word first and write it back
+/// with the other bytes unchanged.
+///
+/// ```
+/// use embedded_storage::storage::{Address, MultiRead, MultiWrite};
+/// use embedded_storage::view::{ByteOrder, ByteView};
+///
+/// // A device storing 32 bit words
+/// struct Words([u32; 4]);
+///
+/// impl MultiRead<u32, usize> for Words {
+///     type Error = ();
+///
+///     fn try_read_slice(&mut self, address: Address<usize>, buf: &mut [u32]) -> nb::Result<(), ()> {
+///         let words = self.0.get(address.0..address.0 + buf.len()).ok_or(nb::Error::Other(()))?;
+///         buf.copy_from_slice(words);
+///         Ok(())
+///     }
+/// }
+///
+/// impl MultiWrite<u32, usize> for Words {
+///     type Error = ();
+///
+///     fn try_write_slice(&mut self, address: Address<usize>, buf: &mut [u32]) -> nb::Result<(), ()> {
+///         let words = self.0.get_mut(address.0..address.0 + buf.len()).ok_or(nb::Error::Other(()))?;
+///         words.copy_from_slice(buf);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut bytes = ByteView::new(Words([0x04030201, 0x08070605, 0, 0]), ByteOrder::Little);
+/// let mut buf = [0u8; 1];
+/// bytes.try_read_slice(Address(2), &mut buf).unwrap();
+/// assert_eq!(buf, [3]);
+/// let mut buf = [0u8; 4];
+/// bytes.try_read_slice(Address(3), &mut buf).unwrap();
+/// assert_eq!(buf, [4, 5, 6, 7]);
+///
+/// bytes.try_write_slice(Address(5), &mut [0xAA, 0xBB]).unwrap();
+/// assert_eq!(bytes.into_inner().0[1], 0x08BBAA05);
+///
+/// let mut bytes = ByteView::new(Words([0x04030201, 0, 0, 0]), ByteOrder::Big);
+/// let mut buf = [0u8; 4];
+/// bytes.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [4, 3, 2, 1]);
+/// assert!(bytes.try_read_slice(Address(14), &mut buf).is_err());
+/// ```
+pub struct ByteView<T, Word> {
+	dev: T,
+	order: ByteOrder,
+	_word: PhantomData<Word>,
+}
+
+impl<T, Word> ByteView<T, Word> {
+	/// Wrap `dev`, packing bytes into words in `order`
+	pub fn new(dev: T, order: ByteOrder) -> Self {
+		ByteView {
+			dev,
+			order,
+			_word: PhantomData,
+		}
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+}
+
+fn read_word<T, Word, U>(
+	dev: &mut T,
+	order: ByteOrder,
+	index: usize,
+) -> Result<Word::Bytes, Error<T::Error>>
+where
+	T: MultiRead<Word, U>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+{
+	let mut word = [Word::from_le_bytes(Default::default())];
+	nb::block!(dev.try_read_slice(address(index)?, &mut word))?;
+	Ok(order.unpack(word[0]))
+}
+
+impl<T, Word, U> MultiRead<u8, U> for ByteView<T, Word>
+where
+	T: MultiRead<Word, U>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [u8],
+	) -> nb::Result<(), Self::Error> {
+		let mut byte = index(address)?;
+		let mut buf = buf;
+		while !buf.is_empty() {
+			let offset = byte % Word::BYTES;
+			let bytes = read_word(&mut self.dev, self.order, byte / Word::BYTES)?;
+			let n = (Word::BYTES - offset).min(buf.len());
+			let (part, rest) = buf.split_at_mut(n);
+			part.copy_from_slice(&bytes.as_ref()[offset..offset + n]);
+			byte += n;
+			buf = rest;
+		}
+		Ok(())
+	}
+}
+
+impl<T, Word, U, E> MultiWrite<u8, U> for ByteView<T, Word>
+where
+	T: MultiRead<Word, U, Error = E> + MultiWrite<Word, U, Error = E>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<E>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [u8],
+	) -> nb::Result<(), Self::Error> {
+		let mut byte = index(address)?;
+		let mut buf = &buf[..];
+		while !buf.is_empty() {
+			let (word, offset) = (byte / Word::BYTES, byte % Word::BYTES);
+			let n = (Word::BYTES - offset).min(buf.len());
+			let mut bytes = if n == Word::BYTES {
+				Default::default()
+			} else {
+				read_word(&mut self.dev, self.order, word)?
+			};
+			bytes.as_mut()[offset..offset + n].copy_from_slice(&buf[..n]);
+			let mut word_buf = [self.order.pack(bytes)];
+			let address = self::address(word)?;
+			nb::block!(self.dev.try_write_slice(address, &mut word_buf)).map_err(Error::Device)?;
+			byte += n;
+			buf = &buf[n..];
+		}
+		Ok(())
+	}
+}
+
+impl<T, Word, U> ErasePage<U> for ByteView<T, Word>
+where
+	T: ErasePage<U>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		self.dev
+			.try_erase_page(page)
+			.map_err(|e| e.map(Error::Device))
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		let word = self::address(index(address)? / Word::BYTES)?;
+		self.dev
+			.try_erase_address(word)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, Word, U> StorageSize<u8, U> for ByteView<T, Word>
+where
+	T: StorageSize<Word, U>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		let start = self
+			.dev
+			.try_start_address()
+			.map_err(|e| e.map(Error::Device))?;
+		Ok(Address(scale(AddressOffset(start.0), Word::BYTES)?.0))
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		let size = self
+			.dev
+			.try_total_size()
+			.map_err(|e| e.map(Error::Device))?;
+		Ok(scale(size, Word::BYTES)?)
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		let word = self::address(index(address)? / Word::BYTES)?;
+		let size = self
+			.dev
+			.try_page_size(word)
+			.map_err(|e| e.map(Error::Device))?;
+		Ok(scale(size, Word::BYTES)?)
+	}
+}
+
+/// Word addressed view of a byte device.
+///
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, AddressOffset, MultiRead, MultiWrite, StorageSize};
+/// use embedded_storage::view::{ByteOrder, WordView};
+///
+/// let mut storage = MemStorage::<16>::new(8);
+/// storage.try_write_slice(Address(4), &mut [1, 2, 3, 4]).unwrap();
+///
+/// let mut words = WordView::<_, u32>::new(storage, ByteOrder::Big);
+/// let mut buf = [0u32; 1];
+/// words.try_read_slice(Address(1), &mut buf).unwrap();
+/// assert_eq!(buf, [0x01020304]);
+/// assert_eq!(words.try_total_size(), Ok(AddressOffset(4)));
+///
+/// words.try_write_slice(Address(2), &mut [0x0A0B0C0D]).unwrap();
+/// assert_eq!(words.into_inner().as_slice()[8..12], [0x0A, 0x0B, 0x0C, 0x0D]);
+/// ```
+pub struct WordView<T, Word> {
+	dev: T,
+	order: ByteOrder,
+	_word: PhantomData<Word>,
+}
+
+impl<T, Word> WordView<T, Word> {
+	/// Wrap `dev`, unpacking words into bytes in `order`
+	pub fn new(dev: T, order: ByteOrder) -> Self {
+		WordView {
+			dev,
+			order,
+			_word: PhantomData,
+		}
+	}
+
+	/// Release the device
+	pub fn into_inner(self) -> T {
+		self.dev
+	}
+}
+
+impl<T, Word, U> MultiRead<Word, U> for WordView<T, Word>
+where
+	T: MultiRead<u8, U>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let start = index(address)?;
+		for (i, word) in buf.iter_mut().enumerate() {
+			let byte = (start + i)
+				.checked_mul(Word::BYTES)
+				.ok_or(Error::Overflow)?;
+			let mut bytes = Word::Bytes::default();
+			let address = self::address(byte)?;
+			nb::block!(self.dev.try_read_slice(address, bytes.as_mut())).map_err(Error::Device)?;
+			*word = self.order.pack(bytes);
+		}
+		Ok(())
+	}
+}
+
+impl<T, Word, U> MultiWrite<Word, U> for WordView<T, Word>
+where
+	T: MultiWrite<u8, U>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		let start = index(address)?;
+		for (i, word) in buf.iter().enumerate() {
+			let byte = (start + i)
+				.checked_mul(Word::BYTES)
+				.ok_or(Error::Overflow)?;
+			let mut bytes = self.order.unpack(*word);
+			let address = self::address(byte)?;
+			nb::block!(self.dev.try_write_slice(address, bytes.as_mut())).map_err(Error::Device)?;
+		}
+		Ok(())
+	}
+}
+
+impl<T, Word, U> ErasePage<U> for WordView<T, Word>
+where
+	T: ErasePage<U>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		self.dev
+			.try_erase_page(page)
+			.map_err(|e| e.map(Error::Device))
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		let byte = index(address)?
+			.checked_mul(Word::BYTES)
+			.ok_or(Error::Overflow)?;
+		let byte = self::address(byte)?;
+		self.dev
+			.try_erase_address(byte)
+			.map_err(|e| e.map(Error::Device))
+	}
+}
+
+impl<T, Word, U> StorageSize<Word, U> for WordView<T, Word>
+where
+	T: StorageSize<u8, U>,
+	Word: StorageWord,
+	U: TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		let start = self
+			.dev
+			.try_start_address()
+			.map_err(|e| e.map(Error::Device))?;
+		Ok(Address(shrink(AddressOffset(start.0), Word::BYTES)?.0))
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		let size = self
+			.dev
+			.try_total_size()
+			.map_err(|e| e.map(Error::Device))?;
+		Ok(shrink(size, Word::BYTES)?)
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		let byte = index(address)?
+			.checked_mul(Word::BYTES)
+			.ok_or(Error::Overflow)?;
+		let size = self
+			.dev
+			.try_page_size(self::address(byte)?)
+			.map_err(|e| e.map(Error::Device))?;
+		Ok(shrink(size, Word::BYTES)?)
+	}
+}
+
+impl<T, Word> Flush for ByteView<T, Word>
+where
+	T: Flush,
+{
+	type Error = T::Error;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.dev.try_flush()
+	}
+}
+
+impl<T, Word> ReadAccess for ByteView<T, Word>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.dev.access_pattern()
+	}
+}
+
+impl<T, Word> Flush for WordView<T, Word>
+where
+	T: Flush,
+{
+	type Error = T::Error;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.dev.try_flush()
+	}
+}
+
+impl<T, Word> ReadAccess for WordView<T, Word>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.dev.access_pattern()
+	}
+}
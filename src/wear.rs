@@ -0,0 +1,796 @@
+//! Wear
+//!
+//! Track how often the pages of a device are erased, and spread erases across
+//! them.
+
+use crate::commit::{read_latest, read_slot, write_ab, HEADER_SIZE};
+use crate::helpers;
+use crate::storage::{
+	AccessPattern, Address, AddressOffset, ErasePage, ErrorKind, Flush, MultiRead, MultiWrite,
+	Page, ReadAccess, Semantics, SingleRead, SingleWrite, StorageError, StorageSize, Wear,
+	WriteSemantics,
+};
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::ops::Add;
+
+/// Errors returned by [`WearTracking`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error<E> {
+	/// An error reported by the device
+	Device(E),
+	/// The device has more pages than are tracked, or the page isn't one of them
+	OutOfBounds,
+}
+
+impl<E: StorageError> StorageError for Error<E> {
+	fn kind(&self) -> ErrorKind {
+		match self {
+			Error::Device(e) => e.kind(),
+			Error::OutOfBounds => ErrorKind::OutOfBounds,
+		}
+	}
+}
+
+/// Counts the erases of each page in RAM, for devices without erase counters.
+///
+/// `N` is the maximum number of pages tracked. Erasing by page or by address
+/// increments the counter of the page once the device reports success. The
+/// counters start at zero and are lost with the wrapper, firmware needing
+/// them across reboots has to persist them itself. All other traits are
+/// forwarded unchanged.
+///
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, ErasePage, Page, Wear};
+/// use embedded_storage::wear::{Error, WearTracking};
+///
+/// let mut storage = WearTracking::<_, _, 4>::new(MemStorage::<64>::new(16)).unwrap();
+///
+/// storage.try_erase_page(Page(1)).unwrap();
+/// storage.try_erase_address(Address(16)).unwrap();
+/// storage.try_erase_address(Address(32)).unwrap();
+/// assert_eq!(storage.try_erase_count(Page(1)), Ok(2));
+/// assert_eq!(storage.try_erase_count(Page(2)), Ok(1));
+/// assert_eq!(storage.try_erase_count(Page(4)), Err(nb::Error::Other(Error::OutOfBounds)));
+/// assert_eq!(storage.least_worn(), Some(Page(0)));
+///
+/// // Not enough counters for every page
+/// assert!(WearTracking::<_, _, 2>::new(MemStorage::<64>::new(16)).is_err());
+/// ```
+pub struct WearTracking<T, U, const N: usize> {
+	inner: T,
+	starts: [Address<U>; N],
+	counts: [u32; N],
+	pages: usize,
+	end: Address<U>,
+}
+
+impl<T, U, const N: usize> WearTracking<T, U, N>
+where
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+{
+	/// Wrap `inner`, querying the start address of each of its pages.
+	///
+	/// Returns [`Error::OutOfBounds`] if the device has more than `N` pages.
+	pub fn new<Word>(mut inner: T) -> Result<Self, Error<T::Error>>
+	where
+		T: StorageSize<Word, U>,
+	{
+		let start = nb::block!(inner.try_start_address()).map_err(Error::Device)?;
+		let size = nb::block!(inner.try_total_size()).map_err(Error::Device)?;
+		let mut starts = [start; N];
+		let mut pages = 0;
+		let mut address = start;
+		let end = &start + &size;
+		while address < end {
+			let page_size = nb::block!(inner.try_page_size(address)).map_err(Error::Device)?;
+			if page_size.0 == U::from(0) {
+				// A zero sized page would never advance
+				break;
+			}
+			*starts.get_mut(pages).ok_or(Error::OutOfBounds)? = address;
+			pages += 1;
+			address += page_size;
+		}
+		Ok(WearTracking {
+			inner,
+			starts,
+			counts: [0; N],
+			pages,
+			end,
+		})
+	}
+
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.inner
+	}
+
+	/// The page with the fewest erases, the lowest numbered one if several are tied
+	pub fn least_worn(&self) -> Option<Page<U>>
+	where
+		U: TryFrom<usize>,
+	{
+		let (index, _) = self.counts[..self.pages]
+			.iter()
+			.enumerate()
+			.min_by_key(|(_, count)| **count)?;
+		U::try_from(index).ok().map(Page)
+	}
+
+	/// The index of the page containing `address`
+	fn page_of(&self, address: Address<U>) -> Option<usize> {
+		if address >= self.end {
+			return None;
+		}
+		self.starts[..self.pages]
+			.iter()
+			.rposition(|start| *start <= address)
+	}
+
+	/// The index of `page`, if it is tracked
+	fn index(&self, page: Page<U>) -> Option<usize>
+	where
+		usize: TryFrom<U>,
+	{
+		usize::try_from(page.0).ok().filter(|i| *i < self.pages)
+	}
+}
+
+impl<T, U, const N: usize> Wear<U> for WearTracking<T, U, N>
+where
+	T: ErasePage<U>,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = Error<T::Error>;
+
+	fn try_erase_count(&mut self, page: Page<U>) -> nb::Result<u32, Self::Error> {
+		let index = self.index(page).ok_or(Error::OutOfBounds)?;
+		Ok(self.counts[index])
+	}
+}
+
+impl<T, U, const N: usize> ErasePage<U> for WearTracking<T, U, N>
+where
+	T: ErasePage<U>,
+	U: Add<U, Output = U> + Ord + From<u8> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = T::Error;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		let index = self.index(page);
+		self.inner.try_erase_page(page)?;
+		if let Some(index) = index {
+			self.counts[index] = self.counts[index].saturating_add(1);
+		}
+		Ok(())
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		let index = self.page_of(address);
+		self.inner.try_erase_address(address)?;
+		if let Some(index) = index {
+			self.counts[index] = self.counts[index].saturating_add(1);
+		}
+		Ok(())
+	}
+}
+
+impl<T, Word, U, const N: usize> SingleRead<Word, U> for WearTracking<T, U, N>
+where
+	T: SingleRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read(&mut self, address: Address<U>) -> nb::Result<Word, Self::Error> {
+		self.inner.try_read(address)
+	}
+}
+
+impl<T, Word, U, const N: usize> SingleWrite<Word, U> for WearTracking<T, U, N>
+where
+	T: SingleWrite<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_write(&mut self, address: Address<U>, word: Word) -> nb::Result<(), Self::Error> {
+		self.inner.try_write(address, word)
+	}
+}
+
+impl<T, Word, U, const N: usize> MultiRead<Word, U> for WearTracking<T, U, N>
+where
+	T: MultiRead<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.inner.try_read_slice(address, buf)
+	}
+
+	fn try_read_partial(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<usize, Self::Error> {
+		self.inner.try_read_partial(address, buf)
+	}
+}
+
+impl<T, Word, U, const N: usize> MultiWrite<Word, U> for WearTracking<T, U, N>
+where
+	T: MultiWrite<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [Word],
+	) -> nb::Result<(), Self::Error> {
+		self.inner.try_write_slice(address, buf)
+	}
+}
+
+impl<T, Word, U, const N: usize> StorageSize<Word, U> for WearTracking<T, U, N>
+where
+	T: StorageSize<Word, U>,
+{
+	type Error = T::Error;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		self.inner.try_start_address()
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner.try_total_size()
+	}
+
+	fn try_page_size(&mut self, address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		self.inner.try_page_size(address)
+	}
+}
+
+impl<T, U, const N: usize> Flush for WearTracking<T, U, N>
+where
+	T: Flush,
+{
+	type Error = T::Error;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.inner.try_flush()
+	}
+}
+
+impl<T, U, const N: usize> WriteSemantics for WearTracking<T, U, N>
+where
+	T: WriteSemantics,
+{
+	fn write_semantics(&self) -> Semantics {
+		self.inner.write_semantics()
+	}
+}
+
+impl<T, U, const N: usize> ReadAccess for WearTracking<T, U, N>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.inner.access_pattern()
+	}
+}
+
+/// Size of a mapping update appended to a metadata page, in bytes
+const RECORD_SIZE: usize = 5;
+
+/// Maps logical pages to physical pages, moving a logical page to the least
+/// erased free physical page whenever it is erased.
+///
+/// The first two pages of the device hold the mapping and the erase count of
+/// every physical page, so they survive power loss and reboots. The remaining
+/// pages are physical pages, at least one more than the number of logical
+/// pages so there is a free page to move to. All pages must have the same
+/// size, and erased bytes read as `erase_byte`.
+///
+/// A snapshot of the whole mapping is written with [`write_ab`] to one of
+/// the metadata pages. Every erase then appends a 5 byte record to that page,
+/// the logical and physical page numbers followed by a commit byte of
+/// `!erase_byte` written last, so a record torn by power loss is ignored. Only once the page is
+/// full is a new snapshot written, erasing the other metadata page. With `R`
+/// records fitting after the snapshot, each metadata page is erased once
+/// every `2 * (R + 1)` logical erases, while each physical page is erased
+/// about once every `physical` logical erases. Pages large enough that `R`
+/// exceeds half the number of physical pages keep the metadata pages less
+/// worn than the data pages.
+///
+/// Addresses are relative to the start of the logical pages, and accesses
+/// can't cross a logical page. The mapping is kept in `meta`, which must be
+/// at least [`WearLeveled::meta_len`] bytes. [`Wear`] reports the erase
+/// count of a physical page.
+///
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{Address, ErasePage, MultiRead, MultiWrite, Page, Wear};
+/// use embedded_storage::wear::WearLeveled;
+///
+/// // 2 metadata pages and 4 physical pages for 1 logical page
+/// let mut meta = [0u8; 18];
+/// let device = MemStorage::<384>::new(64);
+/// let mut storage = WearLeveled::new(device, 1, 0xFF, &mut meta).unwrap();
+///
+/// for i in 0..4 {
+///     storage.try_erase_page(Page(0)).unwrap();
+///     storage.try_write_slice(Address(0), &mut [i]).unwrap();
+/// }
+/// for page in 0..4 {
+///     assert_eq!(storage.try_erase_count(Page(page)), Ok(1));
+/// }
+///
+/// // Each write landed on a different physical page
+/// let device = storage.into_inner();
+/// for i in 0..4 {
+///     assert_eq!(device.as_slice()[(2 + i) * 64], i as u8);
+/// }
+///
+/// // The mapping is restored after a reboot
+/// let mut meta = [0u8; 18];
+/// let mut storage = WearLeveled::new(device, 1, 0xFF, &mut meta).unwrap();
+/// let mut buf = [0u8];
+/// storage.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [3]);
+/// ```
+///
+/// With 6 records fitting after the snapshot, the metadata pages are erased
+/// less often than the physical pages:
+///
+/// ```
+/// use embedded_storage::mem::MemStorage;
+/// use embedded_storage::storage::{ErasePage, Page, Wear};
+/// use embedded_storage::wear::{WearLeveled, WearTracking};
+///
+/// let device = WearTracking::<_, _, 6>::new(MemStorage::<384>::new(64)).unwrap();
+/// let mut meta = [0u8; 18];
+/// let mut storage = WearLeveled::new(device, 1, 0xFF, &mut meta).unwrap();
+/// for _ in 0..40 {
+///     storage.try_erase_page(Page(0)).unwrap();
+/// }
+///
+/// let mut device = storage.into_inner();
+/// assert_eq!(device.try_erase_count(Page(0)), Ok(3));
+/// assert_eq!(device.try_erase_count(Page(1)), Ok(3));
+/// for page in 2..6 {
+///     assert_eq!(device.try_erase_count(Page(page)), Ok(10));
+/// }
+/// ```
+///
+/// On a device erasing to `0x00`, blank records aren't mistaken for
+/// committed ones:
+///
+/// ```
+/// use embedded_storage::mem::MemStorageBuilder;
+/// use embedded_storage::storage::{Address, ErasePage, MultiRead, MultiWrite, Page, Wear};
+/// use embedded_storage::wear::WearLeveled;
+///
+/// let device = MemStorageBuilder::<384>::new().page_size(64).erase_byte(0x00).build();
+/// let mut meta = [0u8; 18];
+/// let mut storage = WearLeveled::new(device, 1, 0x00, &mut meta).unwrap();
+/// for i in 0..3 {
+///     storage.try_erase_page(Page(0)).unwrap();
+///     storage.try_write_slice(Address(0), &mut [i + 1]).unwrap();
+/// }
+///
+/// let mut meta = [0u8; 18];
+/// let mut storage = WearLeveled::new(storage.into_inner(), 1, 0x00, &mut meta).unwrap();
+/// let mut buf = [0u8];
+/// storage.try_read_slice(Address(0), &mut buf).unwrap();
+/// assert_eq!(buf, [3]);
+/// assert_eq!(storage.try_erase_count(Page(0)), Ok(1));
+/// assert_eq!(storage.try_erase_count(Page(3)), Ok(0));
+/// ```
+pub struct WearLeveled<'a, T, U> {
+	inner: T,
+	meta: &'a mut [u8],
+	logical: usize,
+	physical: usize,
+	start: usize,
+	page_size: usize,
+	erase_byte: u8,
+	/// The metadata page holding the latest snapshot, and the records appended to it
+	slot: usize,
+	records: usize,
+	_address: PhantomData<U>,
+}
+
+impl<'a, T, U, E> WearLeveled<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	/// Wrap `inner`, providing `logical` pages, with erased bytes reading as `erase_byte`.
+	///
+	/// Loads the mapping from the metadata pages, or starts a new one if
+	/// neither holds a valid mapping. Returns [`helpers::Error::OutOfBounds`]
+	/// if there aren't more physical pages than logical pages, or if the stored
+	/// mapping doesn't match them, and [`helpers::Error::BufferTooSmall`] if
+	/// `meta` is too small.
+	pub fn new(
+		mut inner: T,
+		logical: usize,
+		erase_byte: u8,
+		meta: &'a mut [u8],
+	) -> Result<Self, helpers::Error<E>> {
+		let start = nb::block!(inner.try_start_address())?;
+		let start = usize::try_from(start.0).map_err(|_| helpers::Error::Overflow)?;
+		let total = nb::block!(inner.try_total_size())?;
+		let total = helpers::len_from_offset(total).ok_or(helpers::Error::Overflow)?;
+		let page_size = nb::block!(inner.try_page_size(at(start)?))?;
+		let page_size = helpers::len_from_offset(page_size).ok_or(helpers::Error::Overflow)?;
+		if page_size == 0 {
+			// A zero sized page would never advance
+			return Err(helpers::Error::OutOfBounds);
+		}
+		let physical = (total / page_size).saturating_sub(2);
+		if logical >= physical || physical > usize::from(u16::MAX) {
+			return Err(helpers::Error::OutOfBounds);
+		}
+		let len = Self::meta_len(logical, physical);
+		if meta.len() < len {
+			return Err(helpers::Error::BufferTooSmall);
+		}
+		if HEADER_SIZE + len > page_size {
+			return Err(helpers::Error::CrossesPage);
+		}
+		let mut storage = WearLeveled {
+			inner,
+			meta,
+			logical,
+			physical,
+			start,
+			page_size,
+			erase_byte,
+			slot: 0,
+			records: 0,
+			_address: PhantomData,
+		};
+		let (a, b) = storage.slots()?;
+		match read_latest(&mut storage.inner, a, b, &mut storage.meta[..])? {
+			Some(stored) if stored == len => {
+				storage.slot = storage.latest_slot()?;
+				storage.replay()?;
+				if !storage.is_valid() {
+					return Err(helpers::Error::OutOfBounds);
+				}
+			}
+			Some(_) => return Err(helpers::Error::OutOfBounds),
+			None => {
+				storage.meta[..len].fill(0);
+				for page in 0..logical {
+					storage.set_mapped(page, page);
+				}
+				storage.snapshot()?;
+			}
+		}
+		Ok(storage)
+	}
+
+	/// The number of bytes of `meta` needed for `logical` and `physical` pages
+	pub fn meta_len(logical: usize, physical: usize) -> usize {
+		logical * 2 + physical * 4
+	}
+
+	/// Release the wrapped device
+	pub fn into_inner(self) -> T {
+		self.inner
+	}
+
+	/// The addresses of the two metadata pages
+	fn slots(&self) -> Result<(Address<U>, Address<U>), helpers::Error<E>> {
+		Ok((at(self.start)?, at(self.start + self.page_size)?))
+	}
+
+	/// The physical page of a logical page
+	fn mapped(&self, page: usize) -> usize {
+		usize::from(u16::from_le_bytes([
+			self.meta[page * 2],
+			self.meta[page * 2 + 1],
+		]))
+	}
+
+	fn set_mapped(&mut self, page: usize, physical: usize) {
+		// `new` checked that every physical page fits in a u16
+		let bytes = (physical as u16).to_le_bytes();
+		self.meta[page * 2..page * 2 + 2].copy_from_slice(&bytes);
+	}
+
+	/// The erase count of a physical page
+	fn count(&self, physical: usize) -> u32 {
+		let offset = self.logical * 2 + physical * 4;
+		let mut bytes = [0; 4];
+		bytes.copy_from_slice(&self.meta[offset..offset + 4]);
+		u32::from_le_bytes(bytes)
+	}
+
+	fn set_count(&mut self, physical: usize, count: u32) {
+		let offset = self.logical * 2 + physical * 4;
+		self.meta[offset..offset + 4].copy_from_slice(&count.to_le_bytes());
+	}
+
+	/// Check that every logical page maps to a different physical page
+	fn is_valid(&self) -> bool {
+		(0..self.logical).all(|page| {
+			let physical = self.mapped(page);
+			physical < self.physical && (0..page).all(|other| self.mapped(other) != physical)
+		})
+	}
+
+	/// The address of `len` words from the logical `address`
+	fn translate(&self, address: Address<U>, len: usize) -> Result<Address<U>, helpers::Error<E>> {
+		let address = usize::try_from(address.0).map_err(|_| helpers::Error::OutOfBounds)?;
+		let (page, offset) = (address / self.page_size, address % self.page_size);
+		if page >= self.logical {
+			return Err(helpers::Error::OutOfBounds);
+		}
+		if len > self.page_size - offset {
+			return Err(helpers::Error::CrossesPage);
+		}
+		at(self.physical_start(self.mapped(page))? + offset)
+	}
+
+	/// The start of a physical page, as an offset from the start of the device
+	fn physical_start(&self, physical: usize) -> Result<usize, helpers::Error<E>> {
+		(physical + 2)
+			.checked_mul(self.page_size)
+			.and_then(|offset| offset.checked_add(self.start))
+			.ok_or(helpers::Error::Overflow)
+	}
+
+	/// Move a logical page to the least erased free physical page and erase it
+	fn erase(&mut self, page: usize) -> Result<(), helpers::Error<E>> {
+		let current = self.mapped(page);
+		let mut target = current;
+		for physical in 0..self.physical {
+			let free = (0..self.logical).all(|other| self.mapped(other) != physical);
+			if free && self.count(physical) < self.count(target) {
+				target = physical;
+			}
+		}
+		let address = at(self.physical_start(target)?)?;
+		nb::block!(self.inner.try_erase_address(address))?;
+		self.set_count(target, self.count(target).saturating_add(1));
+		self.set_mapped(page, target);
+		if self.records == self.capacity() {
+			return self.snapshot();
+		}
+
+		// `new` checked that every page number fits in a u16
+		let mut record = [!self.erase_byte; RECORD_SIZE];
+		record[..2].copy_from_slice(&(page as u16).to_le_bytes());
+		record[2..4].copy_from_slice(&(target as u16).to_le_bytes());
+		let address = self.record_address(self.records)?;
+		self.records += 1;
+		nb::block!(self.inner.try_write_slice(at(address)?, &mut record[..4]))?;
+		nb::block!(self
+			.inner
+			.try_write_slice(at(address + 4)?, &mut record[4..]))?;
+		Ok(())
+	}
+
+	/// Write the whole mapping to the other metadata page, starting a new log
+	fn snapshot(&mut self) -> Result<(), helpers::Error<E>> {
+		let (a, b) = self.slots()?;
+		let len = Self::meta_len(self.logical, self.physical);
+		write_ab(&mut self.inner, a, b, &mut self.meta[..len])?;
+		self.slot = self.latest_slot()?;
+		self.records = 0;
+		Ok(())
+	}
+
+	/// Apply the records appended to the snapshot in the current metadata page
+	fn replay(&mut self) -> Result<(), helpers::Error<E>> {
+		self.records = 0;
+		while self.records < self.capacity() {
+			let address = self.record_address(self.records)?;
+			let mut record = [0; RECORD_SIZE];
+			nb::block!(self.inner.try_read_slice(at(address)?, &mut record))?;
+			if record == [self.erase_byte; RECORD_SIZE] {
+				break;
+			}
+			self.records += 1;
+			let page = usize::from(u16::from_le_bytes([record[0], record[1]]));
+			let target = usize::from(u16::from_le_bytes([record[2], record[3]]));
+			// Records without their commit byte were torn by power loss
+			if record[4] == !self.erase_byte && page < self.logical && target < self.physical {
+				self.set_count(target, self.count(target).saturating_add(1));
+				self.set_mapped(page, target);
+			}
+		}
+		Ok(())
+	}
+
+	/// The offset from the start of the device of the metadata page holding the latest snapshot
+	fn latest_slot(&mut self) -> Result<usize, helpers::Error<E>> {
+		let (a, b) = self.slots()?;
+		let a_seq = read_slot(&mut self.inner, a)?.map(|(seq, _)| seq);
+		let b_seq = read_slot(&mut self.inner, b)?.map(|(seq, _)| seq);
+		Ok(match (a_seq, b_seq) {
+			(Some(a), Some(b)) if a >= b => self.start,
+			(_, Some(_)) => self.start + self.page_size,
+			_ => self.start,
+		})
+	}
+
+	/// The number of records fitting after the snapshot in a metadata page
+	fn capacity(&self) -> usize {
+		let used = HEADER_SIZE + Self::meta_len(self.logical, self.physical);
+		(self.page_size - used) / RECORD_SIZE
+	}
+
+	/// The offset from the start of the device of record `index`
+	fn record_address(&self, index: usize) -> Result<usize, helpers::Error<E>> {
+		let offset = HEADER_SIZE + Self::meta_len(self.logical, self.physical);
+		index
+			.checked_mul(RECORD_SIZE)
+			.and_then(|records| (self.slot + offset).checked_add(records))
+			.ok_or(helpers::Error::Overflow)
+	}
+}
+
+fn at<U: TryFrom<usize>, E>(address: usize) -> Result<Address<U>, helpers::Error<E>> {
+	U::try_from(address)
+		.map(Address)
+		.map_err(|_| helpers::Error::Overflow)
+}
+
+impl<'a, T, U, E> MultiRead<u8, U> for WearLeveled<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = helpers::Error<E>;
+
+	fn try_read_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [u8],
+	) -> nb::Result<(), Self::Error> {
+		let address = self.translate(address, buf.len())?;
+		self.inner
+			.try_read_slice(address, buf)
+			.map_err(|e| e.map(helpers::Error::Device))
+	}
+}
+
+impl<'a, T, U, E> MultiWrite<u8, U> for WearLeveled<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = helpers::Error<E>;
+
+	fn try_write_slice(
+		&mut self,
+		address: Address<U>,
+		buf: &mut [u8],
+	) -> nb::Result<(), Self::Error> {
+		let address = self.translate(address, buf.len())?;
+		self.inner
+			.try_write_slice(address, buf)
+			.map_err(|e| e.map(helpers::Error::Device))
+	}
+}
+
+impl<'a, T, U, E> ErasePage<U> for WearLeveled<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = helpers::Error<E>;
+
+	fn try_erase_page(&mut self, page: Page<U>) -> nb::Result<(), Self::Error> {
+		let page = usize::try_from(page.0).map_err(|_| helpers::Error::OutOfBounds)?;
+		if page >= self.logical {
+			return Err(nb::Error::Other(helpers::Error::OutOfBounds));
+		}
+		Ok(self.erase(page)?)
+	}
+
+	fn try_erase_address(&mut self, address: Address<U>) -> nb::Result<(), Self::Error> {
+		let address = usize::try_from(address.0).map_err(|_| helpers::Error::OutOfBounds)?;
+		let page = address / self.page_size;
+		if page >= self.logical {
+			return Err(nb::Error::Other(helpers::Error::OutOfBounds));
+		}
+		Ok(self.erase(page)?)
+	}
+}
+
+impl<'a, T, U, E> StorageSize<u8, U> for WearLeveled<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = helpers::Error<E>;
+
+	fn try_start_address(&mut self) -> nb::Result<Address<U>, Self::Error> {
+		Ok(at(0)?)
+	}
+
+	fn try_total_size(&mut self) -> nb::Result<AddressOffset<U>, Self::Error> {
+		let total = self
+			.logical
+			.checked_mul(self.page_size)
+			.ok_or(helpers::Error::Overflow)?;
+		Ok(helpers::offset_from_len(total).ok_or(helpers::Error::Overflow)?)
+	}
+
+	fn try_page_size(&mut self, _address: Address<U>) -> nb::Result<AddressOffset<U>, Self::Error> {
+		Ok(helpers::offset_from_len(self.page_size).ok_or(helpers::Error::Overflow)?)
+	}
+}
+
+impl<'a, T, U, E> Wear<U> for WearLeveled<'a, T, U>
+where
+	T: MultiRead<u8, U, Error = E>
+		+ MultiWrite<u8, U, Error = E>
+		+ ErasePage<U, Error = E>
+		+ StorageSize<u8, U, Error = E>,
+	U: Add<U, Output = U> + TryFrom<usize> + Copy,
+	usize: TryFrom<U>,
+{
+	type Error = helpers::Error<E>;
+
+	/// The erase count of the physical page
+	fn try_erase_count(&mut self, page: Page<U>) -> nb::Result<u32, Self::Error> {
+		match usize::try_from(page.0) {
+			Ok(page) if page < self.physical => Ok(self.count(page)),
+			_ => Err(nb::Error::Other(helpers::Error::OutOfBounds)),
+		}
+	}
+}
+
+impl<'a, T, U> Flush for WearLeveled<'a, T, U>
+where
+	T: Flush,
+{
+	type Error = T::Error;
+
+	fn try_flush(&mut self) -> nb::Result<(), Self::Error> {
+		self.inner.try_flush()
+	}
+}
+
+impl<'a, T, U> ReadAccess for WearLeveled<'a, T, U>
+where
+	T: ReadAccess,
+{
+	fn access_pattern(&self) -> AccessPattern {
+		self.inner.access_pattern()
+	}
+}